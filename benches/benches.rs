@@ -11,7 +11,7 @@
 extern crate noise;
 extern crate test;
 
-use noise::{NoiseFn, OpenSimplex, Perlin, SuperSimplex, Value, Worley};
+use noise::{NoiseFn, OpenSimplex, Perlin, RangeFunction, ReturnType, SuperSimplex, Value, Worley};
 use test::{Bencher, black_box};
 
 #[bench]
@@ -82,19 +82,19 @@ fn bench_value4(bencher: &mut Bencher) {
 
 #[bench]
 fn bench_worley2_range(bencher: &mut Bencher) {
-    let worley = Worley::new().enable_range(true);
+    let worley = Worley::new().set_return_type(ReturnType::F1);
     bencher.iter(|| worley.get(black_box([42.0f64, 37.0])));
 }
 
 #[bench]
 fn bench_worley3_range(bencher: &mut Bencher) {
-    let worley = Worley::new().enable_range(true);
+    let worley = Worley::new().set_return_type(ReturnType::F1);
     bencher.iter(|| worley.get(black_box([42.0f64, 37.0, 26.0])));
 }
 
 #[bench]
 fn bench_worley4_range(bencher: &mut Bencher) {
-    let worley = Worley::new().enable_range(true);
+    let worley = Worley::new().set_return_type(ReturnType::F1);
     bencher.iter(|| worley.get(black_box([42.0f64, 37.0, 26.0, 128.0])));
 }
 
@@ -116,6 +116,60 @@ fn bench_worley4_value(bencher: &mut Bencher) {
     bencher.iter(|| worley.get(black_box([42.0f64, 37.0, 26.0, 128.0])));
 }
 
+#[bench]
+fn bench_worley2_manhattan(bencher: &mut Bencher) {
+    let worley = Worley::new().set_range_function(RangeFunction::Manhattan);
+    bencher.iter(|| worley.get(black_box([42.0f64, 37.0])));
+}
+
+#[bench]
+fn bench_worley3_manhattan(bencher: &mut Bencher) {
+    let worley = Worley::new().set_range_function(RangeFunction::Manhattan);
+    bencher.iter(|| worley.get(black_box([42.0f64, 37.0, 26.0])));
+}
+
+#[bench]
+fn bench_worley4_manhattan(bencher: &mut Bencher) {
+    let worley = Worley::new().set_range_function(RangeFunction::Manhattan);
+    bencher.iter(|| worley.get(black_box([42.0f64, 37.0, 26.0, 128.0])));
+}
+
+#[bench]
+fn bench_worley2_quadratic(bencher: &mut Bencher) {
+    let worley = Worley::new().set_range_function(RangeFunction::Quadratic);
+    bencher.iter(|| worley.get(black_box([42.0f64, 37.0])));
+}
+
+#[bench]
+fn bench_worley3_quadratic(bencher: &mut Bencher) {
+    let worley = Worley::new().set_range_function(RangeFunction::Quadratic);
+    bencher.iter(|| worley.get(black_box([42.0f64, 37.0, 26.0])));
+}
+
+#[bench]
+fn bench_worley4_quadratic(bencher: &mut Bencher) {
+    let worley = Worley::new().set_range_function(RangeFunction::Quadratic);
+    bencher.iter(|| worley.get(black_box([42.0f64, 37.0, 26.0, 128.0])));
+}
+
+#[bench]
+fn bench_worley2_f2_minus_f1(bencher: &mut Bencher) {
+    let worley = Worley::new().set_return_type(ReturnType::F2MinusF1);
+    bencher.iter(|| worley.get(black_box([42.0f64, 37.0])));
+}
+
+#[bench]
+fn bench_worley3_f2_minus_f1(bencher: &mut Bencher) {
+    let worley = Worley::new().set_return_type(ReturnType::F2MinusF1);
+    bencher.iter(|| worley.get(black_box([42.0f64, 37.0, 26.0])));
+}
+
+#[bench]
+fn bench_worley4_f2_minus_f1(bencher: &mut Bencher) {
+    let worley = Worley::new().set_return_type(ReturnType::F2MinusF1);
+    bencher.iter(|| worley.get(black_box([42.0f64, 37.0, 26.0, 128.0])));
+}
+
 #[bench]
 fn bench_open_simplex2_64x64(bencher: &mut Bencher) {
     let open_simplex = OpenSimplex::new();
@@ -228,7 +282,7 @@ fn bench_value4_64x64(bencher: &mut Bencher) {
 
 #[bench]
 fn bench_worley2_range_64x64(bencher: &mut Bencher) {
-    let worley = Worley::new().enable_range(true);
+    let worley = Worley::new().set_return_type(ReturnType::F1);
     bencher.iter(|| for y in 0i8..64 {
                      for x in 0i8..64 {
                          black_box(worley.get([x as f64, y as f64]));
@@ -238,7 +292,7 @@ fn bench_worley2_range_64x64(bencher: &mut Bencher) {
 
 #[bench]
 fn bench_worley3_range_64x64(bencher: &mut Bencher) {
-    let worley = Worley::new().enable_range(true);
+    let worley = Worley::new().set_return_type(ReturnType::F1);
     bencher.iter(|| for y in 0i8..64 {
                      for x in 0i8..64 {
                          black_box(worley.get([x as f64, y as f64, x as f64]));
@@ -248,7 +302,7 @@ fn bench_worley3_range_64x64(bencher: &mut Bencher) {
 
 #[bench]
 fn bench_worley4_range_64x64(bencher: &mut Bencher) {
-    let worley = Worley::new().enable_range(true);
+    let worley = Worley::new().set_return_type(ReturnType::F1);
     bencher.iter(|| for y in 0i8..64 {
                      for x in 0i8..64 {
                          black_box(worley.get([x as f64, y as f64, x as f64, y as f64]));
@@ -285,3 +339,248 @@ fn bench_worley4_value_64x64(bencher: &mut Bencher) {
                      }
                  });
 }
+
+// The points above lie on the lattice, where the nearest seed points are quicker to find than
+// in between, so these sample a grid with fractional coordinates.
+#[bench]
+fn bench_worley2_value_fractional_64x64(bencher: &mut Bencher) {
+    let worley = Worley::new();
+    let points = grid2_64x64();
+    bencher.iter(|| {
+        for &point in &points {
+            black_box(worley.get(point));
+        }
+    });
+}
+
+#[bench]
+fn bench_worley3_value_fractional_64x64(bencher: &mut Bencher) {
+    let worley = Worley::new();
+    let points = grid3_64x64();
+    bencher.iter(|| {
+        for &point in &points {
+            black_box(worley.get(point));
+        }
+    });
+}
+
+#[bench]
+fn bench_worley4_value_fractional_64x64(bencher: &mut Bencher) {
+    let worley = Worley::new();
+    let points = grid4_64x64();
+    bencher.iter(|| {
+        for &point in &points {
+            black_box(worley.get(point));
+        }
+    });
+}
+
+#[bench]
+fn bench_worley2_f2_minus_f1_fractional_64x64(bencher: &mut Bencher) {
+    let worley = Worley::new().set_return_type(ReturnType::F2MinusF1);
+    let points = grid2_64x64();
+    bencher.iter(|| {
+        for &point in &points {
+            black_box(worley.get(point));
+        }
+    });
+}
+
+#[bench]
+fn bench_worley3_f2_minus_f1_fractional_64x64(bencher: &mut Bencher) {
+    let worley = Worley::new().set_return_type(ReturnType::F2MinusF1);
+    let points = grid3_64x64();
+    bencher.iter(|| {
+        for &point in &points {
+            black_box(worley.get(point));
+        }
+    });
+}
+
+#[bench]
+fn bench_worley4_f2_minus_f1_fractional_64x64(bencher: &mut Bencher) {
+    let worley = Worley::new().set_return_type(ReturnType::F2MinusF1);
+    let points = grid4_64x64();
+    bencher.iter(|| {
+        for &point in &points {
+            black_box(worley.get(point));
+        }
+    });
+}
+
+fn grid2_64x64() -> Vec<[f64; 2]> {
+    let mut points = Vec::with_capacity(64 * 64);
+    for y in 0i8..64 {
+        for x in 0i8..64 {
+            points.push([x as f64 * 0.1, y as f64 * 0.1]);
+        }
+    }
+    points
+}
+
+fn grid3_64x64() -> Vec<[f64; 3]> {
+    let mut points = Vec::with_capacity(64 * 64);
+    for y in 0i8..64 {
+        for x in 0i8..64 {
+            points.push([x as f64 * 0.1, y as f64 * 0.1, 0.5]);
+        }
+    }
+    points
+}
+
+fn grid4_64x64() -> Vec<[f64; 4]> {
+    let mut points = Vec::with_capacity(64 * 64);
+    for y in 0i8..64 {
+        for x in 0i8..64 {
+            points.push([x as f64 * 0.1, y as f64 * 0.1, 0.5, 0.25]);
+        }
+    }
+    points
+}
+
+#[bench]
+fn bench_open_simplex2_get_many_64x64(bencher: &mut Bencher) {
+    let open_simplex = OpenSimplex::new();
+    let points = grid2_64x64();
+    let mut out = vec![0.0; points.len()];
+    bencher.iter(|| open_simplex.get_many(&points, black_box(&mut out)));
+}
+
+// Calls `get` on the same points as `get_many`, for comparison.
+#[bench]
+fn bench_open_simplex2_get_loop_64x64(bencher: &mut Bencher) {
+    let open_simplex = OpenSimplex::new();
+    let points = grid2_64x64();
+    let mut out = vec![0.0; points.len()];
+    bencher.iter(|| {
+        for (&point, out) in points.iter().zip(black_box(&mut out).iter_mut()) {
+            *out = open_simplex.get(point);
+        }
+    });
+}
+
+#[bench]
+fn bench_open_simplex3_get_many_64x64(bencher: &mut Bencher) {
+    let open_simplex = OpenSimplex::new();
+    let points = grid3_64x64();
+    let mut out = vec![0.0; points.len()];
+    bencher.iter(|| open_simplex.get_many(&points, black_box(&mut out)));
+}
+
+// Calls `get` on the same points as `get_many`, for comparison.
+#[bench]
+fn bench_open_simplex3_get_loop_64x64(bencher: &mut Bencher) {
+    let open_simplex = OpenSimplex::new();
+    let points = grid3_64x64();
+    let mut out = vec![0.0; points.len()];
+    bencher.iter(|| {
+        for (&point, out) in points.iter().zip(black_box(&mut out).iter_mut()) {
+            *out = open_simplex.get(point);
+        }
+    });
+}
+
+#[bench]
+fn bench_perlin2_get_many_64x64(bencher: &mut Bencher) {
+    let perlin = Perlin::new();
+    let points = grid2_64x64();
+    let mut out = vec![0.0; points.len()];
+    bencher.iter(|| perlin.get_many(&points, black_box(&mut out)));
+}
+
+// Calls `get` on the same points as `get_many`, for comparison.
+#[bench]
+fn bench_perlin2_get_loop_64x64(bencher: &mut Bencher) {
+    let perlin = Perlin::new();
+    let points = grid2_64x64();
+    let mut out = vec![0.0; points.len()];
+    bencher.iter(|| {
+        for (&point, out) in points.iter().zip(black_box(&mut out).iter_mut()) {
+            *out = perlin.get(point);
+        }
+    });
+}
+
+#[bench]
+fn bench_perlin3_get_many_64x64(bencher: &mut Bencher) {
+    let perlin = Perlin::new();
+    let points = grid3_64x64();
+    let mut out = vec![0.0; points.len()];
+    bencher.iter(|| perlin.get_many(&points, black_box(&mut out)));
+}
+
+// Calls `get` on the same points as `get_many`, for comparison.
+#[bench]
+fn bench_perlin3_get_loop_64x64(bencher: &mut Bencher) {
+    let perlin = Perlin::new();
+    let points = grid3_64x64();
+    let mut out = vec![0.0; points.len()];
+    bencher.iter(|| {
+        for (&point, out) in points.iter().zip(black_box(&mut out).iter_mut()) {
+            *out = perlin.get(point);
+        }
+    });
+}
+
+#[bench]
+fn bench_value2_get_many_64x64(bencher: &mut Bencher) {
+    let value = Value::new();
+    let points = grid2_64x64();
+    let mut out = vec![0.0; points.len()];
+    bencher.iter(|| value.get_many(&points, black_box(&mut out)));
+}
+
+// Calls `get` on the same points as `get_many`, for comparison.
+#[bench]
+fn bench_value2_get_loop_64x64(bencher: &mut Bencher) {
+    let value = Value::new();
+    let points = grid2_64x64();
+    let mut out = vec![0.0; points.len()];
+    bencher.iter(|| {
+        for (&point, out) in points.iter().zip(black_box(&mut out).iter_mut()) {
+            *out = value.get(point);
+        }
+    });
+}
+
+#[bench]
+fn bench_value3_get_many_64x64(bencher: &mut Bencher) {
+    let value = Value::new();
+    let points = grid3_64x64();
+    let mut out = vec![0.0; points.len()];
+    bencher.iter(|| value.get_many(&points, black_box(&mut out)));
+}
+
+// Calls `get` on the same points as `get_many`, for comparison.
+#[bench]
+fn bench_value3_get_loop_64x64(bencher: &mut Bencher) {
+    let value = Value::new();
+    let points = grid3_64x64();
+    let mut out = vec![0.0; points.len()];
+    bencher.iter(|| {
+        for (&point, out) in points.iter().zip(black_box(&mut out).iter_mut()) {
+            *out = value.get(point);
+        }
+    });
+}
+
+#[bench]
+fn bench_value4_get_many_64x64(bencher: &mut Bencher) {
+    let value = Value::new();
+    let points = grid4_64x64();
+    let mut out = vec![0.0; points.len()];
+    bencher.iter(|| value.get_many(&points, black_box(&mut out)));
+}
+
+// Calls `get` on the same points as `get_many`, for comparison.
+#[bench]
+fn bench_value4_get_loop_64x64(bencher: &mut Bencher) {
+    let value = Value::new();
+    let points = grid4_64x64();
+    let mut out = vec![0.0; points.len()];
+    bencher.iter(|| {
+        for (&point, out) in points.iter().zip(black_box(&mut out).iter_mut()) {
+            *out = value.get(point);
+        }
+    });
+}
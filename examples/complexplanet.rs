@@ -77,7 +77,7 @@ mod debug;
 #[allow(non_snake_case)]
 fn main() {
     /// Planet seed. Change this to generate a different planet.
-    const CURRENT_SEED: u32 = 0;
+    const CURRENT_SEED: u64 = 0;
 
     /// Frequency of the planet's continents. Higher frequency produces
     /// smaller, more numerous continents. This value is measured in radians.
@@ -876,7 +876,7 @@ fn main() {
         .set_seed(CURRENT_SEED + 81)
         .set_frequency(16183.25)
         .set_displacement(0.0)
-        .enable_range(true);
+        .set_return_type(ReturnType::F1);
 
     // 4: [Scaled-dune-detail module]: This scale/bias module shrinks the dune
     // details by a large amount. This is necessary so that the subsequent
@@ -32,7 +32,7 @@ fn main() {
     let base_grains = Worley::new()
         .set_seed(1)
         .set_frequency(16.0)
-        .enable_range(true);
+        .set_return_type(ReturnType::F1);
 
     // Scale the small grain values so that they can be added to the base
     // granite texture. Worley polygons normally generate pits, so apply a
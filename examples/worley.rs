@@ -16,7 +16,7 @@
 
 extern crate noise;
 
-use noise::{RangeFunction, Worley};
+use noise::{RangeFunction, ReturnType, Worley};
 
 mod debug;
 
@@ -26,17 +26,17 @@ fn main() {
     debug::render_noise_module4("worley-linear_4d.png", &Worley::new(), 1024, 1024, 50);
 
     debug::render_noise_module2("worley-linear-range_2d.png",
-                                &Worley::new().enable_range(true),
+                                &Worley::new().set_return_type(ReturnType::F1),
                                 1024,
                                 1024,
                                 50);
     debug::render_noise_module3("worley-linear-range_3d.png",
-                                &Worley::new().enable_range(true),
+                                &Worley::new().set_return_type(ReturnType::F1),
                                 1024,
                                 1024,
                                 50);
     debug::render_noise_module4("worley-linear-range_4d.png",
-                                &Worley::new().enable_range(true),
+                                &Worley::new().set_return_type(ReturnType::F1),
                                 1024,
                                 1024,
                                 50);
@@ -75,21 +75,21 @@ fn main() {
 
     debug::render_noise_module2("worley-manhattan-range_2d.png",
                                 &Worley::new()
-                                    .enable_range(true)
+                                    .set_return_type(ReturnType::F1)
                                     .set_range_function(RangeFunction::Manhattan),
                                 1024,
                                 1024,
                                 50);
     debug::render_noise_module3("worley-manhattan-range_3d.png",
                                 &Worley::new()
-                                    .enable_range(true)
+                                    .set_return_type(ReturnType::F1)
                                     .set_range_function(RangeFunction::Manhattan),
                                 1024,
                                 1024,
                                 50);
     debug::render_noise_module4("worley-manhattan-range_4d.png",
                                 &Worley::new()
-                                    .enable_range(true)
+                                    .set_return_type(ReturnType::F1)
                                     .set_range_function(RangeFunction::Manhattan),
                                 1024,
                                 1024,
@@ -113,21 +113,21 @@ fn main() {
 
     debug::render_noise_module2("worley-chebyshev-range_2d.png",
                                 &Worley::new()
-                                    .enable_range(true)
+                                    .set_return_type(ReturnType::F1)
                                     .set_range_function(RangeFunction::Chebyshev),
                                 1024,
                                 1024,
                                 50);
     debug::render_noise_module3("worley-chebyshev-range_3d.png",
                                 &Worley::new()
-                                    .enable_range(true)
+                                    .set_return_type(ReturnType::F1)
                                     .set_range_function(RangeFunction::Chebyshev),
                                 1024,
                                 1024,
                                 50);
     debug::render_noise_module4("worley-chebyshev-range_4d.png",
                                 &Worley::new()
-                                    .enable_range(true)
+                                    .set_return_type(ReturnType::F1)
                                     .set_range_function(RangeFunction::Chebyshev),
                                 1024,
                                 1024,
@@ -151,23 +151,34 @@ fn main() {
 
     debug::render_noise_module2("worley-quadratic-range_2d.png",
                                 &Worley::new()
-                                    .enable_range(true)
+                                    .set_return_type(ReturnType::F1)
                                     .set_range_function(RangeFunction::Quadratic),
                                 1024,
                                 1024,
                                 50);
     debug::render_noise_module3("worley-quadratic-range_3d.png",
                                 &Worley::new()
-                                    .enable_range(true)
+                                    .set_return_type(ReturnType::F1)
                                     .set_range_function(RangeFunction::Quadratic),
                                 1024,
                                 1024,
                                 50);
     debug::render_noise_module4("worley-quadratic-range_4d.png",
                                 &Worley::new()
-                                    .enable_range(true)
+                                    .set_return_type(ReturnType::F1)
                                     .set_range_function(RangeFunction::Quadratic),
                                 1024,
                                 1024,
                                 50);
+
+    debug::render_noise_module2("worley-linear-f2_2d.png",
+                                &Worley::new().set_return_type(ReturnType::F2),
+                                1024,
+                                1024,
+                                50);
+    debug::render_noise_module2("worley-linear-edges_2d.png",
+                                &Worley::new().set_return_type(ReturnType::F2MinusF1),
+                                1024,
+                                1024,
+                                50);
 }
@@ -0,0 +1,886 @@
+// Copyright (c) 2017 The Noise-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. All files in the
+// project carrying such notice may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Noise function graphs built at runtime from a declarative description.
+//!
+//! A graph is described by a document with a map of named `nodes` and the
+//! `output` node. Each node has a `type`, which is the name of one of the
+//! noise functions in this crate, and the settings of that noise function.
+//! Settings that are left out keep their default values.
+//!
+//! Inputs to a node are given either as the name of another node, or as an
+//! inline node description:
+//!
+//! ```json
+//! {
+//!     "nodes": {
+//!         "mountains": { "type": "RidgedMulti", "octaves": 6, "seed": 1 },
+//!         "plains": {
+//!             "type": "ScaleBias",
+//!             "source": { "type": "Billow", "frequency": 2.0 },
+//!             "scale": 0.125,
+//!             "bias": -0.75
+//!         },
+//!         "terrain": {
+//!             "type": "Select",
+//!             "sources": ["plains", "mountains"],
+//!             "control": { "type": "Perlin", "seed": 2 },
+//!             "bounds": [0.0, 1000.0],
+//!             "falloff": 0.125
+//!         }
+//!     },
+//!     "output": "terrain"
+//! }
+//! ```
+//!
+//! The settings use the names of the fields and setters of each noise
+//! function. Single inputs are called `source`, pairs of inputs `sources`, and
+//! the control input of `Blend` and `Select` is `control`. The fractal
+//! functions take a `basis` naming the generator to build their octaves from,
+//! which defaults to `Perlin`. `Curve` takes its control points as
+//! `[input, output]` pairs, and `Displace` takes its `x`, `y` and `z`
+//! displacement functions as `displace`. `Worley` takes its optional value
+//! source as `source`.
+//!
+//! Documents can be loaded from JSON with the `serde_json` feature, from RON
+//! with the `ron` feature, or from any other serde format with
+//! `from_deserializer`. In RON, nodes are written as structs, e.g.
+//! `(type: "Fbm", octaves: 6)`, and settings don't need to be wrapped in
+//! `Some`.
+
+use math::Point3;
+use noise_fns::*;
+use permutationtable::LatticeMode;
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
+use std::collections::BTreeMap;
+use std::{error, fmt};
+
+/// Noise function built from a graph description.
+pub type BoxedNoiseFn = Box<dyn NoiseFn<Point3<f64>>>;
+
+/// Errors that can occur while building a noise function graph.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GraphError {
+    /// The document could not be parsed.
+    Parse(String),
+
+    /// A node's `type` is not the name of a noise function.
+    UnknownType { node: String, type_name: String },
+
+    /// A node refers to a node name that is not defined in `nodes`.
+    UnknownNode { node: String, reference: String },
+
+    /// A node is missing one of the inputs its type requires.
+    MissingInput { node: String, input: String },
+
+    /// A node has a setting with an unusable value.
+    InvalidParameter {
+        node: String,
+        parameter: &'static str,
+        message: String,
+    },
+
+    /// The named nodes refer to each other in a cycle. The first and last
+    /// names are the same.
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for GraphError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            GraphError::Parse(ref message) => {
+                write!(f, "invalid noise graph description: {}", message)
+            },
+            GraphError::UnknownType {
+                ref node,
+                ref type_name,
+            } => write!(f, "node `{}` has unknown type `{}`", node, type_name),
+            GraphError::UnknownNode {
+                ref node,
+                ref reference,
+            } => write!(
+                f,
+                "node `{}` refers to undefined node `{}`",
+                node, reference
+            ),
+            GraphError::MissingInput {
+                ref node,
+                ref input,
+            } => write!(f, "node `{}` is missing input `{}`", node, input),
+            GraphError::InvalidParameter {
+                ref node,
+                parameter,
+                ref message,
+            } => write!(
+                f,
+                "node `{}` has invalid `{}`: {}",
+                node, parameter, message
+            ),
+            GraphError::Cycle(ref nodes) => {
+                write!(f, "noise graph contains a cycle: {}", nodes.join(" -> "))
+            },
+        }
+    }
+}
+
+impl error::Error for GraphError {
+    fn description(&self) -> &str {
+        "invalid noise graph"
+    }
+}
+
+/// Builds a noise function from a JSON graph description.
+#[cfg(feature = "serde_json")]
+pub fn from_json(json: &str) -> Result<BoxedNoiseFn, GraphError> {
+    let description: GraphDescription =
+        ::serde_json::from_str(json).map_err(|error| GraphError::Parse(error.to_string()))?;
+    description.build()
+}
+
+/// Builds a noise function from a RON graph description.
+#[cfg(feature = "ron")]
+pub fn from_ron(ron: &str) -> Result<BoxedNoiseFn, GraphError> {
+    // Settings are optional, but shouldn't need to be wrapped in `Some`.
+    let options = ::ron::Options::default()
+        .with_default_extension(::ron::extensions::Extensions::IMPLICIT_SOME);
+    let description: GraphDescription = options
+        .from_str(ron)
+        .map_err(|error| GraphError::Parse(error.to_string()))?;
+    description.build()
+}
+
+/// Builds a noise function from a graph description read by any serde
+/// deserializer.
+pub fn from_deserializer<'de, D>(deserializer: D) -> Result<BoxedNoiseFn, GraphError>
+where
+    D: Deserializer<'de>,
+{
+    GraphDescription::deserialize(deserializer)
+        .map_err(|error| GraphError::Parse(error.to_string()))?
+        .build()
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GraphDescription {
+    #[serde(default)]
+    nodes: BTreeMap<String, NodeDescription>,
+    output: Input,
+}
+
+impl GraphDescription {
+    fn build(&self) -> Result<BoxedNoiseFn, GraphError> {
+        let mut builder = Builder {
+            nodes: &self.nodes,
+            path: Vec::new(),
+        };
+        builder.build_input("output", "output", &self.output)
+    }
+}
+
+/// Every setting of every node type. Each type only reads the settings that
+/// apply to it.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NodeDescription {
+    #[serde(rename = "type")]
+    type_name: String,
+
+    source: Option<Input>,
+    sources: Option<Vec<Input>>,
+    control: Option<Input>,
+    displace: Option<Vec<Input>>,
+
+    seed: Option<u64>,
+    lattice_mode: Option<LatticeMode>,
+    period: Option<usize>,
+    basis: Option<String>,
+    octaves: Option<usize>,
+    frequency: Option<f64>,
+    lacunarity: Option<f64>,
+    persistence: Option<f64>,
+    attenuation: Option<f64>,
+    range_function: Option<RangeFunction>,
+    return_type: Option<ReturnType>,
+    displacement: Option<f64>,
+    jitter: Option<f64>,
+    size: Option<usize>,
+    value: Option<f64>,
+    bounds: Option<[f64; 2]>,
+    falloff: Option<f64>,
+    control_points: Option<ControlPoints>,
+    invert_terraces: Option<bool>,
+    exponent: Option<f64>,
+    scale: Option<f64>,
+    bias: Option<f64>,
+    power: Option<f64>,
+    roughness: Option<usize>,
+    angles: Option<[f64; 3]>,
+    scales: Option<[f64; 3]>,
+    translation: Option<[f64; 3]>,
+}
+
+/// An input to a node: either the name of a node, or an inline node.
+#[derive(Debug)]
+enum Input {
+    Name(String),
+    Node(Box<NodeDescription>),
+}
+
+impl<'de> Deserialize<'de> for Input {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Input, D::Error> {
+        struct InputVisitor;
+
+        impl<'de> Visitor<'de> for InputVisitor {
+            type Value = Input;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a node name or a node description")
+            }
+
+            fn visit_str<E: de::Error>(self, name: &str) -> Result<Input, E> {
+                Ok(Input::Name(name.to_owned()))
+            }
+
+            fn visit_map<M: MapAccess<'de>>(self, map: M) -> Result<Input, M::Error> {
+                let node = NodeDescription::deserialize(MapAccessDeserializer::new(map))?;
+                Ok(Input::Node(Box::new(node)))
+            }
+        }
+
+        deserializer.deserialize_any(InputVisitor)
+    }
+}
+
+/// Control points of a `Terrace`, or `[input, output]` pairs of a `Curve`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ControlPoints {
+    Values(Vec<f64>),
+    Pairs(Vec<[f64; 2]>),
+}
+
+struct Builder<'a> {
+    nodes: &'a BTreeMap<String, NodeDescription>,
+
+    /// Names of the named nodes currently being built, used to find cycles.
+    path: Vec<&'a str>,
+}
+
+/// Applies `set` to `value` if the setting is present.
+fn set<T, V>(value: T, setting: Option<V>, set: fn(T, V) -> T) -> T {
+    match setting {
+        Some(setting) => set(value, setting),
+        None => value,
+    }
+}
+
+/// Expands `$build` with `$basis` bound to the generator type named by the
+/// node's `basis` setting, and boxes the fractal it builds. Only the periodic
+/// generators are given the node's period; `build_node` rejects a period for
+/// the others.
+macro_rules! with_basis {
+    ($node:expr, $desc:expr, $basis:ident => $build:expr) => {
+        match $desc.basis.as_ref().map(|basis| basis.as_str()) {
+            None | Some("Perlin") => {
+                type $basis = Perlin;
+                Box::new($build.set_period($desc.period))
+            },
+            Some("OpenSimplex") => {
+                type $basis = OpenSimplex;
+                Box::new($build)
+            },
+            Some("SuperSimplex") => {
+                type $basis = SuperSimplex;
+                Box::new($build)
+            },
+            Some("Value") => {
+                type $basis = Value;
+                Box::new($build.set_period($desc.period))
+            },
+            Some("Worley") => {
+                type $basis = Worley;
+                Box::new($build.set_period($desc.period))
+            },
+            Some(other) => {
+                return Err(GraphError::InvalidParameter {
+                    node: $node.to_owned(),
+                    parameter: "basis",
+                    message: format!("unknown generator `{}`", other),
+                });
+            },
+        }
+    };
+}
+
+impl<'a> Builder<'a> {
+    /// Builds the input called `label` of the node called `node`.
+    fn build_input(
+        &mut self,
+        node: &str,
+        label: &str,
+        input: &'a Input,
+    ) -> Result<BoxedNoiseFn, GraphError> {
+        match *input {
+            Input::Name(ref name) => {
+                if let Some(start) = self.path.iter().position(|&visiting| visiting == name) {
+                    let mut cycle: Vec<String> = self.path[start..]
+                        .iter()
+                        .map(|&name| name.to_owned())
+                        .collect();
+                    cycle.push(name.clone());
+                    return Err(GraphError::Cycle(cycle));
+                }
+
+                let desc = self
+                    .nodes
+                    .get(name)
+                    .ok_or_else(|| GraphError::UnknownNode {
+                        node: node.to_owned(),
+                        reference: name.clone(),
+                    })?;
+
+                self.path.push(name);
+                let result = self.build_node(name, desc);
+                self.path.pop();
+                result
+            },
+            Input::Node(ref desc) => {
+                let name = if node == label {
+                    node.to_owned()
+                } else {
+                    format!("{}.{}", node, label)
+                };
+                self.build_node(&name, desc)
+            },
+        }
+    }
+
+    fn build_node(
+        &mut self,
+        node: &str,
+        desc: &'a NodeDescription,
+    ) -> Result<BoxedNoiseFn, GraphError> {
+        if desc.period == Some(0) {
+            return Err(invalid(node, "period", "expected a period greater than 0"));
+        }
+        let simplex_basis = matches!(
+            desc.basis.as_deref(),
+            Some("OpenSimplex") | Some("SuperSimplex")
+        );
+        if desc.period.is_some() && simplex_basis {
+            return Err(invalid(
+                node,
+                "period",
+                "expected a Perlin, Value or Worley basis for a periodic fractal",
+            ));
+        }
+
+        let noise_fn: BoxedNoiseFn = match desc.type_name.as_str() {
+            "Checkerboard" => Box::new(set(Checkerboard::new(), desc.size, Checkerboard::set_size)),
+            "Constant" => Box::new(Constant::new(desc.value.unwrap_or(0.0))),
+            "Cylinders" => Box::new(set(
+                Cylinders::new(),
+                desc.frequency,
+                Cylinders::set_frequency,
+            )),
+            "OpenSimplex" => {
+                let simplex = set(OpenSimplex::new(), desc.seed, OpenSimplex::set_seed);
+                Box::new(set(simplex, desc.lattice_mode, OpenSimplex::set_lattice_mode))
+            },
+            "Perlin" => {
+                let perlin = set(Perlin::new(), desc.seed, Perlin::set_seed);
+                let perlin = set(perlin, desc.lattice_mode, Perlin::set_lattice_mode);
+                Box::new(perlin.set_period(desc.period))
+            },
+            "SuperSimplex" => {
+                let simplex = set(SuperSimplex::new(), desc.seed, SuperSimplex::set_seed);
+                Box::new(set(simplex, desc.lattice_mode, SuperSimplex::set_lattice_mode))
+            },
+            "Value" => {
+                let value = set(Value::new(), desc.seed, Value::set_seed);
+                let value = set(value, desc.lattice_mode, Value::set_lattice_mode);
+                Box::new(value.set_period(desc.period))
+            },
+            "Worley" => {
+                if let Some(jitter) = desc.jitter {
+                    if jitter.is_nan() {
+                        return Err(invalid(node, "jitter", "expected a number"));
+                    }
+                }
+                if let Some(RangeFunction::Minkowski(p)) = desc.range_function {
+                    if p.is_nan() || p <= 0.0 {
+                        return Err(invalid(
+                            node,
+                            "range_function",
+                            "expected a Minkowski p greater than 0",
+                        ));
+                    }
+                }
+
+                let worley = set(Worley::new(), desc.seed, Worley::set_seed);
+                let worley = set(worley, desc.lattice_mode, Worley::set_lattice_mode);
+                let worley = worley.set_period(desc.period);
+                let worley = set(worley, desc.range_function, Worley::set_range_function);
+                let worley = set(worley, desc.return_type, Worley::set_return_type);
+                let worley = set(worley, desc.frequency, Worley::set_frequency);
+                let worley = set(worley, desc.displacement, Worley::set_displacement);
+                let worley = set(worley, desc.jitter, Worley::set_jitter);
+                match desc.source {
+                    Some(_) => Box::new(worley.set_value_source(self.source(node, desc)?)),
+                    None => Box::new(worley),
+                }
+            },
+
+            "BasicMulti" => with_basis!(node, desc, T => fractal(BasicMulti::<T>::default(), desc)),
+            "Billow" => with_basis!(node, desc, T => fractal(Billow::<T>::default(), desc)),
+            "Fbm" => with_basis!(node, desc, T => fractal(Fbm::<T>::default(), desc)),
+            "HybridMulti" => {
+                with_basis!(node, desc, T => fractal(HybridMulti::<T>::default(), desc))
+            },
+            "RidgedMulti" => with_basis!(node, desc, T => {
+                let ridged = RidgedMulti::<T>::default();
+                fractal(set(ridged, desc.attenuation, RidgedMulti::set_attenuation), desc)
+            }),
+
+            "Abs" => Box::new(Abs::new(self.source(node, desc)?)),
+            "Clamp" => {
+                let clamp = Clamp::new(self.source(node, desc)?);
+                match desc.bounds {
+                    Some([lower, upper]) => Box::new(clamp.set_bounds(lower, upper)),
+                    None => Box::new(clamp),
+                }
+            },
+            "Curve" => {
+                let points = match desc.control_points {
+                    Some(ControlPoints::Pairs(ref points)) if points.len() >= 4 => points,
+                    Some(ControlPoints::Values(ref points)) if !points.is_empty() => {
+                        return Err(invalid(
+                            node,
+                            "control_points",
+                            "expected [input, output] pairs",
+                        ));
+                    },
+                    _ => {
+                        return Err(invalid(
+                            node,
+                            "control_points",
+                            "expected at least 4 control points",
+                        ));
+                    },
+                };
+                let curve = points.iter().fold(
+                    Curve::new(self.source(node, desc)?),
+                    |curve, &[input, output]| curve.add_control_point(input, output),
+                );
+                Box::new(curve)
+            },
+            "Exponent" => {
+                let exponent = Exponent::new(self.source(node, desc)?);
+                Box::new(set(exponent, desc.exponent, Exponent::set_exponent))
+            },
+            "Invert" => Box::new(Invert::new(self.source(node, desc)?)),
+            "ScaleBias" => {
+                let scale_bias = ScaleBias::new(self.source(node, desc)?);
+                let scale_bias = set(scale_bias, desc.scale, ScaleBias::set_scale);
+                Box::new(set(scale_bias, desc.bias, ScaleBias::set_bias))
+            },
+            "Terrace" => {
+                let points = match desc.control_points {
+                    Some(ControlPoints::Values(ref points)) if points.len() >= 2 => points,
+                    Some(ControlPoints::Pairs(ref points)) if !points.is_empty() => {
+                        return Err(invalid(node, "control_points", "expected a list of values"));
+                    },
+                    _ => {
+                        return Err(invalid(
+                            node,
+                            "control_points",
+                            "expected at least 2 control points",
+                        ));
+                    },
+                };
+                let terrace = points
+                    .iter()
+                    .fold(Terrace::new(self.source(node, desc)?), |terrace, &point| {
+                        terrace.add_control_point(point)
+                    });
+                Box::new(set(terrace, desc.invert_terraces, Terrace::invert_terraces))
+            },
+
+            "Add" => {
+                let (source1, source2) = self.sources(node, desc)?;
+                Box::new(Add::new(source1, source2))
+            },
+            "Max" => {
+                let (source1, source2) = self.sources(node, desc)?;
+                Box::new(Max::new(source1, source2))
+            },
+            "Min" => {
+                let (source1, source2) = self.sources(node, desc)?;
+                Box::new(Min::new(source1, source2))
+            },
+            "Multiply" => {
+                let (source1, source2) = self.sources(node, desc)?;
+                Box::new(Multiply::new(source1, source2))
+            },
+            "Power" => {
+                let (source1, source2) = self.sources(node, desc)?;
+                Box::new(Power::new(source1, source2))
+            },
+
+            "Blend" => {
+                let (source1, source2) = self.sources(node, desc)?;
+                let control = self.control(node, desc)?;
+                Box::new(Blend::new(source1, source2, control))
+            },
+            "Select" => {
+                let (source1, source2) = self.sources(node, desc)?;
+                let control = self.control(node, desc)?;
+                let select = Select::new(source1, source2, control);
+                let select = match desc.bounds {
+                    Some([lower, upper]) => select.set_bounds(lower, upper),
+                    None => select,
+                };
+                Box::new(set(select, desc.falloff, Select::set_edge_falloff))
+            },
+
+            "Displace" => {
+                let source = self.source(node, desc)?;
+                let mut displace = self.inputs(node, desc, "displace", &desc.displace, 3)?;
+                let z = displace.pop().unwrap();
+                let y = displace.pop().unwrap();
+                let x = displace.pop().unwrap();
+                Box::new(Displace::new(source, x, y, z, Constant::new(0.0)))
+            },
+            "RotatePoint" => {
+                let rotate = RotatePoint::new(self.source(node, desc)?);
+                match desc.angles {
+                    Some([x, y, z]) => Box::new(rotate.set_angles(x, y, z, 0.0)),
+                    None => Box::new(rotate),
+                }
+            },
+            "ScalePoint" => {
+                let scale = ScalePoint::new(self.source(node, desc)?);
+                match desc.scales {
+                    Some([x, y, z]) => Box::new(scale.set_all_scales(x, y, z, 1.0)),
+                    None => Box::new(scale),
+                }
+            },
+            "TranslatePoint" => {
+                let translate = TranslatePoint::new(self.source(node, desc)?);
+                match desc.translation {
+                    Some([x, y, z]) => Box::new(translate.set_all_translations(x, y, z, 0.0)),
+                    None => Box::new(translate),
+                }
+            },
+            "Turbulence" => {
+                let turbulence = Turbulence::new(self.source(node, desc)?);
+                let turbulence = set(turbulence, desc.seed, Turbulence::set_seed);
+                let turbulence = set(turbulence, desc.frequency, Turbulence::set_frequency);
+                let turbulence = set(turbulence, desc.power, Turbulence::set_power);
+                Box::new(set(turbulence, desc.roughness, Turbulence::set_roughness))
+            },
+
+            "Cache" => Box::new(Cache::new(self.source(node, desc)?)),
+
+            _ => {
+                return Err(GraphError::UnknownType {
+                    node: node.to_owned(),
+                    type_name: desc.type_name.clone(),
+                });
+            },
+        };
+
+        Ok(noise_fn)
+    }
+
+    fn source(
+        &mut self,
+        node: &str,
+        desc: &'a NodeDescription,
+    ) -> Result<BoxedNoiseFn, GraphError> {
+        match desc.source {
+            Some(ref source) => self.build_input(node, "source", source),
+            None => Err(missing(node, "source")),
+        }
+    }
+
+    fn control(
+        &mut self,
+        node: &str,
+        desc: &'a NodeDescription,
+    ) -> Result<BoxedNoiseFn, GraphError> {
+        match desc.control {
+            Some(ref control) => self.build_input(node, "control", control),
+            None => Err(missing(node, "control")),
+        }
+    }
+
+    fn sources(
+        &mut self,
+        node: &str,
+        desc: &'a NodeDescription,
+    ) -> Result<(BoxedNoiseFn, BoxedNoiseFn), GraphError> {
+        let mut sources = self.inputs(node, desc, "sources", &desc.sources, 2)?;
+        let source2 = sources.pop().unwrap();
+        let source1 = sources.pop().unwrap();
+        Ok((source1, source2))
+    }
+
+    /// Builds a list of exactly `count` inputs.
+    fn inputs(
+        &mut self,
+        node: &str,
+        desc: &'a NodeDescription,
+        label: &'static str,
+        inputs: &'a Option<Vec<Input>>,
+        count: usize,
+    ) -> Result<Vec<BoxedNoiseFn>, GraphError> {
+        let inputs = match *inputs {
+            Some(ref inputs) => inputs,
+            None => return Err(missing(node, label)),
+        };
+        if inputs.len() < count {
+            return Err(missing(node, &format!("{}[{}]", label, inputs.len())));
+        }
+        if inputs.len() > count {
+            let message = format!(
+                "`{}` takes {} inputs, found {}",
+                desc.type_name,
+                count,
+                inputs.len()
+            );
+            return Err(invalid(node, label, &message));
+        }
+
+        inputs
+            .iter()
+            .enumerate()
+            .map(|(index, input)| self.build_input(node, &format!("{}[{}]", label, index), input))
+            .collect()
+    }
+}
+
+/// Applies the common fractal settings.
+fn fractal<F>(fractal: F, desc: &NodeDescription) -> F
+where
+    F: MultiFractal + Seedable,
+{
+    let fractal = set(fractal, desc.seed, F::set_seed);
+    let fractal = set(fractal, desc.octaves, F::set_octaves);
+    let fractal = set(fractal, desc.frequency, F::set_frequency);
+    let fractal = set(fractal, desc.lacunarity, F::set_lacunarity);
+    set(fractal, desc.persistence, F::set_persistence)
+}
+
+fn missing(node: &str, input: &str) -> GraphError {
+    GraphError::MissingInput {
+        node: node.to_owned(),
+        input: input.to_owned(),
+    }
+}
+
+fn invalid(node: &str, parameter: &'static str, message: &str) -> GraphError {
+    GraphError::InvalidParameter {
+        node: node.to_owned(),
+        parameter: parameter,
+        message: message.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json;
+
+    fn from_str(json: &str) -> Result<BoxedNoiseFn, GraphError> {
+        from_deserializer(&mut serde_json::Deserializer::from_str(json))
+    }
+
+    #[test]
+    fn test_graph_matches_hand_built_graph() {
+        let graph = from_str(
+            r#"{
+                "nodes": {
+                    "mountains": { "type": "RidgedMulti", "octaves": 6, "seed": 1 },
+                    "plains": {
+                        "type": "ScaleBias",
+                        "source": { "type": "Billow", "basis": "Value", "frequency": 2.0 },
+                        "scale": 0.125,
+                        "bias": -0.75
+                    },
+                    "terrain": {
+                        "type": "Select",
+                        "sources": ["plains", "mountains"],
+                        "control": { "type": "Perlin", "seed": 2 },
+                        "bounds": [0.0, 1000.0],
+                        "falloff": 0.125
+                    }
+                },
+                "output": "terrain"
+            }"#,
+        )
+        .unwrap();
+
+        let plains = ScaleBias::new(Billow::<Value>::default().set_frequency(2.0))
+            .set_scale(0.125)
+            .set_bias(-0.75);
+        let mountains = RidgedMulti::new().set_seed(1).set_octaves(6);
+        let expected = Select::new(plains, mountains, Perlin::new().set_seed(2))
+            .set_bounds(0.0, 1000.0)
+            .set_edge_falloff(0.125);
+
+        for &point in &[[0.5, 1.5, 2.5], [-3.2, 0.7, 9.1]] {
+            assert_eq!(graph.get(point), expected.get(point));
+        }
+    }
+
+    #[test]
+    fn test_graph_errors() {
+        assert_eq!(
+            from_str(r#"{ "output": { "type": "Perlim" } }"#).err(),
+            Some(GraphError::UnknownType {
+                node: "output".to_owned(),
+                type_name: "Perlim".to_owned(),
+            })
+        );
+        assert_eq!(
+            from_str(r#"{ "output": { "type": "Abs", "source": "base" } }"#).err(),
+            Some(GraphError::UnknownNode {
+                node: "output".to_owned(),
+                reference: "base".to_owned(),
+            })
+        );
+        assert_eq!(
+            from_str(r#"{ "output": { "type": "Add", "sources": [{ "type": "Perlin" }] } }"#).err(),
+            Some(GraphError::MissingInput {
+                node: "output".to_owned(),
+                input: "sources[1]".to_owned(),
+            })
+        );
+        assert_eq!(
+            from_str(
+                r#"{
+                    "nodes": {
+                        "a": { "type": "Abs", "source": { "type": "Invert", "source": "b" } },
+                        "b": { "type": "Add", "sources": ["c", "a"] },
+                        "c": { "type": "Perlin" }
+                    },
+                    "output": "a"
+                }"#,
+            )
+            .err(),
+            Some(GraphError::Cycle(vec![
+                "a".to_owned(),
+                "b".to_owned(),
+                "a".to_owned(),
+            ]))
+        );
+        assert_eq!(
+            from_str(r#"{ "output": { "type": "Fbm", "basis": "OpenSimplex", "period": 4 } }"#)
+                .err(),
+            Some(GraphError::InvalidParameter {
+                node: "output".to_owned(),
+                parameter: "period",
+                message: "expected a Perlin, Value or Worley basis for a periodic fractal"
+                    .to_owned(),
+            })
+        );
+        assert_eq!(
+            from_str(
+                r#"{ "output": { "type": "Worley", "range_function": { "Minkowski": -1.0 } } }"#,
+            )
+            .err(),
+            Some(GraphError::InvalidParameter {
+                node: "output".to_owned(),
+                parameter: "range_function",
+                message: "expected a Minkowski p greater than 0".to_owned(),
+            })
+        );
+        match from_str(r#"{ "output": { "type": "Perlin", "sede": 3 } }"#) {
+            Err(GraphError::Parse(message)) => assert!(message.contains("sede")),
+            _ => panic!("expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn test_graph_worley_value_source() {
+        let graph = from_str(
+            r#"{
+                "output": {
+                    "type": "Worley",
+                    "frequency": 2.0,
+                    "source": { "type": "Fbm", "seed": 5 }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let expected = Worley::new()
+            .set_frequency(2.0)
+            .set_value_source(Fbm::new().set_seed(5));
+        for &point in &[[0.5, 1.5, 2.5], [-3.2, 0.7, 9.1]] {
+            assert_eq!(graph.get(point), expected.get(point));
+        }
+    }
+
+    fn deserialize_like<T: de::DeserializeOwned>(_: &T, json: &str) -> T {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_serde_round_trip_rebuilds_tables() {
+        let terrace = Terrace::new(Perlin::new())
+            .add_control_point(-1.0)
+            .add_control_point(0.0)
+            .add_control_point(1.0);
+        let control = ScaleBias::new(Perlin::new()).set_scale(0.5).set_bias(0.5);
+
+        let graph = Select::new(
+            Blend::new(Fbm::new(), terrace, control),
+            Turbulence::new(
+                Worley::new()
+                    .set_seed(7)
+                    .set_frequency(2.0)
+                    .set_return_type(ReturnType::F2MinusF1)
+                    .set_lattice_mode(LatticeMode::Hash),
+            )
+            .set_seed(3),
+            Clamp::new(RidgedMulti::<Perlin>::new().set_seed(11).set_octaves(4))
+                .set_bounds(-0.5, 0.5),
+        )
+        .set_bounds(0.0, 1.0)
+        .set_edge_falloff(0.25);
+
+        let json = serde_json::to_string(&graph).unwrap();
+        assert!(!json.contains("perm_table") && !json.contains("sources"));
+
+        let copy = deserialize_like(&graph, &json);
+        for &point in &[[0.5, 1.5, 2.5], [-3.2, 0.7, 9.1]] {
+            assert_eq!(graph.get(point), copy.get(point));
+        }
+    }
+
+    #[cfg(feature = "ron")]
+    #[test]
+    fn test_graph_from_ron() {
+        let graph = from_ron(
+            r#"(
+                nodes: {
+                    "base": (type: "Fbm", octaves: 4),
+                },
+                output: (type: "Abs", source: "base"),
+            )"#,
+        )
+        .unwrap();
+
+        let expected = Abs::new(Fbm::new().set_octaves(4));
+        assert_eq!(graph.get([0.5, 1.5, 2.5]), expected.get([0.5, 1.5, 2.5]));
+    }
+}
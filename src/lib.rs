@@ -20,12 +20,26 @@
 #![cfg_attr(feature = "cargo-clippy", allow(inline_always))]
 #![deny(missing_copy_implementations)]
 
+#[cfg(any(test, feature = "rand"))]
 extern crate rand;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(any(feature = "serde_json", all(test, feature = "serde")))]
+extern crate serde_json;
+#[cfg(feature = "ron")]
+extern crate ron;
+#[cfg(feature = "image")]
+extern crate image;
 
 pub use math::{Point2, Point3, Point4};
 pub use noise_fns::*;
+pub use permutationtable::{LatticeMode, PermutationTable, DEFAULT_LATTICE_MODE};
 
+#[cfg(feature = "serde")]
+pub mod graph;
 mod gradient;
 mod math;
 mod noise_fns;
 mod permutationtable;
+pub mod utils;
@@ -147,6 +147,35 @@ where
     f(f(f(ax, ay), az), aw)
 }
 
+/// Picks each component from either `a` or `b`, taking component n from `b`
+/// when bit n of `mask` is set.
+#[inline]
+pub fn select2<T: Copy>(mask: usize, a: Vector2<T>, b: Vector2<T>) -> Vector2<T> {
+    [
+        if mask & 1 == 0 { a[0] } else { b[0] },
+        if mask & 2 == 0 { a[1] } else { b[1] },
+    ]
+}
+
+#[inline]
+pub fn select3<T: Copy>(mask: usize, a: Vector3<T>, b: Vector3<T>) -> Vector3<T> {
+    [
+        if mask & 1 == 0 { a[0] } else { b[0] },
+        if mask & 2 == 0 { a[1] } else { b[1] },
+        if mask & 4 == 0 { a[2] } else { b[2] },
+    ]
+}
+
+#[inline]
+pub fn select4<T: Copy>(mask: usize, a: Vector4<T>, b: Vector4<T>) -> Vector4<T> {
+    [
+        if mask & 1 == 0 { a[0] } else { b[0] },
+        if mask & 2 == 0 { a[1] } else { b[1] },
+        if mask & 4 == 0 { a[2] } else { b[2] },
+        if mask & 8 == 0 { a[3] } else { b[3] },
+    ]
+}
+
 #[inline]
 pub fn add2<T>(a: Point2<T>, b: Vector2<T>) -> Point2<T>
 where
@@ -291,22 +320,6 @@ where
     map4(x, cast)
 }
 
-// f64 doesn't implement From<isize>
-#[inline]
-pub fn to_f642(x: Point2<isize>) -> Point2<f64> {
-    [x[0] as f64, x[1] as f64]
-}
-
-#[inline]
-pub fn to_f643(x: Point3<isize>) -> Point3<f64> {
-    [x[0] as f64, x[1] as f64, x[2] as f64]
-}
-
-#[inline]
-pub fn to_f644(x: Point4<isize>) -> Point4<f64> {
-    [x[0] as f64, x[1] as f64, x[2] as f64, x[3] as f64]
-}
-
 // isize doesn't implement From<f64>
 #[inline]
 pub fn to_isize2(x: Point2<f64>) -> Point2<isize> {
@@ -332,7 +345,7 @@ pub mod interp {
     /// Performs linear interploation between two values.
     #[inline]
     pub fn linear(a: f64, b: f64, x: f64) -> f64 {
-        x.mul_add((b - a), a)
+        x.mul_add(b - a, a)
     }
 
     /// Performs cubic interpolation between two values bound between two other
@@ -367,4 +380,10 @@ pub mod interp {
     pub fn s_curve5(x: f64) -> f64 {
         x * x * x * (x * (x * 6.0 - 15.0 + 10.0))
     }
+
+    /// Derivative of `s_curve5`.
+    #[inline]
+    pub fn s_curve5_derivative(x: f64) -> f64 {
+        x * x * x * (x * 30.0 - 20.0)
+    }
 }
@@ -7,7 +7,7 @@
 // except according to those terms.
 
 use math::{Point2, Point3, Point4};
-use noise_fns::NoiseFn;
+use noise_fns::{NoiseFn, Reseedable};
 use std::cell::{Cell, RefCell};
 
 /// Noise function that caches the last output value generated by the source
@@ -23,13 +23,19 @@ use std::cell::{Cell, RefCell};
 /// multiple noise functions. If a source function is not cached, the source
 /// function will redundantly calculate the same output value once for each
 /// noise function in which it is included.
+///
+/// `Cache` is not `Sync`. To share a cached noise function between threads, or
+/// to remember more than one value, use `SyncCache`.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Cache<Source> {
     /// Outputs the value to be cached.
     pub source: Source,
 
+    #[cfg_attr(feature = "serde", serde(skip))]
     value: Cell<Option<f64>>,
 
+    #[cfg_attr(feature = "serde", serde(skip))]
     point: RefCell<Vec<f64>>,
 }
 
@@ -43,6 +49,17 @@ impl<Source> Cache<Source> {
     }
 }
 
+/// Reseeding a `Cache` reseeds its source function and discards the cached
+/// value.
+impl<Source> Reseedable for Cache<Source>
+where
+    Source: Reseedable,
+{
+    fn reseed(self, seed: u64) -> Cache<Source> {
+        Cache::new(self.source.reseed(seed))
+    }
+}
+
 impl<Source> NoiseFn<Point2<f64>> for Cache<Source>
 where
     Source: NoiseFn<Point2<f64>>,
@@ -6,20 +6,22 @@
 // project carrying such notice may not be copied, modified, or distributed
 // except according to those terms.
 
-use noise_fns::NoiseFn;
+use noise_fns::{NoiseFn, Reseedable, derive_seed};
 
 /// Noise function that outputs the sum of the two output values from two source
 /// functions.
-pub struct Add<'a, T: 'a> {
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Add<Source1, Source2> {
     /// Outputs a value.
-    pub source1: &'a NoiseFn<T>,
+    pub source1: Source1,
 
     /// Outputs a value.
-    pub source2: &'a NoiseFn<T>,
+    pub source2: Source2,
 }
 
-impl<'a, T> Add<'a, T> {
-    pub fn new(source1: &'a NoiseFn<T>, source2: &'a NoiseFn<T>) -> Add<'a, T> {
+impl<Source1, Source2> Add<Source1, Source2> {
+    pub fn new(source1: Source1, source2: Source2) -> Add<Source1, Source2> {
         Add {
             source1: source1,
             source2: source2,
@@ -27,11 +29,45 @@ impl<'a, T> Add<'a, T> {
     }
 }
 
-impl<'a, T> NoiseFn<T> for Add<'a, T>
+impl<Source1, Source2> Reseedable for Add<Source1, Source2>
+where
+    Source1: Reseedable,
+    Source2: Reseedable,
+{
+    fn reseed(self, seed: u64) -> Add<Source1, Source2> {
+        Add {
+            source1: self.source1.reseed(derive_seed(seed, 0)),
+            source2: self.source2.reseed(derive_seed(seed, 1)),
+        }
+    }
+}
+
+impl<Source1, Source2, T> NoiseFn<T> for Add<Source1, Source2>
 where
     T: Copy,
+    Source1: NoiseFn<T>,
+    Source2: NoiseFn<T>,
 {
     fn get(&self, point: T) -> f64 {
         self.source1.get(point) + self.source2.get(point)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {Add, NoiseFn, Perlin, Reseedable, Seedable};
+
+    #[test]
+    fn test_reseed_derives_distinct_seeds() {
+        let add = Add::new(Perlin::new(), Perlin::new()).reseed(42);
+        assert!(add.source1.seed() != add.source2.seed());
+        assert!(add.source1.seed() != 42 && add.source2.seed() != 42);
+
+        let again = Add::new(Perlin::new(), Perlin::new()).reseed(42);
+        assert_eq!(add.source1.seed(), again.source1.seed());
+        assert_eq!(add.source2.seed(), again.source2.seed());
+
+        let other = Add::new(Perlin::new(), Perlin::new()).reseed(43);
+        assert!(add.get([0.5, 1.5, 2.5]) != other.get([0.5, 1.5, 2.5]));
+    }
+}
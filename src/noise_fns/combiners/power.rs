@@ -6,20 +6,22 @@
 // project carrying such notice may not be copied, modified, or distributed
 // except according to those terms.
 
-use noise_fns::NoiseFn;
+use noise_fns::{NoiseFn, Reseedable, derive_seed};
 
 /// Noise function that raises the output value from the first source function
 /// to the power of the output value of the second source function.
-pub struct Power<'a, T: 'a> {
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Power<Source1, Source2> {
     /// Outputs a value.
-    pub source1: &'a NoiseFn<T>,
+    pub source1: Source1,
 
     /// Outputs a value.
-    pub source2: &'a NoiseFn<T>,
+    pub source2: Source2,
 }
 
-impl<'a, T> Power<'a, T> {
-    pub fn new(source1: &'a NoiseFn<T>, source2: &'a NoiseFn<T>) -> Power<'a, T> {
+impl<Source1, Source2> Power<Source1, Source2> {
+    pub fn new(source1: Source1, source2: Source2) -> Power<Source1, Source2> {
         Power {
             source1: source1,
             source2: source2,
@@ -27,9 +29,24 @@ impl<'a, T> Power<'a, T> {
     }
 }
 
-impl<'a, T> NoiseFn<T> for Power<'a, T>
+impl<Source1, Source2> Reseedable for Power<Source1, Source2>
+where
+    Source1: Reseedable,
+    Source2: Reseedable,
+{
+    fn reseed(self, seed: u64) -> Power<Source1, Source2> {
+        Power {
+            source1: self.source1.reseed(derive_seed(seed, 0)),
+            source2: self.source2.reseed(derive_seed(seed, 1)),
+        }
+    }
+}
+
+impl<Source1, Source2, T> NoiseFn<T> for Power<Source1, Source2>
 where
     T: Copy,
+    Source1: NoiseFn<T>,
+    Source2: NoiseFn<T>,
 {
     fn get(&self, point: T) -> f64 {
         (self.source1.get(point)).powf(self.source2.get(point))
@@ -0,0 +1,135 @@
+// Copyright (c) 2017 The Noise-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. All files in the
+// project carrying such notice may not be copied, modified, or distributed
+// except according to those terms.
+
+use noise_fns::*;
+
+/// Extension methods for chaining modifiers and transformers onto a noise
+/// function.
+///
+/// Each method wraps `self` in the matching noise function type, so
+///
+/// ```
+/// use noise::{NoiseFnExt, Perlin};
+///
+/// let noise = Perlin::new().scale(2.0, 2.0, 2.0, 2.0).abs().scale_bias(2.0, -1.0);
+/// ```
+///
+/// builds the same `ScaleBias<Abs<ScalePoint<Perlin>>>` as nesting the `new`
+/// calls by hand. The methods are available on every type implementing
+/// `NoiseFnOps`, which covers the noise functions of this crate, boxed
+/// `dyn NoiseFn` trait objects and references to either. See `NoiseFnOps` for
+/// how to opt your own noise function types in.
+pub trait NoiseFnExt: Sized {
+    /// Wraps `self` in a `ScaleBias` with the given scale and bias.
+    fn scale_bias(self, scale: f64, bias: f64) -> ScaleBias<Self> {
+        ScaleBias::new(self).set_scale(scale).set_bias(bias)
+    }
+
+    /// Wraps `self` in a `Clamp` with the given bounds.
+    fn clamp(self, lower_bound: f64, upper_bound: f64) -> Clamp<Self> {
+        Clamp::new(self).set_bounds(lower_bound, upper_bound)
+    }
+
+    /// Wraps `self` in an `Abs`.
+    fn abs(self) -> Abs<Self> {
+        Abs::new(self)
+    }
+
+    /// Wraps `self` in an `Exponent` with the given exponent.
+    fn exponent(self, exponent: f64) -> Exponent<Self> {
+        Exponent::new(self).set_exponent(exponent)
+    }
+
+    /// Wraps `self` in a `Turbulence` with the given frequency, power and
+    /// roughness.
+    fn turbulence(self, frequency: f64, power: f64, roughness: usize) -> Turbulence<Self> {
+        Turbulence::new(self)
+            .set_frequency(frequency)
+            .set_power(power)
+            .set_roughness(roughness)
+    }
+
+    /// Wraps `self` in a `TranslatePoint` with the given translation along
+    /// each axis.
+    fn translate(self, x: f64, y: f64, z: f64, u: f64) -> TranslatePoint<Self> {
+        TranslatePoint::new(self).set_all_translations(x, y, z, u)
+    }
+
+    /// Wraps `self` in a `RotatePoint` with the given rotation angles, in
+    /// degrees, around each axis.
+    fn rotate(self, x: f64, y: f64, z: f64, u: f64) -> RotatePoint<Self> {
+        RotatePoint::new(self).set_angles(x, y, z, u)
+    }
+
+    /// Wraps `self` in a `ScalePoint` with the given scale along each axis.
+    fn scale(self, x: f64, y: f64, z: f64, u: f64) -> ScalePoint<Self> {
+        ScalePoint::new(self).set_all_scales(x, y, z, u)
+    }
+
+    /// Wraps `self` in a `Cache`.
+    fn cached(self) -> Cache<Self> {
+        Cache::new(self)
+    }
+
+    /// Wraps `self` in a `Terrace` with the given control points.
+    fn terrace(self, control_points: &[f64]) -> Terrace<Self> {
+        control_points
+            .iter()
+            .fold(Terrace::new(self), |terrace, &point| {
+                terrace.add_control_point(point)
+            })
+    }
+}
+
+impl<M: NoiseFnOps> NoiseFnExt for M {}
+
+#[cfg(test)]
+mod tests {
+    use {Abs, Fbm, NoiseFn, NoiseFnExt, Perlin, ScaleBias, Terrace};
+
+    #[test]
+    fn test_chain_matches_nested_nodes() {
+        let chained: Abs<ScaleBias<Fbm>> = (Fbm::new() * 0.5 + 0.25).abs();
+        let nested = Abs::new(ScaleBias::new(Fbm::new()).set_scale(0.5).set_bias(0.25));
+
+        let terraced = (&chained).terrace(&[-1.0, 0.0, 1.0]).cached();
+        let terraced_nested = Terrace::new(&nested)
+            .add_control_point(-1.0)
+            .add_control_point(0.0)
+            .add_control_point(1.0);
+
+        let warped = Perlin::new()
+            .turbulence(2.0, 0.5, 2)
+            .rotate(10.0, 20.0, 30.0, 0.0)
+            .translate(1.0, 2.0, 3.0, 0.0)
+            .scale(0.5, 0.5, 0.5, 0.5)
+            .exponent(2.0)
+            .clamp(0.0, 0.5);
+
+        for &point in &[[0.3, 1.7, -2.1], [4.2, -0.6, 8.9]] {
+            assert_eq!(chained.get(point), nested.get(point));
+            assert_eq!(terraced.get(point), terraced_nested.get(point));
+
+            let value = warped.get(point);
+            assert!((0.0..=0.5).contains(&value));
+        }
+    }
+
+    #[test]
+    fn test_chain_on_boxed_noise_fn() {
+        let boxed: Box<dyn NoiseFn<[f64; 3]>> = Box::new(Fbm::new());
+        let chained = boxed.abs().scale_bias(2.0, -1.0);
+        let nested = ScaleBias::new(Abs::new(Fbm::new()))
+            .set_scale(2.0)
+            .set_bias(-1.0);
+
+        for &point in &[[0.3, 1.7, -2.1], [4.2, -0.6, 8.9]] {
+            assert_eq!(chained.get(point), nested.get(point));
+        }
+    }
+}
@@ -7,7 +7,7 @@
 // except according to those terms.
 
 use math::{Point2, Point3, Point4};
-use noise_fns::NoiseFn;
+use noise_fns::{NoiseFn, Reseedable};
 
 /// Default Checkerboard size
 pub const DEFAULT_CHECKERBOARD_SIZE: usize = 0;
@@ -21,12 +21,14 @@ pub const DEFAULT_CHECKERBOARD_SIZE: usize = 0;
 /// This noise function is not very useful by itself, but it can be used for
 /// debugging purposes.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Checkerboard {
     /// Controls the size of the block in 2^(size).
     pub size: usize,
 
     // Dummy field to prevent struct initialization except through the
     // new() constructor.
+    #[cfg_attr(feature = "serde", serde(skip))]
     _dummy: (),
 }
 
@@ -54,6 +56,13 @@ impl Default for Checkerboard {
 
 // These impl's should be made generic over Point, but there is no higher Point
 // type. Keep the code the same anyway.
+/// `Checkerboard` has no seed, so reseeding leaves it unchanged.
+impl Reseedable for Checkerboard {
+    fn reseed(self, _seed: u64) -> Checkerboard {
+        self
+    }
+}
+
 impl NoiseFn<Point2<f64>> for Checkerboard {
     fn get(&self, point: Point2<f64>) -> f64 {
         calculate_checkerboard(&point, self.size)
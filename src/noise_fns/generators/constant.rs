@@ -6,7 +6,7 @@
 // project carrying such notice may not be copied, modified, or distributed
 // except according to those terms.
 
-use noise_fns::NoiseFn;
+use noise_fns::{NoiseFn, Reseedable};
 
 /// Noise function that outputs a constant value.
 ///
@@ -16,6 +16,7 @@ use noise_fns::NoiseFn;
 /// This function is not very useful by itself, but can be used as a source
 /// function for other noise functions.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Constant {
     /// Constant value.
     pub value: f64,
@@ -27,8 +28,22 @@ impl Constant {
     }
 }
 
+/// `Constant` has no seed, so reseeding leaves it unchanged.
+impl Reseedable for Constant {
+    fn reseed(self, _seed: u64) -> Constant {
+        self
+    }
+}
+
 impl<T: Copy> NoiseFn<T> for Constant {
     fn get(&self, _point: T) -> f64 {
         self.value
     }
+
+    fn get_many(&self, points: &[T], out: &mut [f64]) {
+        assert_eq!(points.len(), out.len());
+        for out in out.iter_mut() {
+            *out = self.value;
+        }
+    }
 }
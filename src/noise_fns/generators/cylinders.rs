@@ -7,7 +7,7 @@
 // except according to those terms.
 
 use math::{Point2, Point3, Point4};
-use noise_fns::NoiseFn;
+use noise_fns::{NoiseFn, Reseedable};
 
 /// Default cylinders frequency
 pub const DEFAULT_CYLINDERS_FREQUENCY: f64 = 1.0;
@@ -18,6 +18,7 @@ pub const DEFAULT_CYLINDERS_FREQUENCY: f64 = 1.0;
 /// cylinders are oriented along the z axis similar to the concentric rings of
 /// a tree. Each cylinder extends infinitely along the z axis.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Cylinders {
     /// Frequency of the concentric objects.
     pub frequency: f64,
@@ -39,6 +40,13 @@ impl Default for Cylinders {
     }
 }
 
+/// `Cylinders` has no seed, so reseeding leaves it unchanged.
+impl Reseedable for Cylinders {
+    fn reseed(self, _seed: u64) -> Cylinders {
+        self
+    }
+}
+
 impl NoiseFn<Point2<f64>> for Cylinders {
     fn get(&self, point: Point2<f64>) -> f64 {
         calculate_cylinders(&point, self.frequency)
@@ -8,10 +8,10 @@
 
 use math;
 use math::{Point2, Point3, Point4};
-use noise_fns::{MultiFractal, NoiseFn, Perlin, Seedable};
+use noise_fns::{MultiFractal, NoiseFn, Periodic, Perlin, Reseedable, Seedable};
 
 /// Default noise seed for the `BasicMulti` noise function.
-pub const DEFAULT_BASICMULTI_SEED: u32 = 0;
+pub const DEFAULT_BASICMULTI_SEED: u64 = 0;
 /// Default number of octaves for the `BasicMulti` noise function.
 pub const DEFAULT_BASICMULTI_OCTAVES: usize = 6;
 /// Default frequency for the `BasicMulti` noise function.
@@ -33,8 +33,18 @@ pub const BASICMULTI_MAX_OCTAVES: usize = 32;
 /// smooth. As the value moves further away from zero, higher frequencies will
 /// not be as damped and thus will grow more jagged as iteration progresses.
 ///
+/// Like `Fbm`, the octaves are sampled from the `T` source function, which
+/// defaults to `Perlin`.
 #[derive(Clone, Debug)]
-pub struct BasicMulti {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        from = "BasicMultiConfig",
+        bound(deserialize = "T: Default + Seedable + Periodic")
+    )
+)]
+pub struct BasicMulti<T = Perlin> {
     /// Total number of frequency octaves to generate the noise with.
     ///
     /// The number of octaves control the _amount of detail_ in the noise
@@ -63,14 +73,58 @@ pub struct BasicMulti {
     /// persistence produces "rougher" noise.
     pub persistence: f64,
 
-    seed: u32,
-    sources: Vec<Perlin>,
+    seed: u64,
+    period: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing))]
+    set_source_period: Option<fn(T, Option<usize>) -> T>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing))]
+    sources: Vec<T>,
+}
+
+/// Serialized form of `BasicMulti`. The octave sources are rebuilt from the seed.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct BasicMultiConfig {
+    octaves: usize,
+    frequency: f64,
+    lacunarity: f64,
+    persistence: f64,
+    seed: u64,
+    #[serde(default)]
+    period: Option<usize>,
+}
+
+#[cfg(feature = "serde")]
+impl<T> From<BasicMultiConfig> for BasicMulti<T>
+where
+    T: Default + Seedable + Periodic,
+{
+    fn from(config: BasicMultiConfig) -> BasicMulti<T> {
+        BasicMulti::default()
+            .set_seed(config.seed)
+            .set_period(config.period)
+            .set_octaves(config.octaves)
+            .set_frequency(config.frequency)
+            .set_lacunarity(config.lacunarity)
+            .set_persistence(config.persistence)
+    }
 }
 
 impl BasicMulti {
     pub fn new() -> BasicMulti {
+        BasicMulti::default()
+    }
+}
+
+impl<T> Default for BasicMulti<T>
+where
+    T: Default + Seedable,
+{
+    fn default() -> Self {
         BasicMulti {
             seed: DEFAULT_BASICMULTI_SEED,
+            period: None,
+            set_source_period: None,
             octaves: DEFAULT_BASICMULTI_OCTAVES,
             frequency: DEFAULT_BASICMULTI_FREQUENCY,
             lacunarity: DEFAULT_BASICMULTI_LACUNARITY,
@@ -80,14 +134,27 @@ impl BasicMulti {
     }
 }
 
-impl Default for BasicMulti {
-    fn default() -> Self {
-        Self::new()
+impl<T> BasicMulti<T> {
+    /// Gives each octave its share of the period, if the noise has one.
+    fn set_octave_periods(self) -> Self {
+        BasicMulti {
+            sources: super::set_periods(
+                self.sources,
+                self.period,
+                self.set_source_period,
+                self.frequency,
+                self.lacunarity,
+            ),
+            ..self
+        }
     }
 }
 
-impl MultiFractal for BasicMulti {
-    fn set_octaves(self, mut octaves: usize) -> BasicMulti {
+impl<T> MultiFractal for BasicMulti<T>
+where
+    T: Default + Seedable,
+{
+    fn set_octaves(self, mut octaves: usize) -> Self {
         if self.octaves == octaves {
             return self;
         }
@@ -98,23 +165,26 @@ impl MultiFractal for BasicMulti {
             sources: super::build_sources(self.seed, octaves),
             ..self
         }
+        .set_octave_periods()
     }
 
-    fn set_frequency(self, frequency: f64) -> BasicMulti {
+    fn set_frequency(self, frequency: f64) -> Self {
         BasicMulti {
             frequency: frequency,
             ..self
         }
+        .set_octave_periods()
     }
 
-    fn set_lacunarity(self, lacunarity: f64) -> BasicMulti {
+    fn set_lacunarity(self, lacunarity: f64) -> Self {
         BasicMulti {
             lacunarity: lacunarity,
             ..self
         }
+        .set_octave_periods()
     }
 
-    fn set_persistence(self, persistence: f64) -> BasicMulti {
+    fn set_persistence(self, persistence: f64) -> Self {
         BasicMulti {
             persistence: persistence,
             ..self
@@ -122,8 +192,11 @@ impl MultiFractal for BasicMulti {
     }
 }
 
-impl Seedable for BasicMulti {
-    fn set_seed(self, seed: u32) -> BasicMulti {
+impl<T> Seedable for BasicMulti<T>
+where
+    T: Default + Seedable,
+{
+    fn set_seed(self, seed: u64) -> Self {
         if self.seed == seed {
             return self;
         }
@@ -133,15 +206,59 @@ impl Seedable for BasicMulti {
             sources: super::build_sources(seed, self.octaves),
             ..self
         }
+        .set_octave_periods()
     }
 
-    fn seed(&self) -> u32 {
+    fn seed(&self) -> u64 {
         self.seed
     }
 }
 
+impl<T> Periodic for BasicMulti<T>
+where
+    T: Default + Seedable + Periodic,
+{
+    /// Sets the period after which the noise repeats. Each octave is given the
+    /// period scaled by its frequency, so the product of the period and the
+    /// frequency of every octave should be a whole number, e.g. by using whole
+    /// number frequencies and lacunarities.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the period is `Some(0)`.
+    fn set_period(self, period: Option<usize>) -> Self {
+        assert!(
+            period != Some(0),
+            "the period of a noise function must not be zero"
+        );
+
+        BasicMulti {
+            period: period,
+            set_source_period: Some(T::set_period),
+            ..self
+        }
+        .set_octave_periods()
+    }
+
+    fn period(&self) -> Option<usize> {
+        self.period
+    }
+}
+
+impl<T> Reseedable for BasicMulti<T>
+where
+    T: Default + Seedable,
+{
+    fn reseed(self, seed: u64) -> BasicMulti<T> {
+        self.set_seed(seed)
+    }
+}
+
 /// 2-dimensional `BasicMulti` noise
-impl NoiseFn<Point2<f64>> for BasicMulti {
+impl<T> NoiseFn<Point2<f64>> for BasicMulti<T>
+where
+    T: NoiseFn<Point2<f64>>,
+{
     fn get(&self, mut point: Point2<f64>) -> f64 {
         // First unscaled octave of function; later octaves are scaled.
         point = math::mul2(point, self.frequency);
@@ -171,7 +288,10 @@ impl NoiseFn<Point2<f64>> for BasicMulti {
 }
 
 /// 3-dimensional `BasicMulti` noise
-impl NoiseFn<Point3<f64>> for BasicMulti {
+impl<T> NoiseFn<Point3<f64>> for BasicMulti<T>
+where
+    T: NoiseFn<Point3<f64>>,
+{
     fn get(&self, mut point: Point3<f64>) -> f64 {
         // First unscaled octave of function; later octaves are scaled.
         point = math::mul3(point, self.frequency);
@@ -201,7 +321,10 @@ impl NoiseFn<Point3<f64>> for BasicMulti {
 }
 
 /// 4-dimensional `BasicMulti` noise
-impl NoiseFn<Point4<f64>> for BasicMulti {
+impl<T> NoiseFn<Point4<f64>> for BasicMulti<T>
+where
+    T: NoiseFn<Point4<f64>>,
+{
     fn get(&self, mut point: Point4<f64>) -> f64 {
         // First unscaled octave of function; later octaves are scaled.
         point = math::mul4(point, self.frequency);
@@ -8,10 +8,10 @@
 
 use math;
 use math::{Point2, Point3, Point4};
-use noise_fns::{MultiFractal, NoiseFn, Perlin, Seedable};
+use noise_fns::{MultiFractal, NoiseFn, Periodic, Perlin, Reseedable, Seedable};
 
 /// Default noise seed for the Billow noise function.
-pub const DEFAULT_BILLOW_SEED: u32 = 0;
+pub const DEFAULT_BILLOW_SEED: u64 = 0;
 /// Default number of octaves for the Billow noise function.
 pub const DEFAULT_BILLOW_OCTAVE_COUNT: usize = 6;
 /// Default frequency for the Billow noise function.
@@ -30,8 +30,19 @@ pub const BILLOW_MAX_OCTAVES: usize = 32;
 /// This noise function is nearly identical to fBm noise, except this noise
 /// function modifes each octave with an absolute-value function. See the
 /// documentation for fBm for more information.
+///
+/// Like `Fbm`, the octaves are sampled from the `T` source function, which
+/// defaults to `Perlin`.
 #[derive(Clone, Debug)]
-pub struct Billow {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        from = "BillowConfig",
+        bound(deserialize = "T: Default + Seedable + Periodic")
+    )
+)]
+pub struct Billow<T = Perlin> {
     /// Total number of frequency octaves to generate the noise with.
     ///
     /// The number of octaves control the _amount of detail_ in the noise
@@ -60,14 +71,58 @@ pub struct Billow {
     /// persistence produces "rougher" noise.
     pub persistence: f64,
 
-    seed: u32,
-    sources: Vec<Perlin>,
+    seed: u64,
+    period: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing))]
+    set_source_period: Option<fn(T, Option<usize>) -> T>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing))]
+    sources: Vec<T>,
+}
+
+/// Serialized form of `Billow`. The octave sources are rebuilt from the seed.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct BillowConfig {
+    octaves: usize,
+    frequency: f64,
+    lacunarity: f64,
+    persistence: f64,
+    seed: u64,
+    #[serde(default)]
+    period: Option<usize>,
+}
+
+#[cfg(feature = "serde")]
+impl<T> From<BillowConfig> for Billow<T>
+where
+    T: Default + Seedable + Periodic,
+{
+    fn from(config: BillowConfig) -> Billow<T> {
+        Billow::default()
+            .set_seed(config.seed)
+            .set_period(config.period)
+            .set_octaves(config.octaves)
+            .set_frequency(config.frequency)
+            .set_lacunarity(config.lacunarity)
+            .set_persistence(config.persistence)
+    }
 }
 
 impl Billow {
     pub fn new() -> Billow {
+        Billow::default()
+    }
+}
+
+impl<T> Default for Billow<T>
+where
+    T: Default + Seedable,
+{
+    fn default() -> Self {
         Billow {
             seed: DEFAULT_BILLOW_SEED,
+            period: None,
+            set_source_period: None,
             octaves: DEFAULT_BILLOW_OCTAVE_COUNT,
             frequency: DEFAULT_BILLOW_FREQUENCY,
             lacunarity: DEFAULT_BILLOW_LACUNARITY,
@@ -77,14 +132,27 @@ impl Billow {
     }
 }
 
-impl Default for Billow {
-    fn default() -> Self {
-        Self::new()
+impl<T> Billow<T> {
+    /// Gives each octave its share of the period, if the noise has one.
+    fn set_octave_periods(self) -> Self {
+        Billow {
+            sources: super::set_periods(
+                self.sources,
+                self.period,
+                self.set_source_period,
+                self.frequency,
+                self.lacunarity,
+            ),
+            ..self
+        }
     }
 }
 
-impl MultiFractal for Billow {
-    fn set_octaves(self, mut octaves: usize) -> Billow {
+impl<T> MultiFractal for Billow<T>
+where
+    T: Default + Seedable,
+{
+    fn set_octaves(self, mut octaves: usize) -> Self {
         if self.octaves == octaves {
             return self;
         }
@@ -95,23 +163,26 @@ impl MultiFractal for Billow {
             sources: super::build_sources(self.seed, octaves),
             ..self
         }
+        .set_octave_periods()
     }
 
-    fn set_frequency(self, frequency: f64) -> Billow {
+    fn set_frequency(self, frequency: f64) -> Self {
         Billow {
             frequency: frequency,
             ..self
         }
+        .set_octave_periods()
     }
 
-    fn set_lacunarity(self, lacunarity: f64) -> Billow {
+    fn set_lacunarity(self, lacunarity: f64) -> Self {
         Billow {
             lacunarity: lacunarity,
             ..self
         }
+        .set_octave_periods()
     }
 
-    fn set_persistence(self, persistence: f64) -> Billow {
+    fn set_persistence(self, persistence: f64) -> Self {
         Billow {
             persistence: persistence,
             ..self
@@ -119,8 +190,11 @@ impl MultiFractal for Billow {
     }
 }
 
-impl Seedable for Billow {
-    fn set_seed(self, seed: u32) -> Billow {
+impl<T> Seedable for Billow<T>
+where
+    T: Default + Seedable,
+{
+    fn set_seed(self, seed: u64) -> Self {
         if self.seed == seed {
             return self;
         }
@@ -130,15 +204,59 @@ impl Seedable for Billow {
             sources: super::build_sources(seed, self.octaves),
             ..self
         }
+        .set_octave_periods()
     }
 
-    fn seed(&self) -> u32 {
+    fn seed(&self) -> u64 {
         self.seed
     }
 }
 
+impl<T> Periodic for Billow<T>
+where
+    T: Default + Seedable + Periodic,
+{
+    /// Sets the period after which the noise repeats. Each octave is given the
+    /// period scaled by its frequency, so the product of the period and the
+    /// frequency of every octave should be a whole number, e.g. by using whole
+    /// number frequencies and lacunarities.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the period is `Some(0)`.
+    fn set_period(self, period: Option<usize>) -> Self {
+        assert!(
+            period != Some(0),
+            "the period of a noise function must not be zero"
+        );
+
+        Billow {
+            period: period,
+            set_source_period: Some(T::set_period),
+            ..self
+        }
+        .set_octave_periods()
+    }
+
+    fn period(&self) -> Option<usize> {
+        self.period
+    }
+}
+
+impl<T> Reseedable for Billow<T>
+where
+    T: Default + Seedable,
+{
+    fn reseed(self, seed: u64) -> Billow<T> {
+        self.set_seed(seed)
+    }
+}
+
 /// 2-dimensional Billow noise
-impl NoiseFn<Point2<f64>> for Billow {
+impl<T> NoiseFn<Point2<f64>> for Billow<T>
+where
+    T: NoiseFn<Point2<f64>>,
+{
     fn get(&self, mut point: Point2<f64>) -> f64 {
         let mut result = 0.0;
 
@@ -168,7 +286,10 @@ impl NoiseFn<Point2<f64>> for Billow {
 }
 
 /// 3-dimensional Billow noise
-impl NoiseFn<Point3<f64>> for Billow {
+impl<T> NoiseFn<Point3<f64>> for Billow<T>
+where
+    T: NoiseFn<Point3<f64>>,
+{
     fn get(&self, mut point: Point3<f64>) -> f64 {
         let mut result = 0.0;
 
@@ -198,7 +319,10 @@ impl NoiseFn<Point3<f64>> for Billow {
 }
 
 /// 4-dimensional Billow noise
-impl NoiseFn<Point4<f64>> for Billow {
+impl<T> NoiseFn<Point4<f64>> for Billow<T>
+where
+    T: NoiseFn<Point4<f64>>,
+{
     fn get(&self, mut point: Point4<f64>) -> f64 {
         let mut result = 0.0;
 
@@ -226,3 +350,19 @@ impl NoiseFn<Point4<f64>> for Billow {
         result * 0.5
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use noise_fns::tests::assert_tiles;
+    use {Billow, MultiFractal, Periodic, Perlin};
+
+    #[test]
+    fn test_periodic_noise_tiles() {
+        let billow = Billow::<Perlin>::new()
+            .set_period(Some(6))
+            .set_frequency(0.5)
+            .set_octaves(4);
+        assert_eq!(billow.period(), Some(6));
+        assert_tiles(&billow, 6.0);
+    }
+}
@@ -7,11 +7,11 @@
 // except according to those terms.
 
 use math;
-use math::{Point2, Point3, Point4};
-use noise_fns::{MultiFractal, NoiseFn, Perlin, Seedable};
+use math::{Point2, Point3, Point4, Vector2, Vector3, Vector4};
+use noise_fns::{MultiFractal, NoiseFn, NoiseFnWithGradient, Periodic, Perlin, Reseedable, Seedable};
 
 /// Default noise seed for the fBm noise function.
-pub const DEFAULT_FBM_SEED: u32 = 0;
+pub const DEFAULT_FBM_SEED: u64 = 0;
 /// Default number of octaves for the fBm noise function.
 pub const DEFAULT_FBM_OCTAVE_COUNT: usize = 6;
 /// Default frequency for the fBm noise function.
@@ -39,8 +39,24 @@ pub const FBM_MAX_OCTAVES: usize = 32;
 /// and ever-decreasing amplitude.
 ///
 /// fBm is commonly referred to as Perlin noise.
+///
+/// Each octave is sampled from its own instance of the `T` source function,
+/// which defaults to `Perlin`. Any `Default + Seedable` noise function can be
+/// used instead, e.g. `Fbm::<OpenSimplex>::default()`. Octave `n` is seeded
+/// with `seed + n`.
+///
+/// If `T` provides gradients, so does `Fbm`; each octave's gradient is scaled
+/// by its amplitude and by the chain rule factor of its frequency.
 #[derive(Clone, Debug)]
-pub struct Fbm {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        from = "FbmConfig",
+        bound(deserialize = "T: Default + Seedable + Periodic")
+    )
+)]
+pub struct Fbm<T = Perlin> {
     /// Total number of frequency octaves to generate the noise with.
     ///
     /// The number of octaves control the _amount of detail_ in the noise
@@ -69,14 +85,58 @@ pub struct Fbm {
     /// persistence produces "rougher" noise.
     pub persistence: f64,
 
-    seed: u32,
-    sources: Vec<Perlin>,
+    seed: u64,
+    period: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing))]
+    set_source_period: Option<fn(T, Option<usize>) -> T>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing))]
+    sources: Vec<T>,
+}
+
+/// Serialized form of `Fbm`. The octave sources are rebuilt from the seed.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct FbmConfig {
+    octaves: usize,
+    frequency: f64,
+    lacunarity: f64,
+    persistence: f64,
+    seed: u64,
+    #[serde(default)]
+    period: Option<usize>,
+}
+
+#[cfg(feature = "serde")]
+impl<T> From<FbmConfig> for Fbm<T>
+where
+    T: Default + Seedable + Periodic,
+{
+    fn from(config: FbmConfig) -> Fbm<T> {
+        Fbm::default()
+            .set_seed(config.seed)
+            .set_period(config.period)
+            .set_octaves(config.octaves)
+            .set_frequency(config.frequency)
+            .set_lacunarity(config.lacunarity)
+            .set_persistence(config.persistence)
+    }
 }
 
 impl Fbm {
     pub fn new() -> Fbm {
+        Fbm::default()
+    }
+}
+
+impl<T> Default for Fbm<T>
+where
+    T: Default + Seedable,
+{
+    fn default() -> Self {
         Fbm {
             seed: DEFAULT_FBM_SEED,
+            period: None,
+            set_source_period: None,
             octaves: DEFAULT_FBM_OCTAVE_COUNT,
             frequency: DEFAULT_FBM_FREQUENCY,
             lacunarity: DEFAULT_FBM_LACUNARITY,
@@ -86,14 +146,27 @@ impl Fbm {
     }
 }
 
-impl Default for Fbm {
-    fn default() -> Self {
-        Self::new()
+impl<T> Fbm<T> {
+    /// Gives each octave its share of the period, if the noise has one.
+    fn set_octave_periods(self) -> Self {
+        Fbm {
+            sources: super::set_periods(
+                self.sources,
+                self.period,
+                self.set_source_period,
+                self.frequency,
+                self.lacunarity,
+            ),
+            ..self
+        }
     }
 }
 
-impl MultiFractal for Fbm {
-    fn set_octaves(self, mut octaves: usize) -> Fbm {
+impl<T> MultiFractal for Fbm<T>
+where
+    T: Default + Seedable,
+{
+    fn set_octaves(self, mut octaves: usize) -> Self {
         if self.octaves == octaves {
             return self;
         }
@@ -104,23 +177,26 @@ impl MultiFractal for Fbm {
             sources: super::build_sources(self.seed, octaves),
             ..self
         }
+        .set_octave_periods()
     }
 
-    fn set_frequency(self, frequency: f64) -> Fbm {
+    fn set_frequency(self, frequency: f64) -> Self {
         Fbm {
             frequency: frequency,
             ..self
         }
+        .set_octave_periods()
     }
 
-    fn set_lacunarity(self, lacunarity: f64) -> Fbm {
+    fn set_lacunarity(self, lacunarity: f64) -> Self {
         Fbm {
             lacunarity: lacunarity,
             ..self
         }
+        .set_octave_periods()
     }
 
-    fn set_persistence(self, persistence: f64) -> Fbm {
+    fn set_persistence(self, persistence: f64) -> Self {
         Fbm {
             persistence: persistence,
             ..self
@@ -128,8 +204,11 @@ impl MultiFractal for Fbm {
     }
 }
 
-impl Seedable for Fbm {
-    fn set_seed(self, seed: u32) -> Fbm {
+impl<T> Seedable for Fbm<T>
+where
+    T: Default + Seedable,
+{
+    fn set_seed(self, seed: u64) -> Self {
         if self.seed == seed {
             return self;
         }
@@ -139,15 +218,59 @@ impl Seedable for Fbm {
             sources: super::build_sources(seed, self.octaves),
             ..self
         }
+        .set_octave_periods()
     }
 
-    fn seed(&self) -> u32 {
+    fn seed(&self) -> u64 {
         self.seed
     }
 }
 
+impl<T> Periodic for Fbm<T>
+where
+    T: Default + Seedable + Periodic,
+{
+    /// Sets the period after which the noise repeats. Each octave is given the
+    /// period scaled by its frequency, so the product of the period and the
+    /// frequency of every octave should be a whole number, e.g. by using whole
+    /// number frequencies and lacunarities.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the period is `Some(0)`.
+    fn set_period(self, period: Option<usize>) -> Self {
+        assert!(
+            period != Some(0),
+            "the period of a noise function must not be zero"
+        );
+
+        Fbm {
+            period: period,
+            set_source_period: Some(T::set_period),
+            ..self
+        }
+        .set_octave_periods()
+    }
+
+    fn period(&self) -> Option<usize> {
+        self.period
+    }
+}
+
+impl<T> Reseedable for Fbm<T>
+where
+    T: Default + Seedable,
+{
+    fn reseed(self, seed: u64) -> Fbm<T> {
+        self.set_seed(seed)
+    }
+}
+
 /// 2-dimensional Fbm noise
-impl NoiseFn<Point2<f64>> for Fbm {
+impl<T> NoiseFn<Point2<f64>> for Fbm<T>
+where
+    T: NoiseFn<Point2<f64>>,
+{
     fn get(&self, mut point: Point2<f64>) -> f64 {
         let mut result = 0.0;
 
@@ -174,7 +297,10 @@ impl NoiseFn<Point2<f64>> for Fbm {
 }
 
 /// 3-dimensional Fbm noise
-impl NoiseFn<Point3<f64>> for Fbm {
+impl<T> NoiseFn<Point3<f64>> for Fbm<T>
+where
+    T: NoiseFn<Point3<f64>>,
+{
     fn get(&self, mut point: Point3<f64>) -> f64 {
         let mut result = 0.0;
 
@@ -201,7 +327,10 @@ impl NoiseFn<Point3<f64>> for Fbm {
 }
 
 /// 4-dimensional Fbm noise
-impl NoiseFn<Point4<f64>> for Fbm {
+impl<T> NoiseFn<Point4<f64>> for Fbm<T>
+where
+    T: NoiseFn<Point4<f64>>,
+{
     fn get(&self, mut point: Point4<f64>) -> f64 {
         let mut result = 0.0;
 
@@ -226,3 +355,134 @@ impl NoiseFn<Point4<f64>> for Fbm {
         result / scale
     }
 }
+
+/// 2-dimensional Fbm noise, with its analytic gradient
+impl<T> NoiseFnWithGradient<Point2<f64>> for Fbm<T>
+where
+    T: NoiseFnWithGradient<Point2<f64>>,
+{
+    fn get_with_gradient(&self, mut point: Point2<f64>) -> (f64, Vector2<f64>) {
+        let mut result = 0.0;
+        let mut gradient = [0.0; 2];
+        let mut frequency = self.frequency;
+
+        point = math::mul2(point, self.frequency);
+
+        for x in 0..self.octaves {
+            // Get the signal and its gradient with respect to the scaled point.
+            let (signal, signal_gradient) = self.sources[x].get_with_gradient(point);
+
+            // Scale the amplitude appropriately for this frequency. The
+            // gradient is also scaled by the frequency, since the octave was
+            // sampled at `point * frequency`.
+            let amplitude = self.persistence.powi(x as i32);
+            result += signal * amplitude;
+            gradient = math::add2(gradient, math::mul2(signal_gradient, amplitude * frequency));
+
+            // Increase the frequency for the next octave.
+            point = math::mul2(point, self.lacunarity);
+            frequency *= self.lacunarity;
+        }
+
+        // Scale and shift the result into the [-1,1] range
+        let scale = 2.0 - self.persistence.powi(self.octaves as i32 - 1);
+        (result / scale, math::mul2(gradient, 1.0 / scale))
+    }
+}
+
+/// 3-dimensional Fbm noise, with its analytic gradient
+impl<T> NoiseFnWithGradient<Point3<f64>> for Fbm<T>
+where
+    T: NoiseFnWithGradient<Point3<f64>>,
+{
+    fn get_with_gradient(&self, mut point: Point3<f64>) -> (f64, Vector3<f64>) {
+        let mut result = 0.0;
+        let mut gradient = [0.0; 3];
+        let mut frequency = self.frequency;
+
+        point = math::mul3(point, self.frequency);
+
+        for x in 0..self.octaves {
+            // Get the signal and its gradient with respect to the scaled point.
+            let (signal, signal_gradient) = self.sources[x].get_with_gradient(point);
+
+            // Scale the amplitude appropriately for this frequency. The
+            // gradient is also scaled by the frequency, since the octave was
+            // sampled at `point * frequency`.
+            let amplitude = self.persistence.powi(x as i32);
+            result += signal * amplitude;
+            gradient = math::add3(gradient, math::mul3(signal_gradient, amplitude * frequency));
+
+            // Increase the frequency for the next octave.
+            point = math::mul3(point, self.lacunarity);
+            frequency *= self.lacunarity;
+        }
+
+        // Scale and shift the result into the [-1,1] range
+        let scale = 2.0 - self.persistence.powi(self.octaves as i32 - 1);
+        (result / scale, math::mul3(gradient, 1.0 / scale))
+    }
+}
+
+/// 4-dimensional Fbm noise, with its analytic gradient
+impl<T> NoiseFnWithGradient<Point4<f64>> for Fbm<T>
+where
+    T: NoiseFnWithGradient<Point4<f64>>,
+{
+    fn get_with_gradient(&self, mut point: Point4<f64>) -> (f64, Vector4<f64>) {
+        let mut result = 0.0;
+        let mut gradient = [0.0; 4];
+        let mut frequency = self.frequency;
+
+        point = math::mul4(point, self.frequency);
+
+        for x in 0..self.octaves {
+            // Get the signal and its gradient with respect to the scaled point.
+            let (signal, signal_gradient) = self.sources[x].get_with_gradient(point);
+
+            // Scale the amplitude appropriately for this frequency. The
+            // gradient is also scaled by the frequency, since the octave was
+            // sampled at `point * frequency`.
+            let amplitude = self.persistence.powi(x as i32);
+            result += signal * amplitude;
+            gradient = math::add4(gradient, math::mul4(signal_gradient, amplitude * frequency));
+
+            // Increase the frequency for the next octave.
+            point = math::mul4(point, self.lacunarity);
+            frequency *= self.lacunarity;
+        }
+
+        // Scale and shift the result into the [-1,1] range
+        let scale = 2.0 - self.persistence.powi(self.octaves as i32 - 1);
+        (result / scale, math::mul4(gradient, 1.0 / scale))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use noise_fns::tests::assert_gradient_matches;
+    use noise_fns::{OpenSimplex, Value};
+
+    #[test]
+    fn test_fbm_over_other_sources() {
+        let perlin = Fbm::new();
+        let value = Fbm::<Value>::default().set_octaves(4);
+        let simplex = Fbm::<OpenSimplex>::default().set_seed(3);
+        assert_eq!((value.octaves, simplex.seed()), (4, 3));
+
+        for &point in &[[0.3, 1.7, -2.1], [4.2, -0.6, 8.9], [-7.5, 3.25, 0.4]] {
+            let expected = perlin.get(point);
+            assert!(value.get(point) != expected);
+            assert!(simplex.get(point) != expected);
+        }
+    }
+
+    #[test]
+    fn test_gradient_matches_finite_differences() {
+        let fbm = Fbm::<Value>::default().set_octaves(4);
+        assert_gradient_matches(&fbm, &[[1.3, -2.7], [-0.6, 8.2]]);
+        assert_gradient_matches(&fbm, &[[1.3, -2.7, 0.4], [-0.6, 8.2, -3.3]]);
+        assert_gradient_matches(&fbm, &[[1.3, -2.7, 0.4, 5.1], [-0.6, 8.2, -3.3, 0.9]]);
+    }
+}
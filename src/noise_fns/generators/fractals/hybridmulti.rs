@@ -8,10 +8,10 @@
 
 use math;
 use math::{Point2, Point3, Point4};
-use noise_fns::{MultiFractal, NoiseFn, Perlin, Seedable};
+use noise_fns::{MultiFractal, NoiseFn, Periodic, Perlin, Reseedable, Seedable};
 
 /// Default noise seed for the `HybridMulti` noise function.
-pub const DEFAULT_HYBRIDMULTI_SEED: u32 = 0;
+pub const DEFAULT_HYBRIDMULTI_SEED: u64 = 0;
 /// Default number of octaves for the `HybridMulti` noise function.
 pub const DEFAULT_HYBRIDMULTI_OCTAVES: usize = 6;
 /// Default frequency for the `HybridMulti` noise function.
@@ -27,8 +27,19 @@ pub const HYBRIDMULTI_MAX_OCTAVES: usize = 32;
 ///
 /// The result of this multifractal noise is that valleys in the noise should
 /// have smooth bottoms at all altitudes.
+///
+/// Like `Fbm`, the octaves are sampled from the `T` source function, which
+/// defaults to `Perlin`.
 #[derive(Clone, Debug)]
-pub struct HybridMulti {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        from = "HybridMultiConfig",
+        bound(deserialize = "T: Default + Seedable + Periodic")
+    )
+)]
+pub struct HybridMulti<T = Perlin> {
     /// Total number of frequency octaves to generate the noise with.
     ///
     /// The number of octaves control the _amount of detail_ in the noise
@@ -57,14 +68,58 @@ pub struct HybridMulti {
     /// persistence produces "rougher" noise.
     pub persistence: f64,
 
-    seed: u32,
-    sources: Vec<Perlin>,
+    seed: u64,
+    period: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing))]
+    set_source_period: Option<fn(T, Option<usize>) -> T>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing))]
+    sources: Vec<T>,
+}
+
+/// Serialized form of `HybridMulti`. The octave sources are rebuilt from the seed.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct HybridMultiConfig {
+    octaves: usize,
+    frequency: f64,
+    lacunarity: f64,
+    persistence: f64,
+    seed: u64,
+    #[serde(default)]
+    period: Option<usize>,
+}
+
+#[cfg(feature = "serde")]
+impl<T> From<HybridMultiConfig> for HybridMulti<T>
+where
+    T: Default + Seedable + Periodic,
+{
+    fn from(config: HybridMultiConfig) -> HybridMulti<T> {
+        HybridMulti::default()
+            .set_seed(config.seed)
+            .set_period(config.period)
+            .set_octaves(config.octaves)
+            .set_frequency(config.frequency)
+            .set_lacunarity(config.lacunarity)
+            .set_persistence(config.persistence)
+    }
 }
 
 impl HybridMulti {
     pub fn new() -> HybridMulti {
+        HybridMulti::default()
+    }
+}
+
+impl<T> Default for HybridMulti<T>
+where
+    T: Default + Seedable,
+{
+    fn default() -> Self {
         HybridMulti {
             seed: DEFAULT_HYBRIDMULTI_SEED,
+            period: None,
+            set_source_period: None,
             octaves: DEFAULT_HYBRIDMULTI_OCTAVES,
             frequency: DEFAULT_HYBRIDMULTI_FREQUENCY,
             lacunarity: DEFAULT_HYBRIDMULTI_LACUNARITY,
@@ -74,14 +129,27 @@ impl HybridMulti {
     }
 }
 
-impl Default for HybridMulti {
-    fn default() -> Self {
-        Self::new()
+impl<T> HybridMulti<T> {
+    /// Gives each octave its share of the period, if the noise has one.
+    fn set_octave_periods(self) -> Self {
+        HybridMulti {
+            sources: super::set_periods(
+                self.sources,
+                self.period,
+                self.set_source_period,
+                self.frequency,
+                self.lacunarity,
+            ),
+            ..self
+        }
     }
 }
 
-impl MultiFractal for HybridMulti {
-    fn set_octaves(self, mut octaves: usize) -> HybridMulti {
+impl<T> MultiFractal for HybridMulti<T>
+where
+    T: Default + Seedable,
+{
+    fn set_octaves(self, mut octaves: usize) -> Self {
         if self.octaves == octaves {
             return self;
         }
@@ -92,23 +160,26 @@ impl MultiFractal for HybridMulti {
             sources: super::build_sources(self.seed, octaves),
             ..self
         }
+        .set_octave_periods()
     }
 
-    fn set_frequency(self, frequency: f64) -> HybridMulti {
+    fn set_frequency(self, frequency: f64) -> Self {
         HybridMulti {
             frequency: frequency,
             ..self
         }
+        .set_octave_periods()
     }
 
-    fn set_lacunarity(self, lacunarity: f64) -> HybridMulti {
+    fn set_lacunarity(self, lacunarity: f64) -> Self {
         HybridMulti {
             lacunarity: lacunarity,
             ..self
         }
+        .set_octave_periods()
     }
 
-    fn set_persistence(self, persistence: f64) -> HybridMulti {
+    fn set_persistence(self, persistence: f64) -> Self {
         HybridMulti {
             persistence: persistence,
             ..self
@@ -116,8 +187,11 @@ impl MultiFractal for HybridMulti {
     }
 }
 
-impl Seedable for HybridMulti {
-    fn set_seed(self, seed: u32) -> HybridMulti {
+impl<T> Seedable for HybridMulti<T>
+where
+    T: Default + Seedable,
+{
+    fn set_seed(self, seed: u64) -> Self {
         if self.seed == seed {
             return self;
         }
@@ -127,15 +201,59 @@ impl Seedable for HybridMulti {
             sources: super::build_sources(seed, self.octaves),
             ..self
         }
+        .set_octave_periods()
     }
 
-    fn seed(&self) -> u32 {
+    fn seed(&self) -> u64 {
         self.seed
     }
 }
 
+impl<T> Periodic for HybridMulti<T>
+where
+    T: Default + Seedable + Periodic,
+{
+    /// Sets the period after which the noise repeats. Each octave is given the
+    /// period scaled by its frequency, so the product of the period and the
+    /// frequency of every octave should be a whole number, e.g. by using whole
+    /// number frequencies and lacunarities.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the period is `Some(0)`.
+    fn set_period(self, period: Option<usize>) -> Self {
+        assert!(
+            period != Some(0),
+            "the period of a noise function must not be zero"
+        );
+
+        HybridMulti {
+            period: period,
+            set_source_period: Some(T::set_period),
+            ..self
+        }
+        .set_octave_periods()
+    }
+
+    fn period(&self) -> Option<usize> {
+        self.period
+    }
+}
+
+impl<T> Reseedable for HybridMulti<T>
+where
+    T: Default + Seedable,
+{
+    fn reseed(self, seed: u64) -> HybridMulti<T> {
+        self.set_seed(seed)
+    }
+}
+
 /// 2-dimensional `HybridMulti` noise
-impl NoiseFn<Point2<f64>> for HybridMulti {
+impl<T> NoiseFn<Point2<f64>> for HybridMulti<T>
+where
+    T: NoiseFn<Point2<f64>>,
+{
     fn get(&self, mut point: Point2<f64>) -> f64 {
         // First unscaled octave of function; later octaves are scaled.
         point = math::mul2(point, self.frequency);
@@ -169,7 +287,10 @@ impl NoiseFn<Point2<f64>> for HybridMulti {
 }
 
 /// 3-dimensional `HybridMulti` noise
-impl NoiseFn<Point3<f64>> for HybridMulti {
+impl<T> NoiseFn<Point3<f64>> for HybridMulti<T>
+where
+    T: NoiseFn<Point3<f64>>,
+{
     fn get(&self, mut point: Point3<f64>) -> f64 {
         // First unscaled octave of function; later octaves are scaled.
         point = math::mul3(point, self.frequency);
@@ -203,7 +324,10 @@ impl NoiseFn<Point3<f64>> for HybridMulti {
 }
 
 /// 4-dimensional `HybridMulti` noise
-impl NoiseFn<Point4<f64>> for HybridMulti {
+impl<T> NoiseFn<Point4<f64>> for HybridMulti<T>
+where
+    T: NoiseFn<Point4<f64>>,
+{
     fn get(&self, mut point: Point4<f64>) -> f64 {
         // First unscaled octave of function; later octaves are scaled.
         point = math::mul4(point, self.frequency);
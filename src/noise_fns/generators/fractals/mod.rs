@@ -18,7 +18,7 @@ mod fbm;
 mod hybridmulti;
 mod ridgedmulti;
 
-use noise_fns::{Perlin, Seedable};
+use noise_fns::Seedable;
 
 /// Trait for `MultiFractal` functions
 pub trait MultiFractal {
@@ -31,10 +31,40 @@ pub trait MultiFractal {
     fn set_persistence(self, persistence: f64) -> Self;
 }
 
-fn build_sources(seed: u32, octaves: usize) -> Vec<Perlin> {
+fn build_sources<Source>(seed: u64, octaves: usize) -> Vec<Source>
+where
+    Source: Default + Seedable,
+{
     let mut sources = Vec::with_capacity(octaves);
     for x in 0..octaves {
-        sources.push(Perlin::new().set_seed(seed + x as u32));
+        sources.push(Source::default().set_seed(seed.wrapping_add(x as u64)));
     }
     sources
 }
+
+/// Gives each octave the period that makes the sum of the octaves repeat
+/// every `period` units, using `set_period`, which is `Periodic::set_period`
+/// of the source. Octave `n` is sampled at `frequency * lacunarity^n` times
+/// the input, so its period is scaled by the same factor. Without
+/// `set_period` the source is not periodic and is returned unchanged.
+fn set_periods<Source>(
+    sources: Vec<Source>,
+    period: Option<usize>,
+    set_period: Option<fn(Source, Option<usize>) -> Source>,
+    frequency: f64,
+    lacunarity: f64,
+) -> Vec<Source> {
+    let set_period = match set_period {
+        Some(set_period) => set_period,
+        None => return sources,
+    };
+
+    let mut scale = frequency;
+    let mut periodic = Vec::with_capacity(sources.len());
+    for source in sources {
+        let octave_period = period.map(|period| (period as f64 * scale).round().max(1.0) as usize);
+        periodic.push(set_period(source, octave_period));
+        scale *= lacunarity;
+    }
+    periodic
+}
@@ -8,10 +8,10 @@
 
 use math;
 use math::{Point2, Point3, Point4};
-use noise_fns::{MultiFractal, NoiseFn, Perlin, Seedable};
+use noise_fns::{MultiFractal, NoiseFn, Periodic, Perlin, Reseedable, Seedable};
 
 /// Default noise seed for the `RidgedMulti` noise function.
-pub const DEFAULT_RIDGED_SEED: u32 = 0;
+pub const DEFAULT_RIDGED_SEED: u64 = 0;
 /// Default number of octaves for the `RidgedMulti` noise function.
 pub const DEFAULT_RIDGED_OCTAVE_COUNT: usize = 6;
 /// Default frequency for the `RidgedMulti` noise function.
@@ -41,8 +41,19 @@ pub const RIDGED_MAX_OCTAVES: usize = 32;
 ///
 /// Ridged-multifractal noise is often used to generate craggy mountainous
 /// terrain or marble-like textures.
+///
+/// Like `Fbm`, the octaves are sampled from the `T` source function, which
+/// defaults to `Perlin`.
 #[derive(Clone, Debug)]
-pub struct RidgedMulti {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        from = "RidgedMultiConfig",
+        bound(deserialize = "T: Default + Seedable + Periodic")
+    )
+)]
+pub struct RidgedMulti<T = Perlin> {
     /// Total number of frequency octaves to generate the noise with.
     ///
     /// The number of octaves control the _amount of detail_ in the noise
@@ -77,39 +88,98 @@ pub struct RidgedMulti {
     /// half the height of the previous.
     pub attenuation: f64,
 
-    seed: u32,
-    sources: Vec<Perlin>,
+    seed: u64,
+    period: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing))]
+    set_source_period: Option<fn(T, Option<usize>) -> T>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing))]
+    sources: Vec<T>,
+}
+
+/// Serialized form of `RidgedMulti`. The octave sources are rebuilt from the seed.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct RidgedMultiConfig {
+    octaves: usize,
+    frequency: f64,
+    lacunarity: f64,
+    persistence: f64,
+    attenuation: f64,
+    seed: u64,
+    #[serde(default)]
+    period: Option<usize>,
+}
+
+#[cfg(feature = "serde")]
+impl<T> From<RidgedMultiConfig> for RidgedMulti<T>
+where
+    T: Default + Seedable + Periodic,
+{
+    fn from(config: RidgedMultiConfig) -> RidgedMulti<T> {
+        RidgedMulti::default()
+            .set_seed(config.seed)
+            .set_period(config.period)
+            .set_octaves(config.octaves)
+            .set_frequency(config.frequency)
+            .set_lacunarity(config.lacunarity)
+            .set_persistence(config.persistence)
+            .set_attenuation(config.attenuation)
+    }
 }
 
 impl RidgedMulti {
     pub fn new() -> RidgedMulti {
+        RidgedMulti::default()
+    }
+}
+
+impl<T> RidgedMulti<T> {
+    pub fn set_attenuation(self, attenuation: f64) -> Self {
         RidgedMulti {
-            seed: DEFAULT_RIDGED_SEED,
-            octaves: DEFAULT_RIDGED_OCTAVE_COUNT,
-            frequency: DEFAULT_RIDGED_FREQUENCY,
-            lacunarity: DEFAULT_RIDGED_LACUNARITY,
-            persistence: DEFAULT_RIDGED_PERSISTENCE,
-            attenuation: DEFAULT_RIDGED_ATTENUATION,
-            sources: super::build_sources(DEFAULT_RIDGED_SEED, DEFAULT_RIDGED_OCTAVE_COUNT),
+            attenuation: attenuation,
+            ..self
         }
     }
 
-    pub fn set_attenuation(self, attenuation: f64) -> RidgedMulti {
+    /// Gives each octave its share of the period, if the noise has one.
+    fn set_octave_periods(self) -> Self {
         RidgedMulti {
-            attenuation: attenuation,
+            sources: super::set_periods(
+                self.sources,
+                self.period,
+                self.set_source_period,
+                self.frequency,
+                self.lacunarity,
+            ),
             ..self
         }
     }
 }
 
-impl Default for RidgedMulti {
+impl<T> Default for RidgedMulti<T>
+where
+    T: Default + Seedable,
+{
     fn default() -> Self {
-        Self::new()
+        RidgedMulti {
+            seed: DEFAULT_RIDGED_SEED,
+            period: None,
+            set_source_period: None,
+            octaves: DEFAULT_RIDGED_OCTAVE_COUNT,
+            frequency: DEFAULT_RIDGED_FREQUENCY,
+            lacunarity: DEFAULT_RIDGED_LACUNARITY,
+            persistence: DEFAULT_RIDGED_PERSISTENCE,
+            attenuation: DEFAULT_RIDGED_ATTENUATION,
+            sources: super::build_sources(DEFAULT_RIDGED_SEED, DEFAULT_RIDGED_OCTAVE_COUNT),
+        }
     }
 }
 
-impl MultiFractal for RidgedMulti {
-    fn set_octaves(self, mut octaves: usize) -> RidgedMulti {
+impl<T> MultiFractal for RidgedMulti<T>
+where
+    T: Default + Seedable,
+{
+    fn set_octaves(self, mut octaves: usize) -> Self {
         if self.octaves == octaves {
             return self;
         }
@@ -120,23 +190,26 @@ impl MultiFractal for RidgedMulti {
             sources: super::build_sources(self.seed, octaves),
             ..self
         }
+        .set_octave_periods()
     }
 
-    fn set_frequency(self, frequency: f64) -> RidgedMulti {
+    fn set_frequency(self, frequency: f64) -> Self {
         RidgedMulti {
             frequency: frequency,
             ..self
         }
+        .set_octave_periods()
     }
 
-    fn set_lacunarity(self, lacunarity: f64) -> RidgedMulti {
+    fn set_lacunarity(self, lacunarity: f64) -> Self {
         RidgedMulti {
             lacunarity: lacunarity,
             ..self
         }
+        .set_octave_periods()
     }
 
-    fn set_persistence(self, persistence: f64) -> RidgedMulti {
+    fn set_persistence(self, persistence: f64) -> Self {
         RidgedMulti {
             persistence: persistence,
             ..self
@@ -144,8 +217,11 @@ impl MultiFractal for RidgedMulti {
     }
 }
 
-impl Seedable for RidgedMulti {
-    fn set_seed(self, seed: u32) -> RidgedMulti {
+impl<T> Seedable for RidgedMulti<T>
+where
+    T: Default + Seedable,
+{
+    fn set_seed(self, seed: u64) -> Self {
         if self.seed == seed {
             return self;
         }
@@ -155,15 +231,59 @@ impl Seedable for RidgedMulti {
             sources: super::build_sources(seed, self.octaves),
             ..self
         }
+        .set_octave_periods()
     }
 
-    fn seed(&self) -> u32 {
+    fn seed(&self) -> u64 {
         self.seed
     }
 }
 
+impl<T> Periodic for RidgedMulti<T>
+where
+    T: Default + Seedable + Periodic,
+{
+    /// Sets the period after which the noise repeats. Each octave is given the
+    /// period scaled by its frequency, so the product of the period and the
+    /// frequency of every octave should be a whole number, e.g. by using whole
+    /// number frequencies and lacunarities.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the period is `Some(0)`.
+    fn set_period(self, period: Option<usize>) -> Self {
+        assert!(
+            period != Some(0),
+            "the period of a noise function must not be zero"
+        );
+
+        RidgedMulti {
+            period: period,
+            set_source_period: Some(T::set_period),
+            ..self
+        }
+        .set_octave_periods()
+    }
+
+    fn period(&self) -> Option<usize> {
+        self.period
+    }
+}
+
+impl<T> Reseedable for RidgedMulti<T>
+where
+    T: Default + Seedable,
+{
+    fn reseed(self, seed: u64) -> RidgedMulti<T> {
+        self.set_seed(seed)
+    }
+}
+
 /// 2-dimensional `RidgedMulti` noise
-impl NoiseFn<Point2<f64>> for RidgedMulti {
+impl<T> NoiseFn<Point2<f64>> for RidgedMulti<T>
+where
+    T: NoiseFn<Point2<f64>>,
+{
     fn get(&self, mut point: Point2<f64>) -> f64 {
         let mut result = 0.0;
         let mut weight = 1.0;
@@ -209,7 +329,10 @@ impl NoiseFn<Point2<f64>> for RidgedMulti {
 }
 
 /// 3-dimensional `RidgedMulti` noise
-impl NoiseFn<Point3<f64>> for RidgedMulti {
+impl<T> NoiseFn<Point3<f64>> for RidgedMulti<T>
+where
+    T: NoiseFn<Point3<f64>>,
+{
     fn get(&self, mut point: Point3<f64>) -> f64 {
         let mut result = 0.0;
         let mut weight = 1.0;
@@ -255,7 +378,10 @@ impl NoiseFn<Point3<f64>> for RidgedMulti {
 }
 
 /// 4-dimensional `RidgedMulti` noise
-impl NoiseFn<Point4<f64>> for RidgedMulti {
+impl<T> NoiseFn<Point4<f64>> for RidgedMulti<T>
+where
+    T: NoiseFn<Point4<f64>>,
+{
     fn get(&self, mut point: Point4<f64>) -> f64 {
         let mut result = 0.0;
         let mut weight = 1.0;
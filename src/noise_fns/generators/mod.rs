@@ -10,6 +10,7 @@ pub use self::checkerboard::*;
 pub use self::constant::*;
 pub use self::cylinders::*;
 pub use self::fractals::*;
+pub use self::noise_map_source::*;
 pub use self::open_simplex::*;
 pub use self::perlin::*;
 pub use self::super_simplex::*;
@@ -20,6 +21,7 @@ mod constant;
 mod checkerboard;
 mod cylinders;
 mod fractals;
+mod noise_map_source;
 mod open_simplex;
 mod perlin;
 mod super_simplex;
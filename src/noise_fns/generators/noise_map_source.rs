@@ -0,0 +1,292 @@
+// Copyright (c) 2017 The Noise-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. All files in the
+// project carrying such notice may not be copied, modified, or distributed
+// except according to those terms.
+
+use math::{interp, Point2, Point3};
+use noise_fns::{NoiseFn, Reseedable};
+use utils::NoiseMap;
+
+/// Default lower bound of the x and y coordinates covered by the map.
+pub const DEFAULT_NOISE_MAP_SOURCE_LOWER_BOUND: f64 = -1.0;
+
+/// Default upper bound of the x and y coordinates covered by the map.
+pub const DEFAULT_NOISE_MAP_SOURCE_UPPER_BOUND: f64 = 1.0;
+
+/// How a `NoiseMapSource` samples between the values of its map.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Sampling {
+    /// Returns the value nearest to the point.
+    Nearest,
+
+    /// Interpolates linearly between the four values around the point.
+    Bilinear,
+
+    /// Interpolates cubically between the sixteen values around the point.
+    Bicubic,
+}
+
+/// How a `NoiseMapSource` samples points beyond the edges of its map.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum EdgeMode {
+    /// Repeats the values at the edges of the map.
+    Clamp,
+
+    /// Tiles the map, continuing at the opposite edge.
+    Wrap,
+
+    /// Tiles the map, reflecting it at each edge.
+    Mirror,
+}
+
+/// Noise function that outputs the values of a `NoiseMap`.
+///
+/// The map covers the area between the x and y bounds, which by default match
+/// those of `PlaneMapBuilder`, so that a map built from a plane can be sampled
+/// at the same points it was built from. The z coordinate of 3D points is
+/// ignored.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NoiseMapSource {
+    /// Map to sample.
+    pub map: NoiseMap,
+
+    /// How to sample between the values of the map. Default is bilinear.
+    pub sampling: Sampling,
+
+    /// How to sample beyond the edges of the map. Default is clamp.
+    pub edge_mode: EdgeMode,
+
+    /// Bounds of the x coordinates covered by the map. Default is -1.0 to 1.0.
+    pub x_bounds: (f64, f64),
+
+    /// Bounds of the y coordinates covered by the map. Default is -1.0 to 1.0.
+    pub y_bounds: (f64, f64),
+}
+
+impl NoiseMapSource {
+    pub fn new(map: NoiseMap) -> NoiseMapSource {
+        NoiseMapSource {
+            map: map,
+            sampling: Sampling::Bilinear,
+            edge_mode: EdgeMode::Clamp,
+            x_bounds: (
+                DEFAULT_NOISE_MAP_SOURCE_LOWER_BOUND,
+                DEFAULT_NOISE_MAP_SOURCE_UPPER_BOUND,
+            ),
+            y_bounds: (
+                DEFAULT_NOISE_MAP_SOURCE_LOWER_BOUND,
+                DEFAULT_NOISE_MAP_SOURCE_UPPER_BOUND,
+            ),
+        }
+    }
+
+    /// Creates a source from a grid of the given size, stored row by row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` does not hold `width * height` values.
+    pub fn from_values(width: usize, height: usize, values: &[f64]) -> NoiseMapSource {
+        let mut map = NoiseMap::new(width, height);
+        map.values_mut().copy_from_slice(values);
+        Self::new(map)
+    }
+
+    pub fn set_sampling(self, sampling: Sampling) -> NoiseMapSource {
+        NoiseMapSource {
+            sampling: sampling,
+            ..self
+        }
+    }
+
+    pub fn set_edge_mode(self, edge_mode: EdgeMode) -> NoiseMapSource {
+        NoiseMapSource {
+            edge_mode: edge_mode,
+            ..self
+        }
+    }
+
+    pub fn set_x_bounds(self, lower_x_bound: f64, upper_x_bound: f64) -> NoiseMapSource {
+        NoiseMapSource {
+            x_bounds: (lower_x_bound, upper_x_bound),
+            ..self
+        }
+    }
+
+    pub fn set_y_bounds(self, lower_y_bound: f64, upper_y_bound: f64) -> NoiseMapSource {
+        NoiseMapSource {
+            y_bounds: (lower_y_bound, upper_y_bound),
+            ..self
+        }
+    }
+
+    /// Returns the value of the map at the given column and row, resolving
+    /// indices outside of the map with the edge mode.
+    fn value(&self, x: isize, y: isize) -> f64 {
+        let (width, height) = self.map.size();
+        self.map.get_value(
+            resolve(x, width, self.edge_mode),
+            resolve(y, height, self.edge_mode),
+        )
+    }
+
+    fn sample(&self, x: f64, y: f64) -> f64 {
+        let (width, height) = self.map.size();
+        if width == 0 || height == 0 {
+            return self.map.border_value;
+        }
+
+        // Position of the point in the map, in pixels.
+        let x = (x - self.x_bounds.0) / (self.x_bounds.1 - self.x_bounds.0) * width as f64;
+        let y = (y - self.y_bounds.0) / (self.y_bounds.1 - self.y_bounds.0) * height as f64;
+        let x = reduce(x, width, self.edge_mode);
+        let y = reduce(y, height, self.edge_mode);
+
+        match self.sampling {
+            Sampling::Nearest => self.value(x.round() as isize, y.round() as isize),
+            Sampling::Bilinear => {
+                let (x0, y0) = (x.floor(), y.floor());
+                let (x0i, y0i) = (x0 as isize, y0 as isize);
+                let row = |y| interp::linear(self.value(x0i, y), self.value(x0i + 1, y), x - x0);
+                interp::linear(row(y0i), row(y0i + 1), y - y0)
+            },
+            Sampling::Bicubic => {
+                let (x0, y0) = (x.floor(), y.floor());
+                let (x0i, y0i) = (x0 as isize, y0 as isize);
+                let row = |y| {
+                    interp::cubic(
+                        self.value(x0i - 1, y),
+                        self.value(x0i, y),
+                        self.value(x0i + 1, y),
+                        self.value(x0i + 2, y),
+                        x - x0,
+                    )
+                };
+                interp::cubic(row(y0i - 1), row(y0i), row(y0i + 1), row(y0i + 2), y - y0)
+            },
+        }
+    }
+}
+
+/// Moves a position along an axis of the given size, in pixels, to one that
+/// samples the same pixels, but is close enough to the axis to be converted
+/// to an index and offset by the sampling without overflowing.
+fn reduce(position: f64, size: usize, edge_mode: EdgeMode) -> f64 {
+    let size = size as f64;
+    match edge_mode {
+        EdgeMode::Clamp => position.max(-2.0).min(size + 2.0),
+        EdgeMode::Wrap => position.rem_euclid(size),
+        EdgeMode::Mirror => position.rem_euclid(2.0 * size),
+    }
+}
+
+/// Maps an index along an axis of the given size onto the axis.
+fn resolve(index: isize, size: usize, edge_mode: EdgeMode) -> usize {
+    let size = size as isize;
+    let index = match edge_mode {
+        EdgeMode::Clamp => index.max(0).min(size - 1),
+        EdgeMode::Wrap => index.rem_euclid(size),
+        EdgeMode::Mirror => {
+            let index = index.rem_euclid(2 * size);
+            if index < size {
+                index
+            } else {
+                2 * size - 1 - index
+            }
+        },
+    };
+
+    index as usize
+}
+
+/// `NoiseMapSource` has no seed, so reseeding leaves it unchanged.
+impl Reseedable for NoiseMapSource {
+    fn reseed(self, _seed: u64) -> NoiseMapSource {
+        self
+    }
+}
+
+impl NoiseFn<Point2<f64>> for NoiseMapSource {
+    fn get(&self, point: Point2<f64>) -> f64 {
+        self.sample(point[0], point[1])
+    }
+}
+
+impl NoiseFn<Point3<f64>> for NoiseMapSource {
+    fn get(&self, point: Point3<f64>) -> f64 {
+        self.sample(point[0], point[1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utils::{NoiseMapBuilder, PlaneMapBuilder};
+    use Perlin;
+
+    #[test]
+    fn test_noise_map_source_sampling() {
+        let map = PlaneMapBuilder::new(Perlin::new()).set_size(8, 8).build();
+        let source = NoiseMapSource::new(map.clone());
+
+        // Sampling the points the map was built from returns its values in
+        // every mode.
+        for &sampling in &[Sampling::Nearest, Sampling::Bilinear, Sampling::Bicubic] {
+            let source = source.clone().set_sampling(sampling);
+            let rebuilt = PlaneMapBuilder::new(&source).set_size(8, 8).build();
+            for (a, b) in map.values().iter().zip(rebuilt.values()) {
+                assert!((a - b).abs() < 1e-12);
+            }
+        }
+
+        let ramp = NoiseMapSource::from_values(4, 1, &[0.0, 1.0, 2.0, 3.0])
+            .set_x_bounds(0.0, 4.0)
+            .set_y_bounds(0.0, 1.0);
+        assert_eq!(ramp.get([1.5, 0.0]), 1.5);
+        assert!((ramp.get([1.4, 0.0, 9.0]) - 1.4).abs() < 1e-12);
+
+        let beyond = [5.0, 0.0];
+        assert_eq!(ramp.clone().get(beyond), 3.0);
+        assert_eq!(ramp.clone().set_edge_mode(EdgeMode::Wrap).get(beyond), 1.0);
+        assert_eq!(
+            ramp.clone().set_edge_mode(EdgeMode::Mirror).get(beyond),
+            2.0
+        );
+        assert_eq!(
+            ramp.set_sampling(Sampling::Nearest)
+                .set_edge_mode(EdgeMode::Mirror)
+                .get([-1.0, 0.0]),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_noise_map_source_far_out_of_range() {
+        let ramp = NoiseMapSource::from_values(4, 1, &[0.0, 1.0, 2.0, 3.0])
+            .set_x_bounds(0.0, 4.0)
+            .set_y_bounds(0.0, 1.0);
+
+        for &sampling in &[Sampling::Nearest, Sampling::Bilinear, Sampling::Bicubic] {
+            for &edge_mode in &[EdgeMode::Clamp, EdgeMode::Wrap, EdgeMode::Mirror] {
+                let source = ramp.clone().set_sampling(sampling).set_edge_mode(edge_mode);
+                for &x in &[1e300, -1e300, 1e20, -1e20] {
+                    let value = source.get([x, 0.0]);
+                    assert!((0.0..=3.0).contains(&value));
+                }
+                assert_eq!(source.get([2.0, 1e300]), source.get([2.0, 0.5]));
+            }
+
+            let clamped = ramp.clone().set_sampling(sampling);
+            assert_eq!(clamped.get([1e300, 0.0]), 3.0);
+            assert_eq!(clamped.get([-1e300, 0.0]), 0.0);
+        }
+
+        let wrapped = ramp.set_edge_mode(EdgeMode::Wrap);
+        assert_eq!(wrapped.get([1.5 + 4e6, 0.0]), wrapped.get([1.5, 0.0]));
+    }
+}
@@ -11,9 +11,9 @@
 //! http://uniblock.tumblr.com/post/97868843242/noise
 
 use {gradient, math};
-use math::{Point2, Point3, Point4};
-use noise_fns::{NoiseFn, Seedable};
-use permutationtable::PermutationTable;
+use math::{Point2, Point3, Point4, Vector2, Vector3, Vector4};
+use noise_fns::{NoiseFn, NoiseFnWithGradient, Reseedable, Seedable};
+use permutationtable::{DEFAULT_LATTICE_MODE, LatticeMode, PermutationTable};
 use std::ops::Add;
 
 const STRETCH_CONSTANT_2D: f64 = -0.211324865405187; //(1/sqrt(2+1)-1)/2;
@@ -27,22 +27,85 @@ const NORM_CONSTANT_2D: f64 = 1.0 / 14.0;
 const NORM_CONSTANT_3D: f64 = 1.0 / 14.0;
 const NORM_CONSTANT_4D: f64 = 1.0 / 6.8699090070956625;
 
-pub const DEFAULT_OPENSIMPLEX_SEED: u32 = 0;
+pub const DEFAULT_OPENSIMPLEX_SEED: u64 = 0;
 
 /// Noise function that outputs 2/3/4-dimensional Open Simplex noise.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "OpenSimplexConfig"))]
 pub struct OpenSimplex {
-    seed: u32,
+    seed: u64,
+    lattice_mode: LatticeMode,
+    #[cfg_attr(
+        feature = "serde",
+        serde(skip_serializing_if = "PermutationTable::is_seeded")
+    )]
     perm_table: PermutationTable,
 }
 
+/// Serialized form of `OpenSimplex`. The permutation table is
+/// rebuilt from the seed, unless a custom table was serialized.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct OpenSimplexConfig {
+    seed: u64,
+    #[serde(default)]
+    lattice_mode: LatticeMode,
+    // Only present for a custom permutation table.
+    perm_table: Option<PermutationTable>,
+}
+
+#[cfg(feature = "serde")]
+impl From<OpenSimplexConfig> for OpenSimplex {
+    fn from(config: OpenSimplexConfig) -> OpenSimplex {
+        let noise = OpenSimplex::new()
+            .set_lattice_mode(config.lattice_mode)
+            .set_seed(config.seed);
+
+        match config.perm_table {
+            Some(perm_table) => noise.set_permutation_table(perm_table),
+            None => noise,
+        }
+    }
+}
+
 impl OpenSimplex {
     pub fn new() -> OpenSimplex {
         OpenSimplex {
             seed: DEFAULT_OPENSIMPLEX_SEED,
+            lattice_mode: DEFAULT_LATTICE_MODE,
             perm_table: PermutationTable::new(DEFAULT_OPENSIMPLEX_SEED),
         }
     }
+
+    /// Replaces the permutation table used to hash the lattice points, e.g.
+    /// with one built by `PermutationTable::from_rng` or
+    /// `PermutationTable::from_permutation`.
+    ///
+    /// The seed and lattice mode are left as they were. Setting a different
+    /// seed afterwards rebuilds the table from the seed. The table is
+    /// serialized along with the noise function.
+    pub fn set_permutation_table(self, perm_table: PermutationTable) -> OpenSimplex {
+        OpenSimplex {
+            perm_table: perm_table.set_mode(self.lattice_mode),
+            ..self
+        }
+    }
+
+    /// Sets how the lattice points are hashed. `LatticeMode::Hash` avoids the
+    /// noise repeating every 256 units.
+    pub fn set_lattice_mode(self, lattice_mode: LatticeMode) -> OpenSimplex {
+        OpenSimplex {
+            lattice_mode: lattice_mode,
+            perm_table: self.perm_table.set_mode(lattice_mode),
+            ..self
+        }
+    }
+
+    /// Returns how the lattice points are hashed.
+    pub fn lattice_mode(&self) -> LatticeMode {
+        self.lattice_mode
+    }
 }
 
 impl Default for OpenSimplex {
@@ -53,7 +116,7 @@ impl Default for OpenSimplex {
 
 impl Seedable for OpenSimplex {
     /// Sets the seed value for Open Simplex noise
-    fn set_seed(self, seed: u32) -> OpenSimplex {
+    fn set_seed(self, seed: u64) -> OpenSimplex {
         // If the new seed is the same as the current seed, just return self.
         if self.seed == seed {
             return self;
@@ -62,94 +125,188 @@ impl Seedable for OpenSimplex {
         // Otherwise, regenerate the permutation table based on the new seed.
         OpenSimplex {
             seed: seed,
-            perm_table: PermutationTable::new(seed),
+            perm_table: PermutationTable::new(seed).set_mode(self.lattice_mode),
+            ..self
         }
     }
 
-    fn seed(&self) -> u32 {
+    fn seed(&self) -> u64 {
         self.seed
     }
 }
 
+impl Reseedable for OpenSimplex {
+    fn reseed(self, seed: u64) -> OpenSimplex {
+        self.set_seed(seed)
+    }
+}
+
 /// 2-dimensional [`OpenSimplex` Noise](http://uniblock.tumblr.com/post/97868843242/noise)
 ///
 /// This is a slower but higher quality form of gradient noise than `Perlin` 2D.
 impl NoiseFn<Point2<f64>> for OpenSimplex {
     fn get(&self, point: Point2<f64>) -> f64 {
-        #[inline(always)]
-        fn gradient(perm_table: &PermutationTable, vertex: Point2<f64>, pos: Point2<f64>) -> f64 {
-            let attn = 2.0 - math::dot2(pos, pos);
-            if attn > 0.0 {
-                let index = perm_table.get2(math::to_isize2(vertex));
-                let vec = gradient::get2(index);
-                attn.powi(4) * math::dot2(pos, vec)
-            } else {
-                0.0
-            }
-        }
-
-        // Place input coordinates onto grid.
-        let stretch_offset = math::fold2(point, Add::add) * STRETCH_CONSTANT_2D;
-        let stretched = math::map2(point, |v| v + stretch_offset);
-
-        // Floor to get grid coordinates of rhombus (stretched square) cell origin.
-        let stretched_floor = math::map2(stretched, f64::floor);
+        let mut value = 0.0;
+        open_simplex_2d(point, |vertex, pos| {
+            value += surflet2(self.perm_table.get2(math::to_isize2(vertex)), pos);
+        });
 
-        // Skew out to get actual coordinates of rhombus origin. We'll need these later.
-        let squish_offset = math::fold2(stretched_floor, Add::add) * SQUISH_CONSTANT_2D;
-        let skewed_floor = math::map2(stretched_floor, |v| v + squish_offset);
+        value * NORM_CONSTANT_2D
+    }
 
-        // Compute grid coordinates relative to rhombus origin.
-        let rel_coords = math::sub2(stretched, stretched_floor);
+    fn get_many(&self, points: &[Point2<f64>], out: &mut [f64]) {
+        assert_eq!(points.len(), out.len());
+
+        // Every vertex visited lies on a corner of the stretched cell, so hash
+        // all of its corners once and reuse them while the cell is unchanged.
+        let mut cell = None;
+        let mut corners = [0; 4];
+        let mut corners_of = |stretched_floor: Point2<f64>| {
+            if cell != Some(stretched_floor) {
+                corners = self.perm_table.corners2(math::to_isize2(stretched_floor));
+                cell = Some(stretched_floor);
+            }
+            corners
+        };
+
+        for (&point, out) in points.iter().zip(out.iter_mut()) {
+            let stretched_floor = stretched_floor2(point);
+            let corners = corners_of(stretched_floor);
+
+            let mut value = 0.0;
+            open_simplex_2d(point, |vertex, pos| {
+                value += surflet2(corners[corner_index2(stretched_floor, vertex)], pos);
+            });
+            *out = value * NORM_CONSTANT_2D;
+        }
+    }
+}
 
-        // Sum those together to get a value that determines which region we're in.
-        let region_sum = math::fold2(rel_coords, Add::add);
+/// 2-dimensional [`OpenSimplex` Noise](http://uniblock.tumblr.com/post/97868843242/noise),
+/// with its analytic gradient
+impl NoiseFnWithGradient<Point2<f64>> for OpenSimplex {
+    fn get_with_gradient(&self, point: Point2<f64>) -> (f64, Vector2<f64>) {
+        let mut value = 0.0;
+        let mut gradient = [0.0; 2];
+        open_simplex_2d(point, |vertex, pos| {
+            let (surflet_value, surflet_gradient) =
+                surflet_with_gradient2(self.perm_table.get2(math::to_isize2(vertex)), pos);
+            value += surflet_value;
+            gradient = math::add2(gradient, surflet_gradient);
+        });
+
+        (value * NORM_CONSTANT_2D, math::mul2(gradient, NORM_CONSTANT_2D))
+    }
+}
 
-        // Positions relative to origin point (0, 0).
-        let pos0 = math::sub2(point, skewed_floor);
+/// Floor of `point` placed onto the stretched grid, matching the
+/// `stretched_floor` of `open_simplex_2d`.
+#[inline(always)]
+fn stretched_floor2(point: Point2<f64>) -> Point2<f64> {
+    let stretch_offset = math::fold2(point, Add::add) * STRETCH_CONSTANT_2D;
+    math::map2(point, |v| (v + stretch_offset).floor())
+}
 
-        let mut value = 0.0;
+/// Index of `vertex`, one of the corners of `cell`, into the result of
+/// `PermutationTable::corners2`.
+#[inline(always)]
+fn corner_index2(cell: Point2<f64>, vertex: Point2<f64>) -> usize {
+    (vertex[0] != cell[0]) as usize |
+        ((vertex[1] != cell[1]) as usize) << 1
+}
 
-        let mut vertex;
-        let mut dpos;
+/// Visits each lattice vertex that contributes to the 2-dimensional noise
+/// value at `point`, passing the vertex and the position of `point` relative to
+/// it to `contribute`.
+#[inline(always)]
+fn open_simplex_2d<F>(point: Point2<f64>, mut contribute: F)
+where
+    F: FnMut(Point2<f64>, Point2<f64>),
+{
+    // Place input coordinates onto grid.
+    let stretch_offset = math::fold2(point, Add::add) * STRETCH_CONSTANT_2D;
+    let stretched = math::map2(point, |v| v + stretch_offset);
+
+    // Floor to get grid coordinates of rhombus (stretched square) cell origin.
+    let stretched_floor = math::map2(stretched, f64::floor);
+
+    // Skew out to get actual coordinates of rhombus origin. We'll need these later.
+    let squish_offset = math::fold2(stretched_floor, Add::add) * SQUISH_CONSTANT_2D;
+    let skewed_floor = math::map2(stretched_floor, |v| v + squish_offset);
+
+    // Compute grid coordinates relative to rhombus origin.
+    let rel_coords = math::sub2(stretched, stretched_floor);
+
+    // Sum those together to get a value that determines which region we're in.
+    let region_sum = math::fold2(rel_coords, Add::add);
+
+    // Positions relative to origin point (0, 0).
+    let pos0 = math::sub2(point, skewed_floor);
+
+    let mut vertex;
+    let mut dpos;
+
+    // (0, 0) --- (1, 0)
+    // |   A     /     |
+    // |       /       |
+    // |     /     B   |
+    // (0, 1) --- (1, 1)
+
+    let t0 = SQUISH_CONSTANT_2D;
+    let t1 = SQUISH_CONSTANT_2D + 1.0;
+    let t2 = SQUISH_CONSTANT_2D + t1;
+
+    // Contribution (1, 0)
+    vertex = math::add2(stretched_floor, [1.0, 0.0]);
+    dpos = math::sub2(pos0, [t1, t0]);
+    contribute(vertex, dpos);
+
+    // Contribution (0, 1)
+    vertex = math::add2(stretched_floor, [0.0, 1.0]);
+    dpos = math::sub2(pos0, [t0, t1]);
+    contribute(vertex, dpos);
+
+    // See the graph for an intuitive explanation; the sum of `x` and `y` is
+    // only greater than `1` if we're on Region B.
+    if region_sum > 1.0 {
+        // Contribution (1, 1)
+        vertex = math::add2(stretched_floor, [1.0, 1.0]);
+        // We are moving across the diagonal `/`, so we'll need to add by the
+        // squish constant
+        dpos = math::sub2(pos0, [t2, t2]);
+    } else {
+        vertex = math::add2(stretched_floor, [0.0, 0.0]);
+        dpos = math::sub2(pos0, [0.0, 0.0]);
+    }
 
-        // (0, 0) --- (1, 0)
-        // |   A     /     |
-        // |       /       |
-        // |     /     B   |
-        // (0, 1) --- (1, 1)
+    // Point (0, 0) or (1, 1)
+    contribute(vertex, dpos);
+}
 
-        let t0 = SQUISH_CONSTANT_2D;
-        let t1 = SQUISH_CONSTANT_2D + 1.0;
-        let t2 = SQUISH_CONSTANT_2D + t1;
-
-        // Contribution (1, 0)
-        vertex = math::add2(stretched_floor, [1.0, 0.0]);
-        dpos = math::sub2(pos0, [t1, t0]);
-        value += gradient(&self.perm_table, vertex, dpos);
-
-        // Contribution (0, 1)
-        vertex = math::add2(stretched_floor, [0.0, 1.0]);
-        dpos = math::sub2(pos0, [t0, t1]);
-        value += gradient(&self.perm_table, vertex, dpos);
-
-        // See the graph for an intuitive explanation; the sum of `x` and `y` is
-        // only greater than `1` if we're on Region B.
-        if region_sum > 1.0 {
-            // Contribution (1, 1)
-            vertex = math::add2(stretched_floor, [1.0, 1.0]);
-            // We are moving across the diagonal `/`, so we'll need to add by the
-            // squish constant
-            dpos = math::sub2(pos0, [t2, t2]);
-        } else {
-            vertex = math::add2(stretched_floor, [0.0, 0.0]);
-            dpos = math::sub2(pos0, [0.0, 0.0]);
-        }
-
-        // Point (0, 0) or (1, 1)
-        value += gradient(&self.perm_table, vertex, dpos);
+#[inline(always)]
+fn surflet2(hash: usize, pos: Point2<f64>) -> f64 {
+    let attn = 2.0 - math::dot2(pos, pos);
+    if attn > 0.0 {
+        let vec = gradient::get2(hash);
+        attn.powi(4) * math::dot2(pos, vec)
+    } else {
+        0.0
+    }
+}
 
-        value * NORM_CONSTANT_2D
+#[inline(always)]
+fn surflet_with_gradient2(hash: usize, pos: Point2<f64>) -> (f64, Vector2<f64>) {
+    let attn = 2.0 - math::dot2(pos, pos);
+    if attn > 0.0 {
+        let vec = gradient::get2(hash);
+        let dot = math::dot2(pos, vec);
+
+        // d(attn^4 * dot) = attn^4 * vec - 8 * attn^3 * dot * pos
+        let derivative = math::add2(math::mul2(vec, attn.powi(4)),
+                                     math::mul2(pos, -8.0 * attn.powi(3) * dot));
+        (attn.powi(4) * dot, derivative)
+    } else {
+        (0.0, [0.0; 2])
     }
 }
 
@@ -158,133 +315,221 @@ impl NoiseFn<Point2<f64>> for OpenSimplex {
 /// This is a slower but higher quality form of gradient noise than `Perlin` 3D.
 impl NoiseFn<Point3<f64>> for OpenSimplex {
     fn get(&self, point: Point3<f64>) -> f64 {
-        #[inline(always)]
-        fn gradient(perm_table: &PermutationTable, vertex: Point3<f64>, pos: Point3<f64>) -> f64 {
-            let attn = 2.0 - math::dot3(pos, pos);
-            if attn > 0.0 {
-                let index = perm_table.get3(math::to_isize3(vertex));
-                let vec = gradient::get3(index);
-                attn.powi(4) * math::dot3(pos, vec)
-            } else {
-                0.0
-            }
-        }
+        let mut value = 0.0;
+        open_simplex_3d(point, |vertex, pos| {
+            value += surflet3(self.perm_table.get3(math::to_isize3(vertex)), pos);
+        });
 
-        // Place input coordinates on simplectic h1.0ycomb.
-        let stretch_offset = math::fold3(point, Add::add) * STRETCH_CONSTANT_3D;
-        let stretched = math::map3(point, |v| v + stretch_offset);
+        value * NORM_CONSTANT_3D
+    }
 
-        // Floor to get simplectic h1.0ycomb coordinates of rhombohedron
-        // (stretched cube) super-cell origin.
-        let stretched_floor = math::map3(stretched, f64::floor);
+    fn get_many(&self, points: &[Point3<f64>], out: &mut [f64]) {
+        assert_eq!(points.len(), out.len());
+
+        // Every vertex visited lies on a corner of the stretched cell, so hash
+        // all of its corners once and reuse them while the cell is unchanged.
+        let mut cell = None;
+        let mut corners = [0; 8];
+        let mut corners_of = |stretched_floor: Point3<f64>| {
+            if cell != Some(stretched_floor) {
+                corners = self.perm_table.corners3(math::to_isize3(stretched_floor));
+                cell = Some(stretched_floor);
+            }
+            corners
+        };
+
+        for (&point, out) in points.iter().zip(out.iter_mut()) {
+            let stretched_floor = stretched_floor3(point);
+            let corners = corners_of(stretched_floor);
+
+            let mut value = 0.0;
+            open_simplex_3d(point, |vertex, pos| {
+                value += surflet3(corners[corner_index3(stretched_floor, vertex)], pos);
+            });
+            *out = value * NORM_CONSTANT_3D;
+        }
+    }
+}
 
-        // Skew out to get actual coordinates of rhombohedron origin. We'll need
-        // these later.
-        let squish_offset = math::fold3(stretched_floor, Add::add) * SQUISH_CONSTANT_3D;
-        let skewed_floor = math::map3(stretched_floor, |v| v + squish_offset);
+/// 3-dimensional [`OpenSimplex` Noise](http://uniblock.tumblr.com/post/97868843242/noise),
+/// with its analytic gradient
+impl NoiseFnWithGradient<Point3<f64>> for OpenSimplex {
+    fn get_with_gradient(&self, point: Point3<f64>) -> (f64, Vector3<f64>) {
+        let mut value = 0.0;
+        let mut gradient = [0.0; 3];
+        open_simplex_3d(point, |vertex, pos| {
+            let (surflet_value, surflet_gradient) =
+                surflet_with_gradient3(self.perm_table.get3(math::to_isize3(vertex)), pos);
+            value += surflet_value;
+            gradient = math::add3(gradient, surflet_gradient);
+        });
+
+        (value * NORM_CONSTANT_3D, math::mul3(gradient, NORM_CONSTANT_3D))
+    }
+}
 
-        // Compute simplectic h1.0ycomb coordinates relative to rhombohedral origin.
-        let rel_coords = math::sub3(stretched, stretched_floor);
+/// Floor of `point` placed onto the stretched grid, matching the
+/// `stretched_floor` of `open_simplex_3d`.
+#[inline(always)]
+fn stretched_floor3(point: Point3<f64>) -> Point3<f64> {
+    let stretch_offset = math::fold3(point, Add::add) * STRETCH_CONSTANT_3D;
+    math::map3(point, |v| (v + stretch_offset).floor())
+}
 
-        // Sum those together to get a value that determines which region we're in.
-        let region_sum = math::fold3(rel_coords, Add::add);
+/// Index of `vertex`, one of the corners of `cell`, into the result of
+/// `PermutationTable::corners3`.
+#[inline(always)]
+fn corner_index3(cell: Point3<f64>, vertex: Point3<f64>) -> usize {
+    (vertex[0] != cell[0]) as usize |
+        ((vertex[1] != cell[1]) as usize) << 1 |
+        ((vertex[2] != cell[2]) as usize) << 2
+}
 
-        // Positions relative to origin point.
-        let pos0 = math::sub3(point, skewed_floor);
+/// Visits each lattice vertex that contributes to the 3-dimensional noise
+/// value at `point`, passing the vertex and the position of `point` relative to
+/// it to `contribute`.
+#[inline(always)]
+fn open_simplex_3d<F>(point: Point3<f64>, mut contribute: F)
+where
+    F: FnMut(Point3<f64>, Point3<f64>),
+{
+    // Place input coordinates on simplectic h1.0ycomb.
+    let stretch_offset = math::fold3(point, Add::add) * STRETCH_CONSTANT_3D;
+    let stretched = math::map3(point, |v| v + stretch_offset);
+
+    // Floor to get simplectic h1.0ycomb coordinates of rhombohedron
+    // (stretched cube) super-cell origin.
+    let stretched_floor = math::map3(stretched, f64::floor);
+
+    // Skew out to get actual coordinates of rhombohedron origin. We'll need
+    // these later.
+    let squish_offset = math::fold3(stretched_floor, Add::add) * SQUISH_CONSTANT_3D;
+    let skewed_floor = math::map3(stretched_floor, |v| v + squish_offset);
+
+    // Compute simplectic h1.0ycomb coordinates relative to rhombohedral origin.
+    let rel_coords = math::sub3(stretched, stretched_floor);
+
+    // Sum those together to get a value that determines which region we're in.
+    let region_sum = math::fold3(rel_coords, Add::add);
+
+    // Positions relative to origin point.
+    let pos0 = math::sub3(point, skewed_floor);
+
+    let mut vertex;
+    let mut dpos;
+
+    if region_sum <= 1.0 {
+        // We're inside the tetrahedron (3-Simplex) at (0, 0, 0)
+        let t0 = SQUISH_CONSTANT_3D;
+        let t1 = SQUISH_CONSTANT_2D + 1.0;
 
-        let mut value = 0.0;
+        // Contribution at (0, 0, 0)
+        vertex = math::add3(stretched_floor, [0.0, 0.0, 0.0]);
+        dpos = math::sub3(pos0, [0.0, 0.0, 0.0]);
+        contribute(vertex, dpos);
+
+        // Contribution at (1, 0, 0)
+        vertex = math::add3(stretched_floor, [1.0, 0.0, 0.0]);
+        dpos = math::sub3(pos0, [t1, t0, t0]);
+        contribute(vertex, dpos);
+
+        // Contribution at (0, 1, 0)
+        vertex = math::add3(stretched_floor, [0.0, 1.0, 0.0]);
+        dpos = math::sub3(pos0, [t0, t1, t0]);
+        contribute(vertex, dpos);
+
+        // Contribution at (0, 0, 1)
+        vertex = math::add3(stretched_floor, [0.0, 0.0, 1.0]);
+        dpos = math::sub3(pos0, [t0, t0, t1]);
+        contribute(vertex, dpos);
+    } else if region_sum >= 2.0 {
+        // We're inside the tetrahedron (3-Simplex) at (1, 1, 1)
+        let t0 = 2.0 * SQUISH_CONSTANT_3D;
+        let t1 = 1.0 + 2.0 * SQUISH_CONSTANT_3D;
+        let t2 = t1 + SQUISH_CONSTANT_3D;
+
+        // Contribution at (1, 1, 0)
+        vertex = math::add3(stretched_floor, [1.0, 1.0, 0.0]);
+        dpos = math::sub3(pos0, [t1, t1, t0]);
+        contribute(vertex, dpos);
+
+        // Contribution at (1, 0, 1)
+        vertex = math::add3(stretched_floor, [1.0, 0.0, 1.0]);
+        dpos = math::sub3(pos0, [t1, t0, t1]);
+        contribute(vertex, dpos);
+
+        // Contribution at (0, 1, 1)
+        vertex = math::add3(stretched_floor, [0.0, 1.0, 1.0]);
+        dpos = math::sub3(pos0, [t0, t1, t1]);
+        contribute(vertex, dpos);
+
+        // Contribution at (1, 1, 1)
+        vertex = math::add3(stretched_floor, [1.0, 1.0, 1.0]);
+        dpos = math::sub3(pos0, [t2, t2, t2]);
+        contribute(vertex, dpos);
+    } else {
+        // We're inside the octahedron (Rectified 3-Simplex) inbetween.
+        let t0 = SQUISH_CONSTANT_3D;
+        let t1 = 1.0 + SQUISH_CONSTANT_3D;
+        let t2 = 2.0 * SQUISH_CONSTANT_3D;
+        let t3 = 1.0 + 2.0 * SQUISH_CONSTANT_3D;
+
+        // Contribution at (1, 0, 0)
+        vertex = math::add3(stretched_floor, [1.0, 0.0, 0.0]);
+        dpos = math::sub3(pos0, [t1, t0, t0]);
+        contribute(vertex, dpos);
+
+        // Contribution at (0, 1, 0)
+        vertex = math::add3(stretched_floor, [0.0, 1.0, 0.0]);
+        dpos = math::sub3(pos0, [t0, t1, t0]);
+        contribute(vertex, dpos);
+
+        // Contribution at (0, 0, 1)
+        vertex = math::add3(stretched_floor, [0.0, 0.0, 1.0]);
+        dpos = math::sub3(pos0, [t0, t0, t1]);
+        contribute(vertex, dpos);
+
+        // Contribution at (1, 1, 0)
+        vertex = math::add3(stretched_floor, [1.0, 1.0, 0.0]);
+        dpos = math::sub3(pos0, [t3, t3, t2]);
+        contribute(vertex, dpos);
+
+        // Contribution at (1, 0, 1)
+        vertex = math::add3(stretched_floor, [1.0, 0.0, 1.0]);
+        dpos = math::sub3(pos0, [t3, t2, t3]);
+        contribute(vertex, dpos);
+
+        // Contribution at (0, 1, 1)
+        vertex = math::add3(stretched_floor, [0.0, 1.0, 1.0]);
+        dpos = math::sub3(pos0, [t2, t3, t3]);
+        contribute(vertex, dpos);
+    }
+}
 
-        let mut vertex;
-        let mut dpos;
-
-        if region_sum <= 1.0 {
-            // We're inside the tetrahedron (3-Simplex) at (0, 0, 0)
-            let t0 = SQUISH_CONSTANT_3D;
-            let t1 = SQUISH_CONSTANT_2D + 1.0;
-
-            // Contribution at (0, 0, 0)
-            vertex = math::add3(stretched_floor, [0.0, 0.0, 0.0]);
-            dpos = math::sub3(pos0, [0.0, 0.0, 0.0]);
-            value += gradient(&self.perm_table, vertex, dpos);
-
-            // Contribution at (1, 0, 0)
-            vertex = math::add3(stretched_floor, [1.0, 0.0, 0.0]);
-            dpos = math::sub3(pos0, [t1, t0, t0]);
-            value += gradient(&self.perm_table, vertex, dpos);
-
-            // Contribution at (0, 1, 0)
-            vertex = math::add3(stretched_floor, [0.0, 1.0, 0.0]);
-            dpos = math::sub3(pos0, [t0, t1, t0]);
-            value += gradient(&self.perm_table, vertex, dpos);
-
-            // Contribution at (0, 0, 1)
-            vertex = math::add3(stretched_floor, [0.0, 0.0, 1.0]);
-            dpos = math::sub3(pos0, [t0, t0, t1]);
-            value += gradient(&self.perm_table, vertex, dpos);
-        } else if region_sum >= 2.0 {
-            // We're inside the tetrahedron (3-Simplex) at (1, 1, 1)
-            let t0 = 2.0 * SQUISH_CONSTANT_3D;
-            let t1 = 1.0 + 2.0 * SQUISH_CONSTANT_3D;
-            let t2 = t1 + SQUISH_CONSTANT_3D;
-
-            // Contribution at (1, 1, 0)
-            vertex = math::add3(stretched_floor, [1.0, 1.0, 0.0]);
-            dpos = math::sub3(pos0, [t1, t1, t0]);
-            value += gradient(&self.perm_table, vertex, dpos);
-
-            // Contribution at (1, 0, 1)
-            vertex = math::add3(stretched_floor, [1.0, 0.0, 1.0]);
-            dpos = math::sub3(pos0, [t1, t0, t1]);
-            value += gradient(&self.perm_table, vertex, dpos);
-
-            // Contribution at (0, 1, 1)
-            vertex = math::add3(stretched_floor, [0.0, 1.0, 1.0]);
-            dpos = math::sub3(pos0, [t0, t1, t1]);
-            value += gradient(&self.perm_table, vertex, dpos);
-
-            // Contribution at (1, 1, 1)
-            vertex = math::add3(stretched_floor, [1.0, 1.0, 1.0]);
-            dpos = math::sub3(pos0, [t2, t2, t2]);
-            value += gradient(&self.perm_table, vertex, dpos);
-        } else {
-            // We're inside the octahedron (Rectified 3-Simplex) inbetween.
-            let t0 = SQUISH_CONSTANT_3D;
-            let t1 = 1.0 + SQUISH_CONSTANT_3D;
-            let t2 = 2.0 * SQUISH_CONSTANT_3D;
-            let t3 = 1.0 + 2.0 * SQUISH_CONSTANT_3D;
-
-            // Contribution at (1, 0, 0)
-            vertex = math::add3(stretched_floor, [1.0, 0.0, 0.0]);
-            dpos = math::sub3(pos0, [t1, t0, t0]);
-            value += gradient(&self.perm_table, vertex, dpos);
-
-            // Contribution at (0, 1, 0)
-            vertex = math::add3(stretched_floor, [0.0, 1.0, 0.0]);
-            dpos = math::sub3(pos0, [t0, t1, t0]);
-            value += gradient(&self.perm_table, vertex, dpos);
-
-            // Contribution at (0, 0, 1)
-            vertex = math::add3(stretched_floor, [0.0, 0.0, 1.0]);
-            dpos = math::sub3(pos0, [t0, t0, t1]);
-            value += gradient(&self.perm_table, vertex, dpos);
-
-            // Contribution at (1, 1, 0)
-            vertex = math::add3(stretched_floor, [1.0, 1.0, 0.0]);
-            dpos = math::sub3(pos0, [t3, t3, t2]);
-            value += gradient(&self.perm_table, vertex, dpos);
-
-            // Contribution at (1, 0, 1)
-            vertex = math::add3(stretched_floor, [1.0, 0.0, 1.0]);
-            dpos = math::sub3(pos0, [t3, t2, t3]);
-            value += gradient(&self.perm_table, vertex, dpos);
-
-            // Contribution at (0, 1, 1)
-            vertex = math::add3(stretched_floor, [0.0, 1.0, 1.0]);
-            dpos = math::sub3(pos0, [t2, t3, t3]);
-            value += gradient(&self.perm_table, vertex, dpos);
-        }
+#[inline(always)]
+fn surflet3(hash: usize, pos: Point3<f64>) -> f64 {
+    let attn = 2.0 - math::dot3(pos, pos);
+    if attn > 0.0 {
+        let vec = gradient::get3(hash);
+        attn.powi(4) * math::dot3(pos, vec)
+    } else {
+        0.0
+    }
+}
 
-        value * NORM_CONSTANT_3D
+#[inline(always)]
+fn surflet_with_gradient3(hash: usize, pos: Point3<f64>) -> (f64, Vector3<f64>) {
+    let attn = 2.0 - math::dot3(pos, pos);
+    if attn > 0.0 {
+        let vec = gradient::get3(hash);
+        let dot = math::dot3(pos, vec);
+
+        // d(attn^4 * dot) = attn^4 * vec - 8 * attn^3 * dot * pos
+        let derivative = math::add3(math::mul3(vec, attn.powi(4)),
+                                     math::mul3(pos, -8.0 * attn.powi(3) * dot));
+        (attn.powi(4) * dot, derivative)
+    } else {
+        (0.0, [0.0; 3])
     }
 }
 
@@ -293,334 +538,401 @@ impl NoiseFn<Point3<f64>> for OpenSimplex {
 /// This is a slower but higher quality form of gradient noise than `Perlin` 4D.
 impl NoiseFn<Point4<f64>> for OpenSimplex {
     fn get(&self, point: Point4<f64>) -> f64 {
-        #[inline(always)]
-        fn gradient(perm_table: &PermutationTable, vertex: Point4<f64>, pos: Point4<f64>) -> f64 {
-            let attn = 2.0 - math::dot4(pos, pos);
-            if attn > 0.0 {
-                let index = perm_table.get4(math::to_isize4(vertex));
-                let vec = gradient::get4(index);
-                attn.powi(4) * math::dot4(pos, vec)
-            } else {
-                0.0
-            }
-        }
-
-        // Place input coordinates on simplectic h1.0ycomb.
-        let stretch_offset = math::fold4(point, Add::add) * STRETCH_CONSTANT_4D;
-        let stretched = math::map4(point, |v| v + stretch_offset);
+        let mut value = 0.0;
+        open_simplex_4d(point, |vertex, pos| {
+            value += surflet4(self.perm_table.get4(math::to_isize4(vertex)), pos);
+        });
 
-        // Floor to get simplectic h1.0ycomb coordinates of rhombo-hypercube
-        // super-cell origin.
-        let stretched_floor = math::map4(stretched, f64::floor);
+        value * NORM_CONSTANT_4D
+    }
+}
 
-        // Skew out to get actual coordinates of stretched rhombo-hypercube origin.
-        // We'll need these later.
-        let squish_offset = math::fold4(stretched_floor, Add::add) * SQUISH_CONSTANT_4D;
-        let skewed_floor = math::map4(stretched_floor, |v| v + squish_offset);
+/// 4-dimensional [`OpenSimplex` Noise](http://uniblock.tumblr.com/post/97868843242/noise),
+/// with its analytic gradient
+impl NoiseFnWithGradient<Point4<f64>> for OpenSimplex {
+    fn get_with_gradient(&self, point: Point4<f64>) -> (f64, Vector4<f64>) {
+        let mut value = 0.0;
+        let mut gradient = [0.0; 4];
+        open_simplex_4d(point, |vertex, pos| {
+            let (surflet_value, surflet_gradient) =
+                surflet_with_gradient4(self.perm_table.get4(math::to_isize4(vertex)), pos);
+            value += surflet_value;
+            gradient = math::add4(gradient, surflet_gradient);
+        });
+
+        (value * NORM_CONSTANT_4D, math::mul4(gradient, NORM_CONSTANT_4D))
+    }
+}
 
-        // Compute simplectic h1.0ycomb coordinates relative to rhombo-hypercube
-        // origin.
-        let rel_coords = math::sub4(stretched, stretched_floor);
+/// Visits each lattice vertex that contributes to the 4-dimensional noise
+/// value at `point`, passing the vertex and the position of `point` relative to
+/// it to `contribute`.
+#[inline(always)]
+fn open_simplex_4d<F>(point: Point4<f64>, mut contribute: F)
+where
+    F: FnMut(Point4<f64>, Point4<f64>),
+{
+    // Place input coordinates on simplectic h1.0ycomb.
+    let stretch_offset = math::fold4(point, Add::add) * STRETCH_CONSTANT_4D;
+    let stretched = math::map4(point, |v| v + stretch_offset);
+
+    // Floor to get simplectic h1.0ycomb coordinates of rhombo-hypercube
+    // super-cell origin.
+    let stretched_floor = math::map4(stretched, f64::floor);
+
+    // Skew out to get actual coordinates of stretched rhombo-hypercube origin.
+    // We'll need these later.
+    let squish_offset = math::fold4(stretched_floor, Add::add) * SQUISH_CONSTANT_4D;
+    let skewed_floor = math::map4(stretched_floor, |v| v + squish_offset);
+
+    // Compute simplectic h1.0ycomb coordinates relative to rhombo-hypercube
+    // origin.
+    let rel_coords = math::sub4(stretched, stretched_floor);
+
+    // Sum those together to get a value that determines which region
+    // we're in.
+    let region_sum = math::fold4(rel_coords, Add::add);
+
+    // Position relative to origin point.
+    let mut pos0 = math::sub4(point, skewed_floor);
+
+    if region_sum <= 1.0 {
+        // We're inside the pentachoron (4-Simplex) at (0, 0, 0, 0)
+
+        // Contribution at (0, 0, 0, 0)
+        contribute(stretched_floor, pos0);
+
+        // Contribution at (1, 0, 0, 0)
+        let pos1;
+        {
+            let vertex = math::add4(stretched_floor, [1.0, 0.0, 0.0, 0.0]);
+            pos1 = math::sub4(
+                pos0,
+                [
+                    1.0 + SQUISH_CONSTANT_4D,
+                    SQUISH_CONSTANT_4D,
+                    SQUISH_CONSTANT_4D,
+                    SQUISH_CONSTANT_4D,
+                ],
+            );
+            contribute(vertex, pos1);
+        }
 
-        // Sum those together to get a value that determines which region
-        // we're in.
-        let region_sum = math::fold4(rel_coords, Add::add);
+        // Contribution at (0, 1, 0, 0)
+        let pos2;
+        {
+            let vertex = math::add4(stretched_floor, [0.0, 1.0, 0.0, 0.0]);
+            pos2 = [pos1[0] + 1.0, pos1[1] - 1.0, pos1[2], pos1[3]];
+            contribute(vertex, pos2);
+        }
 
-        // Position relative to origin point.
-        let mut pos0 = math::sub4(point, skewed_floor);
+        // Contribution at (0, 0, 1, 0)
+        let pos3;
+        {
+            let vertex = math::add4(stretched_floor, [0.0, 0.0, 1.0, 0.0]);
+            pos3 = [pos2[0], pos1[1], pos1[2] - 1.0, pos1[3]];
+            contribute(vertex, pos3);
+        }
 
-        let mut value = 0.0;
-        if region_sum <= 1.0 {
-            // We're inside the pentachoron (4-Simplex) at (0, 0, 0, 0)
-
-            // Contribution at (0, 0, 0, 0)
-            value += gradient(&self.perm_table, stretched_floor, pos0);
-
-            // Contribution at (1, 0, 0, 0)
-            let pos1;
-            {
-                let vertex = math::add4(stretched_floor, [1.0, 0.0, 0.0, 0.0]);
-                pos1 = math::sub4(
-                    pos0,
-                    [
-                        1.0 + SQUISH_CONSTANT_4D,
-                        SQUISH_CONSTANT_4D,
-                        SQUISH_CONSTANT_4D,
-                        SQUISH_CONSTANT_4D,
-                    ],
-                );
-                value += gradient(&self.perm_table, vertex, pos1);
-            }
+        // Contribution at (0, 0, 0, 1)
+        let pos4;
+        {
+            let vertex = math::add4(stretched_floor, [0.0, 0.0, 0.0, 1.0]);
+            pos4 = [pos2[0], pos1[1], pos1[2], pos1[3] - 1.0];
+            contribute(vertex, pos4);
+        }
+    } else if region_sum >= 3.0 {
+        // We're inside the pentachoron (4-Simplex) at (1, 1, 1, 1)
+        let squish_constant_3 = 3.0 * SQUISH_CONSTANT_4D;
+
+        // Contribution at (1, 1, 1, 0)
+        let pos4;
+        {
+            let vertex = math::add4(stretched_floor, [1.0, 1.0, 1.0, 0.0]);
+            pos4 = math::sub4(
+                pos0,
+                [
+                    1.0 + squish_constant_3,
+                    1.0 + squish_constant_3,
+                    1.0 + squish_constant_3,
+                    squish_constant_3,
+                ],
+            );
+            contribute(vertex, pos4);
+        }
 
-            // Contribution at (0, 1, 0, 0)
-            let pos2;
-            {
-                let vertex = math::add4(stretched_floor, [0.0, 1.0, 0.0, 0.0]);
-                pos2 = [pos1[0] + 1.0, pos1[1] - 1.0, pos1[2], pos1[3]];
-                value += gradient(&self.perm_table, vertex, pos2);
-            }
+        // Contribution at (1, 1, 0, 1)
+        let pos3;
+        {
+            let vertex = math::add4(stretched_floor, [1.0, 1.0, 0.0, 1.0]);
+            pos3 = [pos4[0], pos4[1], pos4[2] + 1.0, pos4[3] - 1.0];
+            contribute(vertex, pos3);
+        }
 
-            // Contribution at (0, 0, 1, 0)
-            let pos3;
-            {
-                let vertex = math::add4(stretched_floor, [0.0, 0.0, 1.0, 0.0]);
-                pos3 = [pos2[0], pos1[1], pos1[2] - 1.0, pos1[3]];
-                value += gradient(&self.perm_table, vertex, pos3);
-            }
+        // Contribution at (1, 0, 1, 1)
+        let pos2;
+        {
+            let vertex = math::add4(stretched_floor, [1.0, 0.0, 1.0, 1.0]);
+            pos2 = [pos4[0], pos4[1] + 1.0, pos4[2], pos3[3]];
+            contribute(vertex, pos2);
+        }
 
-            // Contribution at (0, 0, 0, 1)
-            let pos4;
-            {
-                let vertex = math::add4(stretched_floor, [0.0, 0.0, 0.0, 1.0]);
-                pos4 = [pos2[0], pos1[1], pos1[2], pos1[3] - 1.0];
-                value += gradient(&self.perm_table, vertex, pos4);
-            }
-        } else if region_sum >= 3.0 {
-            // We're inside the pentachoron (4-Simplex) at (1, 1, 1, 1)
-            let squish_constant_3 = 3.0 * SQUISH_CONSTANT_4D;
-
-            // Contribution at (1, 1, 1, 0)
-            let pos4;
-            {
-                let vertex = math::add4(stretched_floor, [1.0, 1.0, 1.0, 0.0]);
-                pos4 = math::sub4(
-                    pos0,
-                    [
-                        1.0 + squish_constant_3,
-                        1.0 + squish_constant_3,
-                        1.0 + squish_constant_3,
-                        squish_constant_3,
-                    ],
-                );
-                value += gradient(&self.perm_table, vertex, pos4);
-            }
+        // Contribution at (0, 1, 1, 1)
+        let pos1;
+        {
+            let vertex = math::add4(stretched_floor, [0.0, 1.0, 1.0, 1.0]);
+            pos1 = [pos0[0] - squish_constant_3, pos4[1], pos4[2], pos3[3]];
+            contribute(vertex, pos1);
+        }
 
-            // Contribution at (1, 1, 0, 1)
-            let pos3;
-            {
-                let vertex = math::add4(stretched_floor, [1.0, 1.0, 0.0, 1.0]);
-                pos3 = [pos4[0], pos4[1], pos4[2] + 1.0, pos4[3] - 1.0];
-                value += gradient(&self.perm_table, vertex, pos3);
-            }
+        // Contribution at (1, 1, 1, 1)
+        {
+            let vertex = math::add4(stretched_floor, [1.0, 1.0, 1.0, 1.0]);
+            pos0[0] = pos4[0] - SQUISH_CONSTANT_4D;
+            pos0[1] = pos4[1] - SQUISH_CONSTANT_4D;
+            pos0[2] = pos4[2] - SQUISH_CONSTANT_4D;
+            pos0[3] = pos3[3] - SQUISH_CONSTANT_4D;
+            contribute(vertex, pos0);
+        }
+    } else if region_sum <= 2.0 {
+        // We're inside the first dispentachoron (Rectified 4-Simplex)
+
+        // Contribution at (1, 0, 0, 0)
+        let pos1;
+        {
+            let vertex = math::add4(stretched_floor, [1.0, 0.0, 0.0, 0.0]);
+            pos1 = math::sub4(
+                pos0,
+                [
+                    1.0 + SQUISH_CONSTANT_4D,
+                    SQUISH_CONSTANT_4D,
+                    SQUISH_CONSTANT_4D,
+                    SQUISH_CONSTANT_4D,
+                ],
+            );
+            contribute(vertex, pos1);
+        }
 
-            // Contribution at (1, 0, 1, 1)
-            let pos2;
-            {
-                let vertex = math::add4(stretched_floor, [1.0, 0.0, 1.0, 1.0]);
-                pos2 = [pos4[0], pos4[1] + 1.0, pos4[2], pos3[3]];
-                value += gradient(&self.perm_table, vertex, pos2);
-            }
+        // Contribution at (0, 1, 0, 0)
+        let pos2;
+        {
+            let vertex = math::add4(stretched_floor, [0.0, 1.0, 0.0, 0.0]);
+            pos2 = [pos1[0] + 1.0, pos1[1] - 1.0, pos1[2], pos1[3]];
+            contribute(vertex, pos2);
+        }
 
-            // Contribution at (0, 1, 1, 1)
-            let pos1;
-            {
-                let vertex = math::add4(stretched_floor, [0.0, 1.0, 1.0, 1.0]);
-                pos1 = [pos0[0] - squish_constant_3, pos4[1], pos4[2], pos3[3]];
-                value += gradient(&self.perm_table, vertex, pos1);
-            }
+        // Contribution at (0, 0, 1, 0)
+        let pos3;
+        {
+            let vertex = math::add4(stretched_floor, [0.0, 0.0, 1.0, 0.0]);
+            pos3 = [pos2[0], pos1[1], pos1[2] - 1.0, pos1[3]];
+            contribute(vertex, pos3);
+        }
 
-            // Contribution at (1, 1, 1, 1)
-            {
-                let vertex = math::add4(stretched_floor, [1.0, 1.0, 1.0, 1.0]);
-                pos0[0] = pos4[0] - SQUISH_CONSTANT_4D;
-                pos0[1] = pos4[1] - SQUISH_CONSTANT_4D;
-                pos0[2] = pos4[2] - SQUISH_CONSTANT_4D;
-                pos0[3] = pos3[3] - SQUISH_CONSTANT_4D;
-                value += gradient(&self.perm_table, vertex, pos0);
-            }
-        } else if region_sum <= 2.0 {
-            // We're inside the first dispentachoron (Rectified 4-Simplex)
-
-            // Contribution at (1, 0, 0, 0)
-            let pos1;
-            {
-                let vertex = math::add4(stretched_floor, [1.0, 0.0, 0.0, 0.0]);
-                pos1 = math::sub4(
-                    pos0,
-                    [
-                        1.0 + SQUISH_CONSTANT_4D,
-                        SQUISH_CONSTANT_4D,
-                        SQUISH_CONSTANT_4D,
-                        SQUISH_CONSTANT_4D,
-                    ],
-                );
-                value += gradient(&self.perm_table, vertex, pos1);
-            }
+        // Contribution at (0, 0, 0, 1)
+        let pos4;
+        {
+            let vertex = math::add4(stretched_floor, [0.0, 0.0, 0.0, 1.0]);
+            pos4 = [pos2[0], pos1[1], pos1[2], pos1[3] - 1.0];
+            contribute(vertex, pos4);
+        }
 
-            // Contribution at (0, 1, 0, 0)
-            let pos2;
-            {
-                let vertex = math::add4(stretched_floor, [0.0, 1.0, 0.0, 0.0]);
-                pos2 = [pos1[0] + 1.0, pos1[1] - 1.0, pos1[2], pos1[3]];
-                value += gradient(&self.perm_table, vertex, pos2);
-            }
+        // Contribution at (1, 1, 0, 0)
+        let pos5;
+        {
+            let vertex = math::add4(stretched_floor, [1.0, 1.0, 0.0, 0.0]);
+            pos5 = [
+                pos1[0] - SQUISH_CONSTANT_4D,
+                pos2[1] - SQUISH_CONSTANT_4D,
+                pos1[2] - SQUISH_CONSTANT_4D,
+                pos1[3] - SQUISH_CONSTANT_4D,
+            ];
+            contribute(vertex, pos5);
+        }
 
-            // Contribution at (0, 0, 1, 0)
-            let pos3;
-            {
-                let vertex = math::add4(stretched_floor, [0.0, 0.0, 1.0, 0.0]);
-                pos3 = [pos2[0], pos1[1], pos1[2] - 1.0, pos1[3]];
-                value += gradient(&self.perm_table, vertex, pos3);
-            }
+        // Contribution at (1, 0, 1, 0)
+        let pos6;
+        {
+            let vertex = math::add4(stretched_floor, [1.0, 0.0, 1.0, 0.0]);
+            pos6 = [pos5[0], pos5[1] + 1.0, pos5[2] - 1.0, pos5[3]];
+            contribute(vertex, pos6);
+        }
 
-            // Contribution at (0, 0, 0, 1)
-            let pos4;
-            {
-                let vertex = math::add4(stretched_floor, [0.0, 0.0, 0.0, 1.0]);
-                pos4 = [pos2[0], pos1[1], pos1[2], pos1[3] - 1.0];
-                value += gradient(&self.perm_table, vertex, pos4);
-            }
+        // Contribution at (1, 0, 0, 1)
+        let pos7;
+        {
+            let vertex = math::add4(stretched_floor, [1.0, 0.0, 0.0, 1.0]);
+            pos7 = [pos5[0], pos6[1], pos5[2], pos5[3] - 1.0];
+            contribute(vertex, pos7);
+        }
 
-            // Contribution at (1, 1, 0, 0)
-            let pos5;
-            {
-                let vertex = math::add4(stretched_floor, [1.0, 1.0, 0.0, 0.0]);
-                pos5 = [
-                    pos1[0] - SQUISH_CONSTANT_4D,
-                    pos2[1] - SQUISH_CONSTANT_4D,
-                    pos1[2] - SQUISH_CONSTANT_4D,
-                    pos1[3] - SQUISH_CONSTANT_4D,
-                ];
-                value += gradient(&self.perm_table, vertex, pos5);
-            }
+        // Contribution at (0, 1, 1, 0)
+        let pos8;
+        {
+            let vertex = math::add4(stretched_floor, [0.0, 1.0, 1.0, 0.0]);
+            pos8 = [pos5[0] + 1.0, pos5[1], pos6[2], pos5[3]];
+            contribute(vertex, pos8);
+        }
 
-            // Contribution at (1, 0, 1, 0)
-            let pos6;
-            {
-                let vertex = math::add4(stretched_floor, [1.0, 0.0, 1.0, 0.0]);
-                pos6 = [pos5[0], pos5[1] + 1.0, pos5[2] - 1.0, pos5[3]];
-                value += gradient(&self.perm_table, vertex, pos6);
-            }
+        // Contribution at (0, 1, 0, 1)
+        let pos9;
+        {
+            let vertex = math::add4(stretched_floor, [0.0, 1.0, 0.0, 1.0]);
+            pos9 = [pos8[0], pos5[1], pos5[2], pos7[3]];
+            contribute(vertex, pos9);
+        }
 
-            // Contribution at (1, 0, 0, 1)
-            let pos7;
-            {
-                let vertex = math::add4(stretched_floor, [1.0, 0.0, 0.0, 1.0]);
-                pos7 = [pos5[0], pos6[1], pos5[2], pos5[3] - 1.0];
-                value += gradient(&self.perm_table, vertex, pos7);
-            }
+        // Contribution at (0, 0, 1, 1)
+        let pos10;
+        {
+            let vertex = math::add4(stretched_floor, [0.0, 0.0, 1.0, 1.0]);
+            pos10 = [pos8[0], pos6[1], pos6[2], pos7[3]];
+            contribute(vertex, pos10);
+        }
+    } else {
+        // We're inside the second dispentachoron (Rectified 4-Simplex)
+        let squish_constant_3 = 3.0 * SQUISH_CONSTANT_4D;
+
+        // Contribution at (1, 1, 1, 0)
+        let pos4;
+        {
+            let vertex = math::add4(stretched_floor, [1.0, 1.0, 1.0, 0.0]);
+            pos4 = math::sub4(
+                pos0,
+                [
+                    1.0 + squish_constant_3,
+                    1.0 + squish_constant_3,
+                    1.0 + squish_constant_3,
+                    squish_constant_3,
+                ],
+            );
+            contribute(vertex, pos4);
+        }
 
-            // Contribution at (0, 1, 1, 0)
-            let pos8;
-            {
-                let vertex = math::add4(stretched_floor, [0.0, 1.0, 1.0, 0.0]);
-                pos8 = [pos5[0] + 1.0, pos5[1], pos6[2], pos5[3]];
-                value += gradient(&self.perm_table, vertex, pos8);
-            }
+        // Contribution at (1, 1, 0, 1)
+        let pos3;
+        {
+            let vertex = math::add4(stretched_floor, [1.0, 1.0, 0.0, 1.0]);
+            pos3 = [pos4[0], pos4[1], pos4[2] + 1.0, pos4[3] - 1.0];
+            contribute(vertex, pos3);
+        }
 
-            // Contribution at (0, 1, 0, 1)
-            let pos9;
-            {
-                let vertex = math::add4(stretched_floor, [0.0, 1.0, 0.0, 1.0]);
-                pos9 = [pos8[0], pos5[1], pos5[2], pos7[3]];
-                value += gradient(&self.perm_table, vertex, pos9);
-            }
+        // Contribution at (1, 0, 1, 1)
+        let pos2;
+        {
+            let vertex = math::add4(stretched_floor, [1.0, 0.0, 1.0, 1.0]);
+            pos2 = [pos4[0], pos4[1] + 1.0, pos4[2], pos3[3]];
+            contribute(vertex, pos2);
+        }
 
-            // Contribution at (0, 0, 1, 1)
-            let pos10;
-            {
-                let vertex = math::add4(stretched_floor, [0.0, 0.0, 1.0, 1.0]);
-                pos10 = [pos8[0], pos6[1], pos6[2], pos7[3]];
-                value += gradient(&self.perm_table, vertex, pos10);
-            }
-        } else {
-            // We're inside the second dispentachoron (Rectified 4-Simplex)
-            let squish_constant_3 = 3.0 * SQUISH_CONSTANT_4D;
-
-            // Contribution at (1, 1, 1, 0)
-            let pos4;
-            {
-                let vertex = math::add4(stretched_floor, [1.0, 1.0, 1.0, 0.0]);
-                pos4 = math::sub4(
-                    pos0,
-                    [
-                        1.0 + squish_constant_3,
-                        1.0 + squish_constant_3,
-                        1.0 + squish_constant_3,
-                        squish_constant_3,
-                    ],
-                );
-                value += gradient(&self.perm_table, vertex, pos4);
-            }
+        // Contribution at (0, 1, 1, 1)
+        let pos1;
+        {
+            let vertex = math::add4(stretched_floor, [0.0, 1.0, 1.0, 1.0]);
+            pos1 = [pos4[0] + 1.0, pos4[1], pos4[2], pos3[3]];
+            contribute(vertex, pos1);
+        }
 
-            // Contribution at (1, 1, 0, 1)
-            let pos3;
-            {
-                let vertex = math::add4(stretched_floor, [1.0, 1.0, 0.0, 1.0]);
-                pos3 = [pos4[0], pos4[1], pos4[2] + 1.0, pos4[3] - 1.0];
-                value += gradient(&self.perm_table, vertex, pos3);
-            }
+        // Contribution at (1, 1, 0, 0)
+        let pos5;
+        {
+            let vertex = math::add4(stretched_floor, [1.0, 1.0, 0.0, 0.0]);
+            pos5 = [
+                pos4[0] + SQUISH_CONSTANT_4D,
+                pos4[1] + SQUISH_CONSTANT_4D,
+                pos3[2] + SQUISH_CONSTANT_4D,
+                pos4[3] + SQUISH_CONSTANT_4D,
+            ];
+            contribute(vertex, pos5);
+        }
 
-            // Contribution at (1, 0, 1, 1)
-            let pos2;
-            {
-                let vertex = math::add4(stretched_floor, [1.0, 0.0, 1.0, 1.0]);
-                pos2 = [pos4[0], pos4[1] + 1.0, pos4[2], pos3[3]];
-                value += gradient(&self.perm_table, vertex, pos2);
-            }
+        // Contribution at (1, 0, 1, 0)
+        let pos6;
+        {
+            let vertex = math::add4(stretched_floor, [1.0, 0.0, 1.0, 0.0]);
+            pos6 = [pos5[0], pos5[1] + 1.0, pos5[2] - 1.0, pos5[3]];
+            contribute(vertex, pos6);
+        }
 
-            // Contribution at (0, 1, 1, 1)
-            let pos1;
-            {
-                let vertex = math::add4(stretched_floor, [0.0, 1.0, 1.0, 1.0]);
-                pos1 = [pos4[0] + 1.0, pos4[1], pos4[2], pos3[3]];
-                value += gradient(&self.perm_table, vertex, pos1);
-            }
+        // Contribution at (1, 0, 0, 1)
+        let pos7;
+        {
+            let vertex = math::add4(stretched_floor, [1.0, 0.0, 0.0, 1.0]);
+            pos7 = [pos5[0], pos6[1], pos5[2], pos5[3] - 1.0];
+            contribute(vertex, pos7);
+        }
 
-            // Contribution at (1, 1, 0, 0)
-            let pos5;
-            {
-                let vertex = math::add4(stretched_floor, [1.0, 1.0, 0.0, 0.0]);
-                pos5 = [
-                    pos4[0] + SQUISH_CONSTANT_4D,
-                    pos4[1] + SQUISH_CONSTANT_4D,
-                    pos3[2] + SQUISH_CONSTANT_4D,
-                    pos4[3] + SQUISH_CONSTANT_4D,
-                ];
-                value += gradient(&self.perm_table, vertex, pos5);
-            }
+        // Contribution at (0, 1, 1, 0)
+        let pos8;
+        {
+            let vertex = math::add4(stretched_floor, [0.0, 1.0, 1.0, 0.0]);
+            pos8 = [pos5[0] + 1.0, pos5[1], pos6[2], pos5[3]];
+            contribute(vertex, pos8);
+        }
 
-            // Contribution at (1, 0, 1, 0)
-            let pos6;
-            {
-                let vertex = math::add4(stretched_floor, [1.0, 0.0, 1.0, 0.0]);
-                pos6 = [pos5[0], pos5[1] + 1.0, pos5[2] - 1.0, pos5[3]];
-                value += gradient(&self.perm_table, vertex, pos6);
-            }
+        // Contribution at (0, 1, 0, 1)
+        let pos9;
+        {
+            let vertex = math::add4(stretched_floor, [0.0, 1.0, 0.0, 1.0]);
+            pos9 = [pos8[0], pos5[1], pos5[2], pos7[3]];
+            contribute(vertex, pos9);
+        }
 
-            // Contribution at (1, 0, 0, 1)
-            let pos7;
-            {
-                let vertex = math::add4(stretched_floor, [1.0, 0.0, 0.0, 1.0]);
-                pos7 = [pos5[0], pos6[1], pos5[2], pos5[3] - 1.0];
-                value += gradient(&self.perm_table, vertex, pos7);
-            }
+        // Contribution at (0, 0, 1, 1)
+        let pos10;
+        {
+            let vertex = math::add4(stretched_floor, [0.0, 0.0, 1.0, 1.0]);
+            pos10 = [pos8[0], pos6[1], pos6[2], pos7[3]];
+            contribute(vertex, pos10);
+        }
+    }
+}
 
-            // Contribution at (0, 1, 1, 0)
-            let pos8;
-            {
-                let vertex = math::add4(stretched_floor, [0.0, 1.0, 1.0, 0.0]);
-                pos8 = [pos5[0] + 1.0, pos5[1], pos6[2], pos5[3]];
-                value += gradient(&self.perm_table, vertex, pos8);
-            }
+#[inline(always)]
+fn surflet4(hash: usize, pos: Point4<f64>) -> f64 {
+    let attn = 2.0 - math::dot4(pos, pos);
+    if attn > 0.0 {
+        let vec = gradient::get4(hash);
+        attn.powi(4) * math::dot4(pos, vec)
+    } else {
+        0.0
+    }
+}
 
-            // Contribution at (0, 1, 0, 1)
-            let pos9;
-            {
-                let vertex = math::add4(stretched_floor, [0.0, 1.0, 0.0, 1.0]);
-                pos9 = [pos8[0], pos5[1], pos5[2], pos7[3]];
-                value += gradient(&self.perm_table, vertex, pos9);
-            }
+#[inline(always)]
+fn surflet_with_gradient4(hash: usize, pos: Point4<f64>) -> (f64, Vector4<f64>) {
+    let attn = 2.0 - math::dot4(pos, pos);
+    if attn > 0.0 {
+        let vec = gradient::get4(hash);
+        let dot = math::dot4(pos, vec);
+
+        // d(attn^4 * dot) = attn^4 * vec - 8 * attn^3 * dot * pos
+        let derivative = math::add4(math::mul4(vec, attn.powi(4)),
+                                     math::mul4(pos, -8.0 * attn.powi(3) * dot));
+        (attn.powi(4) * dot, derivative)
+    } else {
+        (0.0, [0.0; 4])
+    }
+}
 
-            // Contribution at (0, 0, 1, 1)
-            let pos10;
-            {
-                let vertex = math::add4(stretched_floor, [0.0, 0.0, 1.0, 1.0]);
-                pos10 = [pos8[0], pos6[1], pos6[2], pos7[3]];
-                value += gradient(&self.perm_table, vertex, pos10);
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use noise_fns::tests::{assert_get_many_matches_get, assert_gradient_matches};
+    use {OpenSimplex, Seedable};
+
+    #[test]
+    fn test_get_many_matches_get() {
+        let simplex = OpenSimplex::new().set_seed(5);
+        assert_get_many_matches_get::<[f64; 2], _>(&simplex);
+        assert_get_many_matches_get::<[f64; 3], _>(&simplex);
+        assert_get_many_matches_get::<[f64; 4], _>(&simplex);
+    }
 
-        value * NORM_CONSTANT_4D
+    #[test]
+    fn test_gradient_matches_finite_differences() {
+        let simplex = OpenSimplex::new().set_seed(5);
+        assert_gradient_matches(&simplex, &[[1.3, -2.7], [-0.6, 8.2]]);
+        assert_gradient_matches(&simplex, &[[1.3, -2.7, 0.4], [-0.6, 8.2, -3.3]]);
+        assert_gradient_matches(&simplex, &[[1.3, -2.7, 0.4, 5.1], [-0.6, 8.2, -3.3, 0.9]]);
     }
 }
@@ -8,26 +8,101 @@
 
 use {gradient, math};
 use math::{Point2, Point3, Point4, Vector2, Vector3, Vector4};
-use noise_fns::{NoiseFn, Seedable};
-use permutationtable::PermutationTable;
+use noise_fns::{NoiseFn, NoiseFnWithGradient, Periodic, Reseedable, Seedable};
+use permutationtable::{DEFAULT_LATTICE_MODE, LatticeMode, PermutationTable};
 
 /// Default Seed for the Perlin noise function.
-pub const DEFAULT_PERLIN_SEED: u32 = 0;
+pub const DEFAULT_PERLIN_SEED: u64 = 0;
+
+// Arbitrary values to scale the output of each dimension to -1..1
+const SCALE_FACTOR_2D: f64 = 3.1604938271604937;
+const SCALE_FACTOR_3D: f64 = 3.8898553255531074;
+const SCALE_FACTOR_4D: f64 = 4.424369240215691;
 
 /// Noise function that outputs 2/3/4-dimensional Perlin noise.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "PerlinConfig"))]
 pub struct Perlin {
-    seed: u32,
+    seed: u64,
+    lattice_mode: LatticeMode,
+    period: Option<usize>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(skip_serializing_if = "PermutationTable::is_seeded")
+    )]
     perm_table: PermutationTable,
 }
 
+/// Serialized form of `Perlin`. The permutation table is
+/// rebuilt from the seed, unless a custom table was serialized.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct PerlinConfig {
+    seed: u64,
+    #[serde(default)]
+    lattice_mode: LatticeMode,
+    #[serde(default)]
+    period: Option<usize>,
+    // Only present for a custom permutation table.
+    perm_table: Option<PermutationTable>,
+}
+
+#[cfg(feature = "serde")]
+impl From<PerlinConfig> for Perlin {
+    fn from(config: PerlinConfig) -> Perlin {
+        let noise = Perlin::new()
+            .set_lattice_mode(config.lattice_mode)
+            .set_period(config.period)
+            .set_seed(config.seed);
+
+        match config.perm_table {
+            Some(perm_table) => noise.set_permutation_table(perm_table),
+            None => noise,
+        }
+    }
+}
+
 impl Perlin {
     pub fn new() -> Perlin {
         Perlin {
             seed: DEFAULT_PERLIN_SEED,
+            lattice_mode: DEFAULT_LATTICE_MODE,
+            period: None,
             perm_table: PermutationTable::new(DEFAULT_PERLIN_SEED),
         }
     }
+
+    /// Replaces the permutation table used to hash the lattice points, e.g.
+    /// with one built by `PermutationTable::from_rng` or
+    /// `PermutationTable::from_permutation`.
+    ///
+    /// The seed, lattice mode and period are left as they were. Setting a
+    /// different seed afterwards rebuilds the table from the seed. The table is
+    /// serialized along with the noise function.
+    pub fn set_permutation_table(self, perm_table: PermutationTable) -> Perlin {
+        Perlin {
+            perm_table: perm_table
+                .set_mode(self.lattice_mode)
+                .set_period(self.period),
+            ..self
+        }
+    }
+
+    /// Sets how the lattice points are hashed. `LatticeMode::Hash` avoids the
+    /// noise repeating every 256 units.
+    pub fn set_lattice_mode(self, lattice_mode: LatticeMode) -> Perlin {
+        Perlin {
+            lattice_mode: lattice_mode,
+            perm_table: self.perm_table.set_mode(lattice_mode),
+            ..self
+        }
+    }
+
+    /// Returns how the lattice points are hashed.
+    pub fn lattice_mode(&self) -> LatticeMode {
+        self.lattice_mode
+    }
 }
 
 impl Default for Perlin {
@@ -38,7 +113,7 @@ impl Default for Perlin {
 
 impl Seedable for Perlin {
     /// Sets the seed value for Perlin noise
-    fn set_seed(self, seed: u32) -> Perlin {
+    fn set_seed(self, seed: u64) -> Perlin {
         // If the new seed is the same as the current seed, just return self.
         if self.seed == seed {
             return self;
@@ -47,15 +122,44 @@ impl Seedable for Perlin {
         // Otherwise, regenerate the permutation table based on the new seed.
         Perlin {
             seed: seed,
-            perm_table: PermutationTable::new(seed),
+            perm_table: PermutationTable::new(seed)
+                .set_mode(self.lattice_mode)
+                .set_period(self.period),
+            ..self
         }
     }
 
-    fn seed(&self) -> u32 {
+    fn seed(&self) -> u64 {
         self.seed
     }
 }
 
+impl Periodic for Perlin {
+    /// Sets the period after which the noise repeats. The lattice points are
+    /// wrapped modulo the period before they are hashed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the period is `Some(0)`.
+    fn set_period(self, period: Option<usize>) -> Perlin {
+        Perlin {
+            period: period,
+            perm_table: self.perm_table.set_period(period),
+            ..self
+        }
+    }
+
+    fn period(&self) -> Option<usize> {
+        self.period
+    }
+}
+
+impl Reseedable for Perlin {
+    fn reseed(self, seed: u64) -> Perlin {
+        self.set_seed(seed)
+    }
+}
+
 /// 2-dimensional perlin noise
 impl NoiseFn<Point2<f64>> for Perlin {
     fn get(&self, point: Point2<f64>) -> f64 {
@@ -93,7 +197,28 @@ impl NoiseFn<Point2<f64>> for Perlin {
                           [far_distance[0], far_distance[1]]);
 
         // Multiply by arbitrary value to scale to -1..1
-        (f00 + f10 + f01 + f11) * 3.1604938271604937
+        (f00 + f10 + f01 + f11) * SCALE_FACTOR_2D
+    }
+
+    fn get_many(&self, points: &[Point2<f64>], out: &mut [f64]) {
+        assert_eq!(points.len(), out.len());
+
+        // Neighbouring points usually share a cell, so only hash its corners
+        // again when the cell changes.
+        let mut cell = None;
+        let mut corners = [0; 4];
+        let mut corners_of = |floored: Point2<f64>| {
+            if cell != Some(floored) {
+                corners = self.perm_table.corners2(math::to_isize2(floored));
+                cell = Some(floored);
+            }
+            corners
+        };
+
+        for (&point, out) in points.iter().zip(out.iter_mut()) {
+            let floored = math::map2(point, f64::floor);
+            *out = perlin_2d(point, floored, &corners_of(floored));
+        }
     }
 }
 
@@ -146,7 +271,28 @@ impl NoiseFn<Point3<f64>> for Perlin {
                            [far_distance[0], far_distance[1], far_distance[2]]);
 
         // Multiply by arbitrary value to scale to -1..1
-        (f000 + f100 + f010 + f110 + f001 + f101 + f011 + f111) * 3.8898553255531074
+        (f000 + f100 + f010 + f110 + f001 + f101 + f011 + f111) * SCALE_FACTOR_3D
+    }
+
+    fn get_many(&self, points: &[Point3<f64>], out: &mut [f64]) {
+        assert_eq!(points.len(), out.len());
+
+        // Neighbouring points usually share a cell, so only hash its corners
+        // again when the cell changes.
+        let mut cell = None;
+        let mut corners = [0; 8];
+        let mut corners_of = |floored: Point3<f64>| {
+            if cell != Some(floored) {
+                corners = self.perm_table.corners3(math::to_isize3(floored));
+                cell = Some(floored);
+            }
+            corners
+        };
+
+        for (&point, out) in points.iter().zip(out.iter_mut()) {
+            let floored = math::map3(point, f64::floor);
+            *out = perlin_3d(point, floored, &corners_of(floored));
+        }
     }
 }
 
@@ -417,6 +563,245 @@ impl NoiseFn<Point4<f64>> for Perlin {
         // Multiply by arbitrary value to scale to -1..1
         (f0000 + f1000 + f0100 + f1100 + f0010 + f1010 + f0110 + f1110 +
              f0001 + f1001 + f0101 + f1101 + f0011 + f1011 + f0111 + f1111) *
-            4.424369240215691
+            SCALE_FACTOR_4D
+    }
+}
+
+/// 2-dimensional perlin noise, with its analytic gradient
+impl NoiseFnWithGradient<Point2<f64>> for Perlin {
+    fn get_with_gradient(&self, point: Point2<f64>) -> (f64, Vector2<f64>) {
+        #[inline(always)]
+        fn surflet(
+            perm_table: &PermutationTable,
+            corner: Point2<isize>,
+            distance: Vector2<f64>,
+        ) -> (f64, Vector2<f64>) {
+            let attn = 1.0 - math::dot2(distance, distance);
+            if attn > 0.0 {
+                let gradient = gradient::get2(perm_table.get2(corner));
+                let dot = math::dot2(distance, gradient);
+
+                // d(attn^4 * dot) = attn^4 * gradient - 8 * attn^3 * dot * distance
+                let derivative = math::add2(math::mul2(gradient, attn.powi(4)),
+                                            math::mul2(distance, -8.0 * attn.powi(3) * dot));
+                (attn.powi(4) * dot, derivative)
+            } else {
+                (0.0, [0.0; 2])
+            }
+        }
+
+        let floored = math::map2(point, f64::floor);
+        let near_corner = math::to_isize2(floored);
+        let far_corner = math::add2(near_corner, math::one2());
+        let near_distance = math::sub2(point, floored);
+        let far_distance = math::sub2(near_distance, math::one2());
+
+        let mut value = 0.0;
+        let mut gradient = [0.0; 2];
+
+        // Visit the corners in the same order as `get`, with bit n of the
+        // index selecting the far corner along axis n.
+        for i in 0..4 {
+            let (f, g) = surflet(&self.perm_table,
+                                 math::select2(i, near_corner, far_corner),
+                                 math::select2(i, near_distance, far_distance));
+            value += f;
+            gradient = math::add2(gradient, g);
+        }
+
+        (value * SCALE_FACTOR_2D, math::mul2(gradient, SCALE_FACTOR_2D))
+    }
+}
+
+/// 3-dimensional perlin noise, with its analytic gradient
+impl NoiseFnWithGradient<Point3<f64>> for Perlin {
+    fn get_with_gradient(&self, point: Point3<f64>) -> (f64, Vector3<f64>) {
+        #[inline(always)]
+        fn surflet(
+            perm_table: &PermutationTable,
+            corner: Point3<isize>,
+            distance: Vector3<f64>,
+        ) -> (f64, Vector3<f64>) {
+            let attn = 1.0 - math::dot3(distance, distance);
+            if attn > 0.0 {
+                let gradient = gradient::get3(perm_table.get3(corner));
+                let dot = math::dot3(distance, gradient);
+
+                // d(attn^4 * dot) = attn^4 * gradient - 8 * attn^3 * dot * distance
+                let derivative = math::add3(math::mul3(gradient, attn.powi(4)),
+                                            math::mul3(distance, -8.0 * attn.powi(3) * dot));
+                (attn.powi(4) * dot, derivative)
+            } else {
+                (0.0, [0.0; 3])
+            }
+        }
+
+        let floored = math::map3(point, f64::floor);
+        let near_corner = math::to_isize3(floored);
+        let far_corner = math::add3(near_corner, math::one3());
+        let near_distance = math::sub3(point, floored);
+        let far_distance = math::sub3(near_distance, math::one3());
+
+        let mut value = 0.0;
+        let mut gradient = [0.0; 3];
+
+        // Visit the corners in the same order as `get`, with bit n of the
+        // index selecting the far corner along axis n.
+        for i in 0..8 {
+            let (f, g) = surflet(&self.perm_table,
+                                 math::select3(i, near_corner, far_corner),
+                                 math::select3(i, near_distance, far_distance));
+            value += f;
+            gradient = math::add3(gradient, g);
+        }
+
+        (value * SCALE_FACTOR_3D, math::mul3(gradient, SCALE_FACTOR_3D))
+    }
+}
+
+/// 4-dimensional perlin noise, with its analytic gradient
+impl NoiseFnWithGradient<Point4<f64>> for Perlin {
+    fn get_with_gradient(&self, point: Point4<f64>) -> (f64, Vector4<f64>) {
+        #[inline(always)]
+        fn surflet(
+            perm_table: &PermutationTable,
+            corner: Point4<isize>,
+            distance: Vector4<f64>,
+        ) -> (f64, Vector4<f64>) {
+            let attn = 1.0 - math::dot4(distance, distance);
+            if attn > 0.0 {
+                let gradient = gradient::get4(perm_table.get4(corner));
+                let dot = math::dot4(distance, gradient);
+
+                // d(attn^4 * dot) = attn^4 * gradient - 8 * attn^3 * dot * distance
+                let derivative = math::add4(math::mul4(gradient, attn.powi(4)),
+                                            math::mul4(distance, -8.0 * attn.powi(3) * dot));
+                (attn.powi(4) * dot, derivative)
+            } else {
+                (0.0, [0.0; 4])
+            }
+        }
+
+        let floored = math::map4(point, f64::floor);
+        let near_corner = math::to_isize4(floored);
+        let far_corner = math::add4(near_corner, math::one4());
+        let near_distance = math::sub4(point, floored);
+        let far_distance = math::sub4(near_distance, math::one4());
+
+        let mut value = 0.0;
+        let mut gradient = [0.0; 4];
+
+        // Visit the corners in the same order as `get`, with bit n of the
+        // index selecting the far corner along axis n.
+        for i in 0..16 {
+            let (f, g) = surflet(&self.perm_table,
+                                 math::select4(i, near_corner, far_corner),
+                                 math::select4(i, near_distance, far_distance));
+            value += f;
+            gradient = math::add4(gradient, g);
+        }
+
+        (value * SCALE_FACTOR_4D, math::mul4(gradient, SCALE_FACTOR_4D))
+    }
+}
+
+/// Evaluates 2-dimensional Perlin noise at `point`, given the hashes of the
+/// corners of its cell as returned by `PermutationTable::corners2`.
+#[inline(always)]
+fn perlin_2d(point: Point2<f64>, floored: Point2<f64>, corners: &[usize; 4]) -> f64 {
+    let near_distance = math::sub2(point, floored);
+    let far_distance = math::sub2(near_distance, math::one2());
+
+    let mut value = 0.0;
+    for (i, &hash) in corners.iter().enumerate() {
+        let distance = math::select2(i, near_distance, far_distance);
+        let attn = 1.0 - math::dot2(distance, distance);
+        if attn > 0.0 {
+            value += attn.powi(4) * math::dot2(distance, gradient::get2(hash));
+        }
+    }
+
+    // Multiply by arbitrary value to scale to -1..1
+    value * SCALE_FACTOR_2D
+}
+
+/// Evaluates 3-dimensional Perlin noise at `point`, given the hashes of the
+/// corners of its cell as returned by `PermutationTable::corners3`.
+#[inline(always)]
+fn perlin_3d(point: Point3<f64>, floored: Point3<f64>, corners: &[usize; 8]) -> f64 {
+    let near_distance = math::sub3(point, floored);
+    let far_distance = math::sub3(near_distance, math::one3());
+
+    let mut value = 0.0;
+    for (i, &hash) in corners.iter().enumerate() {
+        let distance = math::select3(i, near_distance, far_distance);
+        let attn = 1.0 - math::dot3(distance, distance);
+        if attn > 0.0 {
+            value += attn.powi(4) * math::dot3(distance, gradient::get3(hash));
+        }
+    }
+
+    // Multiply by arbitrary value to scale to -1..1
+    value * SCALE_FACTOR_3D
+}
+
+#[cfg(test)]
+mod tests {
+    use noise_fns::tests::{assert_get_many_matches_get, assert_tiles};
+    use {NoiseFn, NoiseFnWithGradient, Periodic, Perlin, Seedable};
+
+    #[test]
+    fn test_get_many_matches_get() {
+        let perlin = Perlin::new();
+        let points: Vec<_> = (0..18)
+            .map(|i| [i as f64 * 0.37 - 3.0, i as f64 * -0.21, 0.5])
+            .collect();
+        let mut values = vec![0.0; points.len()];
+        perlin.get_many(&points, &mut values);
+
+        for (&point, &value) in points.iter().zip(&values) {
+            assert_eq!(value, perlin.get(point));
+        }
+    }
+
+    #[test]
+    fn test_get_many_matches_get_in_every_dimension() {
+        let perlin = Perlin::new();
+        assert_get_many_matches_get::<[f64; 2], _>(&perlin);
+        assert_get_many_matches_get::<[f64; 3], _>(&perlin);
+        assert_get_many_matches_get::<[f64; 4], _>(&perlin);
+    }
+
+    #[test]
+    fn test_gradient_matches_finite_differences() {
+        let perlin = Perlin::new();
+        let point = [1.3, -2.7, 0.4];
+        let (value, gradient) = perlin.get_with_gradient(point);
+        assert_eq!(value, perlin.get(point));
+
+        let h = 1e-6;
+        for axis in 0..3 {
+            let mut above = point;
+            let mut below = point;
+            above[axis] += h;
+            below[axis] -= h;
+            let estimate = (perlin.get(above) - perlin.get(below)) / (2.0 * h);
+            assert!((estimate - gradient[axis]).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_periodic_noise_tiles() {
+        assert_tiles(&Perlin::new().set_seed(3).set_period(Some(8)), 8.0);
+
+        let perlin = Perlin::new().set_period(Some(8)).set_seed(4);
+        assert_eq!(perlin.period(), Some(8));
+        assert_eq!(perlin.get([0.5, 0.5]), perlin.get([8.5, -7.5]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_period_panics() {
+        Perlin::new().set_period(Some(0));
     }
 }
@@ -7,13 +7,13 @@
 // except according to those terms.
 
 use {gradient, math};
-use math::{Point2, Point3};
-use noise_fns::{NoiseFn, Seedable};
-use permutationtable::PermutationTable;
+use math::{Point2, Point3, Vector2, Vector3};
+use noise_fns::{NoiseFn, NoiseFnWithGradient, Reseedable, Seedable};
+use permutationtable::{DEFAULT_LATTICE_MODE, LatticeMode, PermutationTable};
 use std::ops::Add;
 
 /// Default Seed for the `SuperSimplex` noise function.
-pub const DEFAULT_SUPER_SIMPLEX_SEED: u32 = 0;
+pub const DEFAULT_SUPER_SIMPLEX_SEED: u64 = 0;
 
 const TO_REAL_CONSTANT_2D: f64 = -0.211324865405187; // (1 / sqrt(2 + 1) - 1) / 2
 const TO_SIMPLEX_CONSTANT_2D: f64 = 0.366025403784439; // (sqrt(2 + 1) - 1) / 2
@@ -98,19 +98,85 @@ const LATTICE_LOOKUP_3D: [[i8; 3]; 4 * 16] =
      [1, 1, 1],[0, 1, 1],[1, 0, 1],[1, 1, 0]];
 
 /// Noise function that outputs 2/3-dimensional Super Simplex noise.
+///
+/// There is no 4-dimensional Super Simplex noise, so its gradients are also
+/// only available for 2D and 3D points.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "SuperSimplexConfig"))]
 pub struct SuperSimplex {
-    seed: u32,
+    seed: u64,
+    lattice_mode: LatticeMode,
+    #[cfg_attr(
+        feature = "serde",
+        serde(skip_serializing_if = "PermutationTable::is_seeded")
+    )]
     perm_table: PermutationTable,
 }
 
+/// Serialized form of `SuperSimplex`. The permutation table is
+/// rebuilt from the seed, unless a custom table was serialized.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct SuperSimplexConfig {
+    seed: u64,
+    #[serde(default)]
+    lattice_mode: LatticeMode,
+    // Only present for a custom permutation table.
+    perm_table: Option<PermutationTable>,
+}
+
+#[cfg(feature = "serde")]
+impl From<SuperSimplexConfig> for SuperSimplex {
+    fn from(config: SuperSimplexConfig) -> SuperSimplex {
+        let noise = SuperSimplex::new()
+            .set_lattice_mode(config.lattice_mode)
+            .set_seed(config.seed);
+
+        match config.perm_table {
+            Some(perm_table) => noise.set_permutation_table(perm_table),
+            None => noise,
+        }
+    }
+}
+
 impl SuperSimplex {
     pub fn new() -> SuperSimplex {
         SuperSimplex {
             seed: DEFAULT_SUPER_SIMPLEX_SEED,
+            lattice_mode: DEFAULT_LATTICE_MODE,
             perm_table: PermutationTable::new(DEFAULT_SUPER_SIMPLEX_SEED),
         }
     }
+
+    /// Replaces the permutation table used to hash the lattice points, e.g.
+    /// with one built by `PermutationTable::from_rng` or
+    /// `PermutationTable::from_permutation`.
+    ///
+    /// The seed and lattice mode are left as they were. Setting a different
+    /// seed afterwards rebuilds the table from the seed. The table is
+    /// serialized along with the noise function.
+    pub fn set_permutation_table(self, perm_table: PermutationTable) -> SuperSimplex {
+        SuperSimplex {
+            perm_table: perm_table.set_mode(self.lattice_mode),
+            ..self
+        }
+    }
+
+    /// Sets how the lattice points are hashed. `LatticeMode::Hash` avoids the
+    /// noise repeating every 256 units.
+    pub fn set_lattice_mode(self, lattice_mode: LatticeMode) -> SuperSimplex {
+        SuperSimplex {
+            lattice_mode: lattice_mode,
+            perm_table: self.perm_table.set_mode(lattice_mode),
+            ..self
+        }
+    }
+
+    /// Returns how the lattice points are hashed.
+    pub fn lattice_mode(&self) -> LatticeMode {
+        self.lattice_mode
+    }
 }
 
 impl Default for SuperSimplex {
@@ -121,7 +187,7 @@ impl Default for SuperSimplex {
 
 impl Seedable for SuperSimplex {
     /// Sets the seed value for Super Simplex noise
-    fn set_seed(self, seed: u32) -> SuperSimplex {
+    fn set_seed(self, seed: u64) -> SuperSimplex {
         // If the new seed is the same as the current seed, just return self.
         if self.seed == seed {
             return self;
@@ -130,115 +196,202 @@ impl Seedable for SuperSimplex {
         // Otherwise, regenerate the permutation table based on the new seed.
         SuperSimplex {
             seed: seed,
-            perm_table: PermutationTable::new(seed),
+            perm_table: PermutationTable::new(seed).set_mode(self.lattice_mode),
+            ..self
         }
     }
 
-    fn seed(&self) -> u32 {
+    fn seed(&self) -> u64 {
         self.seed
     }
 }
 
+impl Reseedable for SuperSimplex {
+    fn reseed(self, seed: u64) -> SuperSimplex {
+        self.set_seed(seed)
+    }
+}
+
 /// 2-dimensional Super Simplex noise
 impl NoiseFn<Point2<f64>> for SuperSimplex {
     fn get(&self, point: Point2<f64>) -> f64 {
         let mut value = 0.0;
-
-        // Transform point from real space to simplex space
-        let to_simplex_offset = math::fold2(point, Add::add) * TO_SIMPLEX_CONSTANT_2D;
-        let simplex_point = math::map2(point, |v| v + to_simplex_offset);
-
-        // Get base point of simplex and barycentric coordinates in simplex space
-        let simplex_base_point = math::map2(simplex_point, f64::floor);
-        let simplex_base_point_i = math::to_isize2(simplex_base_point);
-        let simplex_rel_coords = math::sub2(simplex_point, simplex_base_point);
-
-        // Create index to lookup table from barycentric coordinates
-        let region_sum = math::fold2(simplex_rel_coords, Add::add).floor();
-        let index = ((region_sum >= 1.0) as usize) << 2 |
-            ((simplex_rel_coords[0] - simplex_rel_coords[1] * 0.5 + 1.0 -
-                  region_sum * 0.5 >= 1.0) as usize) << 3 |
-            ((simplex_rel_coords[1] - simplex_rel_coords[0] * 0.5 + 1.0 -
-                  region_sum * 0.5 >= 1.0) as usize) << 4;
-
-        // Transform barycentric coordinates to real space
-        let to_real_offset = math::fold2(simplex_rel_coords, Add::add) * TO_REAL_CONSTANT_2D;
-        let real_rel_coords = math::map2(simplex_rel_coords, |v| v + to_real_offset);
-
-        for lattice_lookup in &LATTICE_LOOKUP_2D[index..index + 4] {
-            let dpos = math::add2(real_rel_coords, math::cast2(lattice_lookup.1));
+        super_simplex_2d(point, |lattice_point, dpos| {
             let attn = (2.0 / 3.0) - math::dot2(dpos, dpos);
             if attn > 0.0 {
-                let lattice_point = math::add2(simplex_base_point_i, math::cast2(lattice_lookup.0));
                 let gradient = gradient::get2(self.perm_table.get2(lattice_point));
                 value += attn.powi(4) * math::dot2(gradient, dpos);
             }
-        }
+        });
 
         value * NORM_CONSTANT_2D
     }
 }
 
+/// 2-dimensional Super Simplex noise, with its analytic gradient
+impl NoiseFnWithGradient<Point2<f64>> for SuperSimplex {
+    fn get_with_gradient(&self, point: Point2<f64>) -> (f64, Vector2<f64>) {
+        let mut value = 0.0;
+        let mut derivative = [0.0; 2];
+        super_simplex_2d(point, |lattice_point, dpos| {
+            let attn = (2.0 / 3.0) - math::dot2(dpos, dpos);
+            if attn > 0.0 {
+                let gradient = gradient::get2(self.perm_table.get2(lattice_point));
+                let dot = math::dot2(gradient, dpos);
+                value += attn.powi(4) * dot;
+
+                // `dpos` moves one-to-one with `point`, so no change of basis is needed.
+                derivative = math::add2(derivative, math::mul2(gradient, attn.powi(4)));
+                derivative = math::add2(derivative, math::mul2(dpos, -8.0 * attn.powi(3) * dot));
+            }
+        });
+
+        (value * NORM_CONSTANT_2D, math::mul2(derivative, NORM_CONSTANT_2D))
+    }
+}
+
+/// Visits each lattice point that contributes to the 2-dimensional noise value
+/// at `point`, passing the lattice point and the position of `point` relative
+/// to it to `contribute`.
+#[inline(always)]
+fn super_simplex_2d<F>(point: Point2<f64>, mut contribute: F)
+where
+    F: FnMut(Point2<isize>, Vector2<f64>),
+{
+    // Transform point from real space to simplex space
+    let to_simplex_offset = math::fold2(point, Add::add) * TO_SIMPLEX_CONSTANT_2D;
+    let simplex_point = math::map2(point, |v| v + to_simplex_offset);
+
+    // Get base point of simplex and barycentric coordinates in simplex space
+    let simplex_base_point = math::map2(simplex_point, f64::floor);
+    let simplex_base_point_i = math::to_isize2(simplex_base_point);
+    let simplex_rel_coords = math::sub2(simplex_point, simplex_base_point);
+
+    // Create index to lookup table from barycentric coordinates
+    let region_sum = math::fold2(simplex_rel_coords, Add::add).floor();
+    let index = ((region_sum >= 1.0) as usize) << 2 |
+        ((simplex_rel_coords[0] - simplex_rel_coords[1] * 0.5 + 1.0 -
+              region_sum * 0.5 >= 1.0) as usize) << 3 |
+        ((simplex_rel_coords[1] - simplex_rel_coords[0] * 0.5 + 1.0 -
+              region_sum * 0.5 >= 1.0) as usize) << 4;
+
+    // Transform barycentric coordinates to real space
+    let to_real_offset = math::fold2(simplex_rel_coords, Add::add) * TO_REAL_CONSTANT_2D;
+    let real_rel_coords = math::map2(simplex_rel_coords, |v| v + to_real_offset);
+
+    for lattice_lookup in &LATTICE_LOOKUP_2D[index..index + 4] {
+        let dpos = math::add2(real_rel_coords, math::cast2(lattice_lookup.1));
+        let lattice_point = math::add2(simplex_base_point_i, math::cast2(lattice_lookup.0));
+        contribute(lattice_point, dpos);
+    }
+}
+
 /// 3-dimensional Super Simplex noise
 impl NoiseFn<Point3<f64>> for SuperSimplex {
     fn get(&self, point: Point3<f64>) -> f64 {
         let mut value = 0.0;
-
-        // Transform point from real space to simplex space
-        let to_simplex_offset = math::fold3(point, Add::add) * TO_SIMPLEX_CONSTANT_3D;
-        let simplex_point = math::map3(point, |v| -(v + to_simplex_offset));
-        let second_simplex_point = math::map3(simplex_point, |v| v + 512.5);
-
-        // Get base point of simplex and barycentric coordinates in simplex space
-        let simplex_base_point = math::map3(simplex_point, f64::floor);
-        let simplex_base_point_i = math::to_isize3(simplex_base_point);
-        let simplex_rel_coords = math::sub3(simplex_point, simplex_base_point);
-        let second_simplex_base_point = math::map3(second_simplex_point, f64::floor);
-        let second_simplex_base_point_i = math::to_isize3(second_simplex_base_point);
-        let second_simplex_rel_coords = math::sub3(second_simplex_point, second_simplex_base_point);
-
-        // Create indices to lookup table from barycentric coordinates
-        let index = ((simplex_rel_coords[0] + simplex_rel_coords[1] +
-                          simplex_rel_coords[2] >= 1.5) as usize) << 2 |
-            ((-simplex_rel_coords[0] + simplex_rel_coords[1] + simplex_rel_coords[2] >=
-                  0.5) as usize) << 3 |
-            ((simplex_rel_coords[0] - simplex_rel_coords[1] + simplex_rel_coords[2] >=
-                 0.5) as usize) << 4 |
-            ((simplex_rel_coords[0] + simplex_rel_coords[1] - simplex_rel_coords[2] >= 0.5) as
-                 usize) << 5;
-        let second_index = ((second_simplex_rel_coords[0] + second_simplex_rel_coords[1] +
-                                 second_simplex_rel_coords[2] >=
-                                 1.5) as usize) << 2 |
-            ((-second_simplex_rel_coords[0] + second_simplex_rel_coords[1] +
-                  second_simplex_rel_coords[2] >= 0.5) as usize) << 3 |
-            ((second_simplex_rel_coords[0] - second_simplex_rel_coords[1] +
-                  second_simplex_rel_coords[2] >= 0.5) as usize) << 4 |
-            ((second_simplex_rel_coords[0] + second_simplex_rel_coords[1] -
-                  second_simplex_rel_coords[2] >= 0.5) as usize) << 5;
-
-        // Sum contributions from first lattice
-        for &lattice_lookup in &LATTICE_LOOKUP_3D[index..index + 4] {
-            let dpos = math::sub3(simplex_rel_coords, math::cast3(lattice_lookup));
+        super_simplex_3d(point, |lattice_point, dpos| {
             let attn = 0.75 - math::dot3(dpos, dpos);
             if attn > 0.0 {
-                let lattice_point = math::add3(simplex_base_point_i, math::cast3(lattice_lookup));
                 let gradient = gradient::get3(self.perm_table.get3(lattice_point));
                 value += attn.powi(4) * math::dot3(gradient, dpos);
             }
-        }
+        });
 
-        // Sum contributions from second lattice
-        for &lattice_lookup in &LATTICE_LOOKUP_3D[second_index..second_index + 4] {
-            let dpos = math::sub3(second_simplex_rel_coords, math::cast3(lattice_lookup));
+        value * NORM_CONSTANT_3D
+    }
+}
+
+/// 3-dimensional Super Simplex noise, with its analytic gradient
+impl NoiseFnWithGradient<Point3<f64>> for SuperSimplex {
+    fn get_with_gradient(&self, point: Point3<f64>) -> (f64, Vector3<f64>) {
+        let mut value = 0.0;
+        let mut derivative = [0.0; 3];
+        super_simplex_3d(point, |lattice_point, dpos| {
             let attn = 0.75 - math::dot3(dpos, dpos);
             if attn > 0.0 {
-                let lattice_point = math::add3(second_simplex_base_point_i,
-                                               math::cast3(lattice_lookup));
                 let gradient = gradient::get3(self.perm_table.get3(lattice_point));
-                value += attn.powi(4) * math::dot3(gradient, dpos);
+                let dot = math::dot3(gradient, dpos);
+                value += attn.powi(4) * dot;
+                derivative = math::add3(derivative, math::mul3(gradient, attn.powi(4)));
+                derivative = math::add3(derivative, math::mul3(dpos, -8.0 * attn.powi(3) * dot));
             }
-        }
+        });
 
-        value * NORM_CONSTANT_3D
+        // `derivative` is with respect to simplex space. The transform into
+        // simplex space is `v -> 2/3 * sum(v) - v`, which is its own inverse.
+        let to_real_offset = math::fold3(derivative, Add::add) * -TO_SIMPLEX_CONSTANT_3D;
+        let derivative = math::map3(derivative, |v| to_real_offset - v);
+
+        (value * NORM_CONSTANT_3D, math::mul3(derivative, NORM_CONSTANT_3D))
+    }
+}
+
+/// Visits each lattice point that contributes to the 3-dimensional noise value
+/// at `point`, passing the lattice point and the position of `point` relative
+/// to it, in simplex space, to `contribute`.
+#[inline(always)]
+fn super_simplex_3d<F>(point: Point3<f64>, mut contribute: F)
+where
+    F: FnMut(Point3<isize>, Vector3<f64>),
+{
+    // Transform point from real space to simplex space
+    let to_simplex_offset = math::fold3(point, Add::add) * TO_SIMPLEX_CONSTANT_3D;
+    let simplex_point = math::map3(point, |v| -(v + to_simplex_offset));
+    let second_simplex_point = math::map3(simplex_point, |v| v + 512.5);
+
+    // Get base point of simplex and barycentric coordinates in simplex space
+    let simplex_base_point = math::map3(simplex_point, f64::floor);
+    let simplex_base_point_i = math::to_isize3(simplex_base_point);
+    let simplex_rel_coords = math::sub3(simplex_point, simplex_base_point);
+    let second_simplex_base_point = math::map3(second_simplex_point, f64::floor);
+    let second_simplex_base_point_i = math::to_isize3(second_simplex_base_point);
+    let second_simplex_rel_coords = math::sub3(second_simplex_point, second_simplex_base_point);
+
+    // Create indices to lookup table from barycentric coordinates
+    let index = ((simplex_rel_coords[0] + simplex_rel_coords[1] +
+                      simplex_rel_coords[2] >= 1.5) as usize) << 2 |
+        ((-simplex_rel_coords[0] + simplex_rel_coords[1] + simplex_rel_coords[2] >=
+              0.5) as usize) << 3 |
+        ((simplex_rel_coords[0] - simplex_rel_coords[1] + simplex_rel_coords[2] >=
+             0.5) as usize) << 4 |
+        ((simplex_rel_coords[0] + simplex_rel_coords[1] - simplex_rel_coords[2] >= 0.5) as
+             usize) << 5;
+    let second_index = ((second_simplex_rel_coords[0] + second_simplex_rel_coords[1] +
+                             second_simplex_rel_coords[2] >=
+                             1.5) as usize) << 2 |
+        ((-second_simplex_rel_coords[0] + second_simplex_rel_coords[1] +
+              second_simplex_rel_coords[2] >= 0.5) as usize) << 3 |
+        ((second_simplex_rel_coords[0] - second_simplex_rel_coords[1] +
+              second_simplex_rel_coords[2] >= 0.5) as usize) << 4 |
+        ((second_simplex_rel_coords[0] + second_simplex_rel_coords[1] -
+              second_simplex_rel_coords[2] >= 0.5) as usize) << 5;
+
+    // Sum contributions from first lattice
+    for &lattice_lookup in &LATTICE_LOOKUP_3D[index..index + 4] {
+        let dpos = math::sub3(simplex_rel_coords, math::cast3(lattice_lookup));
+        let lattice_point = math::add3(simplex_base_point_i, math::cast3(lattice_lookup));
+        contribute(lattice_point, dpos);
+    }
+
+    // Sum contributions from second lattice
+    for &lattice_lookup in &LATTICE_LOOKUP_3D[second_index..second_index + 4] {
+        let dpos = math::sub3(second_simplex_rel_coords, math::cast3(lattice_lookup));
+        let lattice_point = math::add3(second_simplex_base_point_i,
+                                       math::cast3(lattice_lookup));
+        contribute(lattice_point, dpos);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use noise_fns::tests::assert_gradient_matches;
+    use {Seedable, SuperSimplex};
+
+    #[test]
+    fn test_gradient_matches_finite_differences() {
+        let simplex = SuperSimplex::new().set_seed(5);
+        assert_gradient_matches(&simplex, &[[1.3, -2.7], [-0.6, 8.2]]);
+        assert_gradient_matches(&simplex, &[[1.3, -2.7, 0.4], [-0.6, 8.2, -3.3]]);
     }
 }
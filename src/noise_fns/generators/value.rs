@@ -8,27 +8,97 @@
 
 
 use math;
-use math::{Point2, Point3, Point4, interp};
-use noise_fns::{NoiseFn, Seedable};
-use permutationtable::PermutationTable;
+use math::{Point2, Point3, Point4, Vector2, Vector3, Vector4, interp};
+use noise_fns::{NoiseFn, NoiseFnWithGradient, Periodic, Reseedable, Seedable};
+use permutationtable::{DEFAULT_LATTICE_MODE, LatticeMode, PermutationTable};
 
 /// Default Seed for the Value noise function.
-pub const DEFAULT_VALUE_SEED: u32 = 0;
+pub const DEFAULT_VALUE_SEED: u64 = 0;
 
 /// Noise function that outputs 2/3/4-dimensional Value noise.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "ValueConfig"))]
 pub struct Value {
-    seed: u32,
+    seed: u64,
+    lattice_mode: LatticeMode,
+    period: Option<usize>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(skip_serializing_if = "PermutationTable::is_seeded")
+    )]
     perm_table: PermutationTable,
 }
 
+/// Serialized form of `Value`. The permutation table is
+/// rebuilt from the seed, unless a custom table was serialized.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct ValueConfig {
+    seed: u64,
+    #[serde(default)]
+    lattice_mode: LatticeMode,
+    #[serde(default)]
+    period: Option<usize>,
+    // Only present for a custom permutation table.
+    perm_table: Option<PermutationTable>,
+}
+
+#[cfg(feature = "serde")]
+impl From<ValueConfig> for Value {
+    fn from(config: ValueConfig) -> Value {
+        let noise = Value::new()
+            .set_lattice_mode(config.lattice_mode)
+            .set_period(config.period)
+            .set_seed(config.seed);
+
+        match config.perm_table {
+            Some(perm_table) => noise.set_permutation_table(perm_table),
+            None => noise,
+        }
+    }
+}
+
 impl Value {
     pub fn new() -> Value {
         Value {
             seed: DEFAULT_VALUE_SEED,
+            lattice_mode: DEFAULT_LATTICE_MODE,
+            period: None,
             perm_table: PermutationTable::new(DEFAULT_VALUE_SEED),
         }
     }
+
+    /// Replaces the permutation table used to hash the lattice points, e.g.
+    /// with one built by `PermutationTable::from_rng` or
+    /// `PermutationTable::from_permutation`.
+    ///
+    /// The seed, lattice mode and period are left as they were. Setting a
+    /// different seed afterwards rebuilds the table from the seed. The table is
+    /// serialized along with the noise function.
+    pub fn set_permutation_table(self, perm_table: PermutationTable) -> Value {
+        Value {
+            perm_table: perm_table
+                .set_mode(self.lattice_mode)
+                .set_period(self.period),
+            ..self
+        }
+    }
+
+    /// Sets how the lattice points are hashed. `LatticeMode::Hash` avoids the
+    /// noise repeating every 256 units.
+    pub fn set_lattice_mode(self, lattice_mode: LatticeMode) -> Value {
+        Value {
+            lattice_mode: lattice_mode,
+            perm_table: self.perm_table.set_mode(lattice_mode),
+            ..self
+        }
+    }
+
+    /// Returns how the lattice points are hashed.
+    pub fn lattice_mode(&self) -> LatticeMode {
+        self.lattice_mode
+    }
 }
 
 impl Default for Value {
@@ -39,7 +109,7 @@ impl Default for Value {
 
 impl Seedable for Value {
     /// Sets the seed value for Value noise
-    fn set_seed(self, seed: u32) -> Value {
+    fn set_seed(self, seed: u64) -> Value {
         // If the new seed is the same as the current seed, just return self.
         if self.seed == seed {
             return self;
@@ -48,15 +118,44 @@ impl Seedable for Value {
         // Otherwise, regenerate the permutation table based on the new seed.
         Value {
             seed: seed,
-            perm_table: PermutationTable::new(seed),
+            perm_table: PermutationTable::new(seed)
+                .set_mode(self.lattice_mode)
+                .set_period(self.period),
+            ..self
         }
     }
 
-    fn seed(&self) -> u32 {
+    fn seed(&self) -> u64 {
         self.seed
     }
 }
 
+impl Periodic for Value {
+    /// Sets the period after which the noise repeats. The lattice points are
+    /// wrapped modulo the period before they are hashed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the period is `Some(0)`.
+    fn set_period(self, period: Option<usize>) -> Value {
+        Value {
+            period: period,
+            perm_table: self.perm_table.set_period(period),
+            ..self
+        }
+    }
+
+    fn period(&self) -> Option<usize> {
+        self.period
+    }
+}
+
+impl Reseedable for Value {
+    fn reseed(self, seed: u64) -> Value {
+        self.set_seed(seed)
+    }
+}
+
 /// 2-dimensional value noise
 impl NoiseFn<Point2<f64>> for Value {
     fn get(&self, point: Point2<f64>) -> f64 {
@@ -81,6 +180,27 @@ impl NoiseFn<Point2<f64>> for Value {
 
         d * 2.0 - 1.0
     }
+
+    fn get_many(&self, points: &[Point2<f64>], out: &mut [f64]) {
+        assert_eq!(points.len(), out.len());
+
+        // Neighbouring points usually share a cell, so only hash its corners
+        // again when the cell changes.
+        let mut cell = None;
+        let mut corners = [0; 4];
+        let mut corners_of = |floored: Point2<f64>| {
+            if cell != Some(floored) {
+                corners = self.perm_table.corners2(math::to_isize2(floored));
+                cell = Some(floored);
+            }
+            corners
+        };
+
+        for (&point, out) in points.iter().zip(out.iter_mut()) {
+            let floored = math::map2(point, f64::floor);
+            *out = value_2d(point, floored, &corners_of(floored));
+        }
+    }
 }
 
 /// 3-dimensional value noise
@@ -123,6 +243,27 @@ impl NoiseFn<Point3<f64>> for Value {
 
         d * 2.0 - 1.0
     }
+
+    fn get_many(&self, points: &[Point3<f64>], out: &mut [f64]) {
+        assert_eq!(points.len(), out.len());
+
+        // Neighbouring points usually share a cell, so only hash its corners
+        // again when the cell changes.
+        let mut cell = None;
+        let mut corners = [0; 8];
+        let mut corners_of = |floored: Point3<f64>| {
+            if cell != Some(floored) {
+                corners = self.perm_table.corners3(math::to_isize3(floored));
+                cell = Some(floored);
+            }
+            corners
+        };
+
+        for (&point, out) in points.iter().zip(out.iter_mut()) {
+            let floored = math::map3(point, f64::floor);
+            *out = value_3d(point, floored, &corners_of(floored));
+        }
+    }
 }
 
 /// 4-dimensional value noise
@@ -301,4 +442,255 @@ impl NoiseFn<Point4<f64>> for Value {
 
         d * 2.0 - 1.0
     }
+
+    fn get_many(&self, points: &[Point4<f64>], out: &mut [f64]) {
+        assert_eq!(points.len(), out.len());
+
+        // Neighbouring points usually share a cell, so only hash its corners
+        // again when the cell changes.
+        let mut cell = None;
+        let mut corners = [0; 16];
+        let mut corners_of = |floored: Point4<f64>| {
+            if cell != Some(floored) {
+                corners = self.perm_table.corners4(math::to_isize4(floored));
+                cell = Some(floored);
+            }
+            corners
+        };
+
+        for (&point, out) in points.iter().zip(out.iter_mut()) {
+            let floored = math::map4(point, f64::floor);
+            *out = value_4d(point, floored, &corners_of(floored));
+        }
+    }
+}
+
+/// 2-dimensional value noise, with its analytic gradient
+impl NoiseFnWithGradient<Point2<f64>> for Value {
+    fn get_with_gradient(&self, point: Point2<f64>) -> (f64, Vector2<f64>) {
+        #[inline(always)]
+        fn get(perm_table: &PermutationTable, corner: math::Point2<isize>) -> (f64, Vector2<f64>) {
+            (perm_table.get2(corner) as f64 / 255.0, [0.0; 2])
+        }
+
+        // Interpolates between `a` and `b` along `axis`. The gradients of `a`
+        // and `b` are interpolated as well, and the component along `axis` is
+        // filled in from the slope between the two values.
+        #[inline(always)]
+        fn linear(
+            a: (f64, Vector2<f64>),
+            b: (f64, Vector2<f64>),
+            axis: usize,
+            weight: Vector2<f64>,
+            weight_derivative: Vector2<f64>,
+        ) -> (f64, Vector2<f64>) {
+            let mut gradient = math::zip_with2(a.1, b.1, |a, b| interp::linear(a, b, weight[axis]));
+            gradient[axis] = (b.0 - a.0) * weight_derivative[axis];
+            (interp::linear(a.0, b.0, weight[axis]), gradient)
+        }
+
+        let floored = math::map2(point, f64::floor);
+        let near_corner = math::to_isize2(floored);
+        let far_corner = math::add2(near_corner, math::one2());
+        let distance = math::sub2(point, floored);
+        let weight = math::map2(distance, interp::s_curve5);
+        let weight_derivative = math::map2(distance, interp::s_curve5_derivative);
+
+        // Bit n of the index selects the far corner along axis n.
+        let f = |i| get(&self.perm_table, math::select2(i, near_corner, far_corner));
+        let linear = |a, b, axis| linear(a, b, axis, weight, weight_derivative);
+
+        let d0 = linear(f(0), f(1), 0);
+        let d1 = linear(f(2), f(3), 0);
+        let d = linear(d0, d1, 1);
+
+        (d.0 * 2.0 - 1.0, math::mul2(d.1, 2.0))
+    }
+}
+
+/// 3-dimensional value noise, with its analytic gradient
+impl NoiseFnWithGradient<Point3<f64>> for Value {
+    fn get_with_gradient(&self, point: Point3<f64>) -> (f64, Vector3<f64>) {
+        #[inline(always)]
+        fn get(perm_table: &PermutationTable, corner: math::Point3<isize>) -> (f64, Vector3<f64>) {
+            (perm_table.get3(corner) as f64 / 255.0, [0.0; 3])
+        }
+
+        // Interpolates between `a` and `b` along `axis`. The gradients of `a`
+        // and `b` are interpolated as well, and the component along `axis` is
+        // filled in from the slope between the two values.
+        #[inline(always)]
+        fn linear(
+            a: (f64, Vector3<f64>),
+            b: (f64, Vector3<f64>),
+            axis: usize,
+            weight: Vector3<f64>,
+            weight_derivative: Vector3<f64>,
+        ) -> (f64, Vector3<f64>) {
+            let mut gradient = math::zip_with3(a.1, b.1, |a, b| interp::linear(a, b, weight[axis]));
+            gradient[axis] = (b.0 - a.0) * weight_derivative[axis];
+            (interp::linear(a.0, b.0, weight[axis]), gradient)
+        }
+
+        let floored = math::map3(point, f64::floor);
+        let near_corner = math::to_isize3(floored);
+        let far_corner = math::add3(near_corner, math::one3());
+        let distance = math::sub3(point, floored);
+        let weight = math::map3(distance, interp::s_curve5);
+        let weight_derivative = math::map3(distance, interp::s_curve5_derivative);
+
+        // Bit n of the index selects the far corner along axis n.
+        let f = |i| get(&self.perm_table, math::select3(i, near_corner, far_corner));
+        let linear = |a, b, axis| linear(a, b, axis, weight, weight_derivative);
+
+        let d00 = linear(f(0), f(1), 0);
+        let d01 = linear(f(4), f(5), 0);
+        let d10 = linear(f(2), f(3), 0);
+        let d11 = linear(f(6), f(7), 0);
+        let d0 = linear(d00, d10, 1);
+        let d1 = linear(d01, d11, 1);
+        let d = linear(d0, d1, 2);
+
+        (d.0 * 2.0 - 1.0, math::mul3(d.1, 2.0))
+    }
+}
+
+/// 4-dimensional value noise, with its analytic gradient
+impl NoiseFnWithGradient<Point4<f64>> for Value {
+    fn get_with_gradient(&self, point: Point4<f64>) -> (f64, Vector4<f64>) {
+        #[inline(always)]
+        fn get(perm_table: &PermutationTable, corner: math::Point4<isize>) -> (f64, Vector4<f64>) {
+            (perm_table.get4(corner) as f64 / 255.0, [0.0; 4])
+        }
+
+        // Interpolates between `a` and `b` along `axis`. The gradients of `a`
+        // and `b` are interpolated as well, and the component along `axis` is
+        // filled in from the slope between the two values.
+        #[inline(always)]
+        fn linear(
+            a: (f64, Vector4<f64>),
+            b: (f64, Vector4<f64>),
+            axis: usize,
+            weight: Vector4<f64>,
+            weight_derivative: Vector4<f64>,
+        ) -> (f64, Vector4<f64>) {
+            let mut gradient = math::zip_with4(a.1, b.1, |a, b| interp::linear(a, b, weight[axis]));
+            gradient[axis] = (b.0 - a.0) * weight_derivative[axis];
+            (interp::linear(a.0, b.0, weight[axis]), gradient)
+        }
+
+        let floored = math::map4(point, f64::floor);
+        let near_corner = math::to_isize4(floored);
+        let far_corner = math::add4(near_corner, math::one4());
+        let distance = math::sub4(point, floored);
+        let weight = math::map4(distance, interp::s_curve5);
+        let weight_derivative = math::map4(distance, interp::s_curve5_derivative);
+
+        // Bit n of the index selects the far corner along axis n.
+        let f = |i| get(&self.perm_table, math::select4(i, near_corner, far_corner));
+        let linear = |a, b, axis| linear(a, b, axis, weight, weight_derivative);
+
+        let d000 = linear(f(0), f(1), 0);
+        let d010 = linear(f(4), f(5), 0);
+        let d100 = linear(f(2), f(3), 0);
+        let d110 = linear(f(6), f(7), 0);
+        let d001 = linear(f(8), f(9), 0);
+        let d011 = linear(f(12), f(13), 0);
+        let d101 = linear(f(10), f(11), 0);
+        let d111 = linear(f(14), f(15), 0);
+        let d00 = linear(d000, d100, 1);
+        let d10 = linear(d010, d110, 1);
+        let d01 = linear(d001, d101, 1);
+        let d11 = linear(d011, d111, 1);
+        let d0 = linear(d00, d10, 2);
+        let d1 = linear(d01, d11, 2);
+        let d = linear(d0, d1, 3);
+
+        (d.0 * 2.0 - 1.0, math::mul4(d.1, 2.0))
+    }
+}
+
+/// Evaluates 2-dimensional value noise at `point`, given the hashes of the
+/// corners of its cell as returned by `PermutationTable::corners2`.
+#[inline(always)]
+fn value_2d(point: Point2<f64>, floored: Point2<f64>, corners: &[usize; 4]) -> f64 {
+    let weight = math::map2(math::sub2(point, floored), interp::s_curve5);
+    let f = |i: usize| corners[i] as f64 / 255.0;
+
+    let d0 = interp::linear(f(0), f(1), weight[0]);
+    let d1 = interp::linear(f(2), f(3), weight[0]);
+    let d = interp::linear(d0, d1, weight[1]);
+
+    d * 2.0 - 1.0
+}
+
+/// Evaluates 3-dimensional value noise at `point`, given the hashes of the
+/// corners of its cell as returned by `PermutationTable::corners3`.
+#[inline(always)]
+fn value_3d(point: Point3<f64>, floored: Point3<f64>, corners: &[usize; 8]) -> f64 {
+    let weight = math::map3(math::sub3(point, floored), interp::s_curve5);
+    let f = |i: usize| corners[i] as f64 / 255.0;
+
+    let d00 = interp::linear(f(0), f(1), weight[0]);
+    let d01 = interp::linear(f(4), f(5), weight[0]);
+    let d10 = interp::linear(f(2), f(3), weight[0]);
+    let d11 = interp::linear(f(6), f(7), weight[0]);
+    let d0 = interp::linear(d00, d10, weight[1]);
+    let d1 = interp::linear(d01, d11, weight[1]);
+    let d = interp::linear(d0, d1, weight[2]);
+
+    d * 2.0 - 1.0
+}
+
+/// Evaluates 4-dimensional value noise at `point`, given the hashes of the
+/// corners of its cell as returned by `PermutationTable::corners4`.
+#[inline(always)]
+fn value_4d(point: Point4<f64>, floored: Point4<f64>, corners: &[usize; 16]) -> f64 {
+    let weight = math::map4(math::sub4(point, floored), interp::s_curve5);
+    let f = |i: usize| corners[i] as f64 / 255.0;
+
+    let d000 = interp::linear(f(0), f(1), weight[0]);
+    let d010 = interp::linear(f(4), f(5), weight[0]);
+    let d100 = interp::linear(f(2), f(3), weight[0]);
+    let d110 = interp::linear(f(6), f(7), weight[0]);
+    let d001 = interp::linear(f(8), f(9), weight[0]);
+    let d011 = interp::linear(f(12), f(13), weight[0]);
+    let d101 = interp::linear(f(10), f(11), weight[0]);
+    let d111 = interp::linear(f(14), f(15), weight[0]);
+    let d00 = interp::linear(d000, d100, weight[1]);
+    let d10 = interp::linear(d010, d110, weight[1]);
+    let d01 = interp::linear(d001, d101, weight[1]);
+    let d11 = interp::linear(d011, d111, weight[1]);
+    let d0 = interp::linear(d00, d10, weight[2]);
+    let d1 = interp::linear(d01, d11, weight[2]);
+    let d = interp::linear(d0, d1, weight[3]);
+
+    d * 2.0 - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use noise_fns::tests::{assert_get_many_matches_get, assert_gradient_matches, assert_tiles};
+    use {Periodic, Seedable, Value};
+
+    #[test]
+    fn test_get_many_matches_get() {
+        let value = Value::new().set_seed(5);
+        assert_get_many_matches_get::<[f64; 2], _>(&value);
+        assert_get_many_matches_get::<[f64; 3], _>(&value);
+        assert_get_many_matches_get::<[f64; 4], _>(&value);
+    }
+
+    #[test]
+    fn test_gradient_matches_finite_differences() {
+        let value = Value::new().set_seed(5);
+        assert_gradient_matches(&value, &[[1.3, -2.7], [-0.6, 8.2]]);
+        assert_gradient_matches(&value, &[[1.3, -2.7, 0.4], [-0.6, 8.2, -3.3]]);
+        assert_gradient_matches(&value, &[[1.3, -2.7, 0.4, 5.1], [-0.6, 8.2, -3.3, 0.9]]);
+    }
+
+    #[test]
+    fn test_periodic_noise_tiles() {
+        assert_tiles(&Value::new().set_period(Some(13)), 13.0);
+    }
 }
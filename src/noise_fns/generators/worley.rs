@@ -8,29 +8,61 @@
 
 use math;
 use math::{Point2, Point3, Point4};
-use noise_fns::{NoiseFn, Seedable};
-use permutationtable::PermutationTable;
+use noise_fns::{Constant, NoiseFn, Periodic, Reseedable, Seedable, derive_seed};
+use permutationtable::{DEFAULT_LATTICE_MODE, LatticeMode, PermutationTable};
 use std;
 
 /// Default noise seed for the `Worley` noise function.
-pub const DEFAULT_WORLEY_SEED: u32 = 0;
+pub const DEFAULT_WORLEY_SEED: u64 = 0;
 /// Default `RangeFunction` for the `Worley` noise function.
 pub const DEFAULT_WORLEY_RANGEFUNCTION: RangeFunction = RangeFunction::Euclidean;
 /// Default frequency for the `Worley` noise function.
 pub const DEFAULT_WORLEY_FREQUENCY: f64 = 1.0;
 /// Default displacement for the `Worley` noise function.
 pub const DEFAULT_WORLEY_DISPLACEMENT: f64 = 1.0;
+/// Default jitter for the `Worley` noise function.
+pub const DEFAULT_WORLEY_JITTER: f64 = 1.0;
+/// Default `ReturnType` for the `Worley` noise function.
+pub const DEFAULT_WORLEY_RETURN_TYPE: ReturnType = ReturnType::Value;
+
+/// Furthest cells, in steps along any axis, searched for seed points.
+const MAX_SEARCH_RADIUS: usize = 2;
+/// Number of cells searched along each axis.
+const SEARCH_STEPS: usize = 2 * MAX_SEARCH_RADIUS + 1;
+
+/// Returns the cells next to a point other than the nearest lattice cell, as
+/// masks of the axes along which they are a step from it towards the point.
+/// They are searched in this order, by the number of steps and then by axis.
+#[inline(always)]
+fn near_cells(dim: usize) -> &'static [usize] {
+    match dim {
+        2 => &[0b01, 0b10, 0b11],
+        3 => &[0b001, 0b010, 0b100, 0b011, 0b101, 0b110, 0b111],
+        _ => &[
+            0b0001, 0b0010, 0b0100, 0b1000, 0b0011, 0b0101, 0b1001, 0b0110, 0b1010, 0b1100, 0b0111,
+            0b1011, 0b1101, 0b1110, 0b1111,
+        ],
+    }
+}
 
 /// Noise function that outputs Worley noise.
+///
+/// Each cell is given a random value unless a value source is set, such as an
+/// `Fbm` sampled at the seed point of the cell so that the values of
+/// neighbouring cells correlate. The `Source` parameter is the type of the
+/// value source, and only a placeholder while none is set.
+///
+/// Points with an infinite or NaN coordinate give NaN for every return type.
 #[derive(Clone, Copy, Debug)]
-pub struct Worley {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "WorleyConfig<Source>"))]
+pub struct Worley<Source = Constant> {
     /// Specifies the range function to use when calculating the boundaries of
     /// the cell.
     pub range_function: RangeFunction,
 
-    /// Determines if the distance from the nearest seed point is applied to
-    /// the output value.
-    pub enable_range: bool,
+    /// Specifies which of the outputs of the Worley cells is returned.
+    pub return_type: ReturnType,
 
     /// Frequency of the seed points.
     pub frequency: f64,
@@ -43,53 +75,211 @@ pub struct Worley {
     /// the displacement value.
     pub displacement: f64,
 
-    seed: u32,
+    /// How far the seed points are moved from the centers of their cells, from
+    /// 0.0 for a regular grid to 1.0 for the full random displacement.
+    pub jitter: f64,
+
+    /// Noise function that gives each cell its value, sampled at the seed
+    /// point of the cell in input coordinates. Its output is used as is,
+    /// without the displacement. Default is none.
+    pub value_source: Option<Source>,
+
+    seed: u64,
+    lattice_mode: LatticeMode,
+    period: Option<usize>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(skip_serializing_if = "PermutationTable::is_seeded")
+    )]
     perm_table: PermutationTable,
 }
 
+/// Serialized form of `Worley`. The permutation table is
+/// rebuilt from the seed, unless a custom table was serialized.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct WorleyConfig<Source> {
+    range_function: RangeFunction,
+    #[serde(default)]
+    return_type: ReturnType,
+    // Replaced by `return_type`, but still read from older documents.
+    #[serde(default)]
+    enable_range: bool,
+    frequency: f64,
+    displacement: f64,
+    #[serde(default = "default_jitter")]
+    jitter: f64,
+    // Missing from documents without a value source.
+    value_source: Option<Source>,
+    seed: u64,
+    #[serde(default)]
+    lattice_mode: LatticeMode,
+    #[serde(default)]
+    period: Option<usize>,
+    // Only present for a custom permutation table.
+    perm_table: Option<PermutationTable>,
+}
+
+#[cfg(feature = "serde")]
+fn default_jitter() -> f64 {
+    DEFAULT_WORLEY_JITTER
+}
+
+#[cfg(feature = "serde")]
+impl<Source> From<WorleyConfig<Source>> for Worley<Source> {
+    fn from(config: WorleyConfig<Source>) -> Worley<Source> {
+        let return_type = match config.return_type {
+            ReturnType::Value if config.enable_range => ReturnType::F1,
+            return_type => return_type,
+        };
+
+        let noise = Worley::new()
+            .set_range_function(config.range_function)
+            .set_return_type(return_type)
+            .set_frequency(config.frequency)
+            .set_displacement(config.displacement)
+            .set_jitter(config.jitter)
+            .set_lattice_mode(config.lattice_mode)
+            .set_period(config.period)
+            .set_seed(config.seed)
+            .with_value_source(config.value_source);
+
+        match config.perm_table {
+            Some(perm_table) => noise.set_permutation_table(perm_table),
+            None => noise,
+        }
+    }
+}
+
 impl Worley {
     pub fn new() -> Worley {
         Worley {
             perm_table: PermutationTable::new(DEFAULT_WORLEY_SEED),
             seed: DEFAULT_WORLEY_SEED,
+            lattice_mode: DEFAULT_LATTICE_MODE,
+            period: None,
             range_function: DEFAULT_WORLEY_RANGEFUNCTION,
-            enable_range: false,
+            return_type: DEFAULT_WORLEY_RETURN_TYPE,
             frequency: DEFAULT_WORLEY_FREQUENCY,
             displacement: DEFAULT_WORLEY_DISPLACEMENT,
+            jitter: DEFAULT_WORLEY_JITTER,
+            value_source: None,
         }
     }
+}
+
+impl<Source> Worley<Source> {
+    /// Replaces the permutation table used to hash the lattice points, e.g.
+    /// with one built by `PermutationTable::from_rng` or
+    /// `PermutationTable::from_permutation`.
+    ///
+    /// The seed, lattice mode and period are left as they were. Setting a
+    /// different seed afterwards rebuilds the table from the seed. The table is
+    /// serialized along with the noise function.
+    pub fn set_permutation_table(self, perm_table: PermutationTable) -> Worley<Source> {
+        Worley {
+            perm_table: perm_table
+                .set_mode(self.lattice_mode)
+                .set_period(lattice_period(self.period, self.frequency)),
+            ..self
+        }
+    }
+
+    /// Sets how the lattice points are hashed. `LatticeMode::Hash` avoids the
+    /// noise repeating every 256 units.
+    pub fn set_lattice_mode(self, lattice_mode: LatticeMode) -> Worley<Source> {
+        Worley {
+            lattice_mode: lattice_mode,
+            perm_table: self.perm_table.set_mode(lattice_mode),
+            ..self
+        }
+    }
+
+    /// Returns how the lattice points are hashed.
+    pub fn lattice_mode(&self) -> LatticeMode {
+        self.lattice_mode
+    }
 
     /// Sets the range function used by the Worley cells.
-    pub fn set_range_function(self, range_function: RangeFunction) -> Worley {
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range function is `Minkowski` with a p that is NaN or not
+    /// greater than 0.0.
+    pub fn set_range_function(self, range_function: RangeFunction) -> Worley<Source> {
+        if let RangeFunction::Minkowski(p) = range_function {
+            assert!(p > 0.0, "the Minkowski p must be greater than zero");
+        }
+
         Worley {
             range_function: range_function,
             ..self
         }
     }
 
-    /// Enables or disables applying the distance from the nearest seed point
-    /// to the output value.
-    pub fn enable_range(self, enable_range: bool) -> Worley {
+    /// Sets which of the outputs of the Worley cells is returned.
+    pub fn set_return_type(self, return_type: ReturnType) -> Worley<Source> {
         Worley {
-            enable_range: enable_range,
+            return_type: return_type,
             ..self
         }
     }
 
     /// Sets the frequency of the seed points.
-    pub fn set_frequency(self, frequency: f64) -> Worley {
+    pub fn set_frequency(self, frequency: f64) -> Worley<Source> {
         Worley {
             frequency: frequency,
+            perm_table: self.perm_table.set_period(lattice_period(self.period, frequency)),
             ..self
         }
     }
 
-    pub fn set_displacement(self, displacement: f64) -> Worley {
+    pub fn set_displacement(self, displacement: f64) -> Worley<Source> {
         Worley {
             displacement: displacement,
             ..self
         }
     }
+
+    /// Sets how far the seed points are moved from the centers of their
+    /// cells. The jitter is clamped to the range 0.0 to 1.0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the jitter is NaN.
+    pub fn set_jitter(self, jitter: f64) -> Worley<Source> {
+        assert!(!jitter.is_nan(), "the jitter must not be NaN");
+
+        Worley {
+            jitter: math::clamp(jitter, 0.0, 1.0),
+            ..self
+        }
+    }
+
+    /// Sets the noise function that gives each cell its value.
+    pub fn set_value_source<S>(self, value_source: S) -> Worley<S> {
+        self.with_value_source(Some(value_source))
+    }
+
+    fn with_value_source<S>(self, value_source: Option<S>) -> Worley<S> {
+        Worley {
+            range_function: self.range_function,
+            return_type: self.return_type,
+            frequency: self.frequency,
+            displacement: self.displacement,
+            jitter: self.jitter,
+            value_source: value_source,
+            seed: self.seed,
+            lattice_mode: self.lattice_mode,
+            period: self.period,
+            perm_table: self.perm_table,
+        }
+    }
+}
+
+/// Number of cells after which the lattice wraps for a period in input units.
+fn lattice_period(period: Option<usize>, frequency: f64) -> Option<usize> {
+    period.map(|period| (period as f64 * frequency).round().max(1.0) as usize)
 }
 
 impl Default for Worley {
@@ -98,9 +288,9 @@ impl Default for Worley {
     }
 }
 
-impl Seedable for Worley {
+impl<Source> Seedable for Worley<Source> {
     /// Sets the seed value used by the Worley cells.
-    fn set_seed(self, seed: u32) -> Worley {
+    fn set_seed(self, seed: u64) -> Worley<Source> {
         // If the new seed is the same as the current seed, just return self.
         if self.seed == seed {
             return self;
@@ -108,19 +298,76 @@ impl Seedable for Worley {
 
         // Otherwise, regenerate the permutation table based on the new seed.
         Worley {
-            perm_table: PermutationTable::new(seed),
+            perm_table: PermutationTable::new(seed)
+                .set_mode(self.lattice_mode)
+                .set_period(lattice_period(self.period, self.frequency)),
             seed: seed,
             ..self
         }
     }
 
-    fn seed(&self) -> u32 {
+    fn seed(&self) -> u64 {
         self.seed
     }
 }
 
+impl<Source> Periodic for Worley<Source> {
+    /// Sets the period after which the noise repeats. The cells are wrapped
+    /// modulo the period times the frequency before they are hashed, so the
+    /// product should be a whole number.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the period is `Some(0)`.
+    fn set_period(self, period: Option<usize>) -> Worley<Source> {
+        assert!(period != Some(0), "the period of a noise function must not be zero");
+
+        Worley {
+            period: period,
+            perm_table: self.perm_table.set_period(lattice_period(period, self.frequency)),
+            ..self
+        }
+    }
+
+    fn period(&self) -> Option<usize> {
+        self.period
+    }
+}
+
+/// Set of outputs the Worley noise function can return.
+///
+/// The distances are scaled from `[0, 1]` to `[-1, 1]`, as are the cell
+/// values for the default displacement.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ReturnType {
+    /// The random value of the nearest cell, scaled by the displacement.
+    Value,
+
+    /// The distance to the nearest seed point.
+    F1,
+
+    /// The distance to the second-nearest seed point.
+    F2,
+
+    /// The difference between the distances to the two nearest seed points,
+    /// which is zero on the edges between cells.
+    F2MinusF1,
+
+    /// The identifier of the nearest cell, as a whole number from 0 to
+    /// `u32::MAX`. Unlike the other outputs, it is not scaled.
+    CellId,
+}
+
+impl Default for ReturnType {
+    fn default() -> Self {
+        DEFAULT_WORLEY_RETURN_TYPE
+    }
+}
+
 /// Set of distance functions that can be used in the Worley noise function.
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum RangeFunction {
     /// The standard linear distance. Expensive to compute because it requires
     /// square root calculations.
@@ -142,133 +389,564 @@ pub enum RangeFunction {
 
     /// Experimental function where all values are multiplied together and then
     /// added up like a quadratic equation.
+    ///
+    /// The range is zero wherever the components of the offset cancel out, so
+    /// only the cells next to the point are searched, and the seed points found
+    /// are not always the nearest.
     Quadratic,
+
+    /// The p-th root of the sum of the distances along each axis raised to the
+    /// power p. A p of 1.0 is the same as Manhattan, 2.0 the same as Euclidean,
+    /// and larger values approach Chebyshev.
+    ///
+    /// A p below 1.0 grows so much faster along the diagonals than along the
+    /// axes that the nearest seed points can lie further away than the two
+    /// cells in each direction that are searched, so they are occasionally
+    /// missed.
+    Minkowski(f64),
+
+    /// A user-supplied function of the offset from the seed point to the input
+    /// point, such as a hexagonal distance.
+    ///
+    /// The nearest cells are found assuming that the distance does not
+    /// decrease as any component of the offset grows in magnitude. A NaN
+    /// distance is treated as infinite. Noise functions using a custom range
+    /// function cannot be serialized.
+    ///
+    /// The function is a plain `fn` pointer rather than a boxed closure so
+    /// that `RangeFunction`, and with it `Worley`, stays `Copy` and cheap to
+    /// call. A closure can't capture any state here, so any parameters of the
+    /// distance have to be constants of the function.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(fn(&[f64]) -> f64),
 }
 
-fn calculate_range(range_function: RangeFunction, p1: &[f64], p2: &[f64]) -> f64 {
-    match range_function {
-        RangeFunction::Euclidean => range_euclidean(p1, p2),
-        RangeFunction::EuclideanSquared => range_euclidean_squared(p1, p2),
-        RangeFunction::Manhattan => range_manhattan(p1, p2),
-        RangeFunction::Chebyshev => range_chebyshev(p1, p2),
-        RangeFunction::Quadratic => range_quadratic(p1, p2),
+impl<Source> Reseedable for Worley<Source>
+where
+    Source: Reseedable,
+{
+    fn reseed(self, seed: u64) -> Worley<Source> {
+        Worley {
+            value_source: self
+                .value_source
+                .map(|source| source.reseed(derive_seed(seed, 0))),
+            ..self
+        }.set_seed(seed)
     }
 }
 
-fn range_euclidean(p1: &[f64], p2: &[f64]) -> f64 {
-    p1.iter()
-        .zip(p2.iter())
-        .map(|(a, b)| *a - *b)
-        .map(|a| a * a)
-        .fold(0.0, |acc, x| acc + x)
-        .sqrt()
+/// Trait for cellular noise functions that can return all of their outputs
+/// for a point at once.
+///
+/// `get_all` costs the same as a single call to `get`, so it is cheaper than
+/// calling `get` once for each `ReturnType` when more than one output is
+/// needed.
+///
+/// For a point with an infinite or NaN coordinate, the value, distances and
+/// seed point are NaN, and the cell identifier is 0.
+pub trait WorleyFn<T>: NoiseFn<T> {
+    fn get_all(&self, point: T) -> WorleyOutput<T>;
 }
 
-fn range_euclidean_squared(p1: &[f64], p2: &[f64]) -> f64 {
-    p1.iter()
-        .zip(p2.iter())
-        .map(|(a, b)| *a - *b)
-        .map(|a| a * a)
-        .fold(0.0, |acc, x| acc + x)
+impl<T, M: WorleyFn<T> + ?Sized> WorleyFn<T> for &M {
+    #[inline]
+    fn get_all(&self, point: T) -> WorleyOutput<T> {
+        M::get_all(*self, point)
+    }
 }
 
-fn range_manhattan(p1: &[f64], p2: &[f64]) -> f64 {
-    p1.iter()
-        .zip(p2.iter())
-        .map(|(a, b)| *a - *b)
-        .map(|a| a.abs())
-        .fold(0.0, |acc, x| acc + x)
+/// Every output of `Worley` at a point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WorleyOutput<T> {
+    /// Value of the nearest cell, as returned for `ReturnType::Value`. It comes
+    /// from the value source if one is set.
+    pub value: f64,
+
+    /// Distance to the nearest seed point, measured with the range function
+    /// after scaling by the frequency.
+    pub f1: f64,
+
+    /// Distance to the second-nearest seed point.
+    pub f2: f64,
+
+    /// Identifier of the nearest cell. It depends only on the seed, the
+    /// period and the position of the cell, so it is stable across frequencies
+    /// and versions of this crate.
+    pub cell_id: u32,
+
+    /// Position of the nearest seed point, in input coordinates.
+    pub seed_point: T,
 }
 
-fn range_chebyshev(p1: &[f64], p2: &[f64]) -> f64 {
-    p1.iter()
-        .zip(p2.iter())
-        .map(|(a, b)| *a - *b)
-        .map(|a| a.abs())
-        .fold(std::f64::MIN, |a, b| a.max(b))
+impl<T> WorleyOutput<T> {
+    /// Returns the output `Worley::get` returns for the given return type.
+    pub fn get(&self, return_type: ReturnType) -> f64 {
+        match return_type {
+            ReturnType::Value => self.value,
+            ReturnType::F1 => self.f1 * 2.0 - 1.0,
+            ReturnType::F2 => self.f2 * 2.0 - 1.0,
+            ReturnType::F2MinusF1 => (self.f2 - self.f1) * 2.0 - 1.0,
+            ReturnType::CellId => f64::from(self.cell_id),
+        }
+    }
 }
 
-fn range_quadratic(p1: &[f64], p2: &[f64]) -> f64 {
-    let temp: Vec<f64> = p1.iter().zip(p2.iter()).map(|(a, b)| *a - *b).collect();
+/// Nearest and second-nearest seed points found so far.
+struct Search<C, P> {
+    f1: f64,
+    f2: f64,
+    cell: C,
+    seed_point: P,
+
+    /// Whether the second-nearest seed point is needed. If not, cells that
+    /// can't hold a point nearer than the nearest one are skipped, and `f2` is
+    /// only an upper bound.
+    second: bool,
+}
 
-    let mut result = 0.0;
+impl<C, P> Search<C, P> {
+    fn new(cell: C, seed_point: P, second: bool) -> Search<C, P> {
+        Search {
+            f1: f64::INFINITY,
+            f2: f64::INFINITY,
+            cell: cell,
+            seed_point: seed_point,
+            second: second,
+        }
+    }
 
-    for i in &temp {
-        for j in &temp {
-            result += *i * *j;
+    /// Range beyond which seed points no longer affect the result.
+    #[inline(always)]
+    fn limit(&self) -> f64 {
+        if self.second {
+            self.f2
+        } else {
+            self.f1
         }
     }
 
-    result
+    #[inline(always)]
+    fn test(&mut self, range: f64, cell: C, seed_point: P) {
+        if range < self.f1 {
+            self.f2 = self.f1;
+            self.f1 = range;
+            self.cell = cell;
+            self.seed_point = seed_point;
+        } else if range < self.f2 {
+            self.f2 = range;
+        }
+    }
+}
+
+/// Offset from the center of the cell at the given index along an axis. The
+/// cells are indexed in order of their distance from the point, alternating
+/// between the side of the center the point is on, given by `sign`, and the
+/// other side.
+#[inline(always)]
+fn axis_step(index: usize, sign: isize) -> isize {
+    let distance = (index / 2 + index % 2) as isize;
+    if index % 2 == 1 {
+        sign * distance
+    } else {
+        -sign * distance
+    }
+}
+
+/// Distance along an axis from the point, `frac` away from the center, to the
+/// slab the seed point of the cell at the given index can lie in.
+#[inline(always)]
+fn axis_gap(index: usize, frac: f64, reach: f64) -> f64 {
+    let distance = (index / 2 + index % 2) as f64;
+    let distance = if index % 2 == 1 {
+        distance - frac
+    } else {
+        distance + frac
+    };
+    (distance - reach).max(0.0)
 }
 
-impl NoiseFn<Point2<f64>> for Worley {
-    fn get(&self, point: Point2<f64>) -> f64 {
-        #[inline(always)]
-        fn get_point(perm_table: &PermutationTable, whole: Point2<isize>) -> Point2<f64> {
-            math::add2(get_vec2(perm_table.get2(whole)), math::to_f642(whole))
+impl<Source> Worley<Source> {
+    /// Returns the value of the cell with the given lattice hash and seed
+    /// point, from the value source if there is one.
+    #[inline(always)]
+    fn cell_value<P>(&self, hash: usize, seed_point: P) -> f64
+    where
+        Source: NoiseFn<P>,
+    {
+        match self.value_source {
+            Some(ref source) => source.get(seed_point),
+            None => {
+                let value = self.displacement * hash as f64 / 255.0;
+                value * 2.0 - 1.0
+            },
         }
+    }
+
+    /// Returns the identifier of a lattice cell, wrapped to the period.
+    fn cell_id(&self, cell: &[isize]) -> u32 {
+        let period = self.perm_table.period().map(|period| period as isize);
+        let hash = cell.iter().fold(self.seed, |hash, &coordinate| {
+            let coordinate = period.map_or(coordinate, |period| coordinate.rem_euclid(period));
+            (hash ^ coordinate as u64)
+                .wrapping_add(0x9e37_79b9_7f4a_7c15)
+                .wrapping_mul(0xbf58_476d_1ce4_e5b9)
+                .rotate_left(31)
+        });
+
+        // SplitMix64 finalizer.
+        let hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        let hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        ((hash ^ (hash >> 31)) >> 32) as u32
+    }
+}
+
+// Along each axis, each seed point lies within `$reach` of its lattice point,
+// scaled by the jitter. That is half a unit, except in 4D where the offsets all
+// point along diagonals. The cells within `MAX_SEARCH_RADIUS` steps of the
+// nearest lattice point are searched, the 2^N cells next to the point first,
+// skipping cells whose seed point cannot be nearer than the points needed so
+// far. Usually that rules out every other cell. Only the range functions that
+// grow much faster along a diagonal than along an axis, such as Minkowski with
+// a p below 1.0, can need cells further than one step from the center.
+//
+// The quadratic range is zero wherever the components of the offset cancel
+// out, so no bound on the distance of a cell holds for it. Only the 2^N cells
+// next to the point are searched for it, skipping those whose seed point is
+// further from the point along any axis than the nearest one found so far.
+//
+// The range functions are implemented for each dimension on fixed-size
+// offsets, and the search is specialised for each of them. The Euclidean and
+// Minkowski ranges are compared without their final root, which doesn't change
+// their order, and the root is only taken of the results.
+macro_rules! impl_worley {
+    (
+        $point:ident,
+        $dim:expr,
+        $get:ident,
+        $get_vec:ident,
+        $search:ident,
+        $search_with:ident,
+        $reach:expr
+    ) => {
+        impl<Source> Worley<Source> {
+            fn $search(
+                &self,
+                point: $point<f64>,
+                second: bool,
+            ) -> Search<$point<isize>, $point<f64>> {
+                let mut search = match self.range_function {
+                    RangeFunction::Euclidean | RangeFunction::EuclideanSquared => self
+                        .$search_with(point, second, false, |offset: $point<f64>| {
+                            offset.iter().fold(0.0, |acc, x| acc + x * x)
+                        }),
+                    RangeFunction::Manhattan => {
+                        self.$search_with(point, second, false, |offset: $point<f64>| {
+                            offset.iter().fold(0.0, |acc, x| acc + x.abs())
+                        })
+                    },
+                    RangeFunction::Chebyshev => {
+                        self.$search_with(point, second, false, |offset: $point<f64>| {
+                            offset.iter().fold(0.0, |acc: f64, x| acc.max(x.abs()))
+                        })
+                    },
+                    RangeFunction::Quadratic => {
+                        // The sum of the products of every pair of components.
+                        self.$search_with(point, second, true, |offset: $point<f64>| {
+                            offset
+                                .iter()
+                                .fold(0.0, |acc, x| offset.iter().fold(acc, |acc, y| acc + x * y))
+                        })
+                    },
+                    RangeFunction::Minkowski(p) => {
+                        self.$search_with(point, second, false, |offset: $point<f64>| {
+                            offset.iter().fold(0.0, |acc, x| acc + x.abs().powf(p))
+                        })
+                    },
+                    RangeFunction::Custom(range) => {
+                        self.$search_with(point, second, false, |offset: $point<f64>| {
+                            let range = range(&offset);
+                            if range.is_nan() {
+                                f64::INFINITY
+                            } else {
+                                range
+                            }
+                        })
+                    },
+                };
+
+                match self.range_function {
+                    RangeFunction::Euclidean => {
+                        search.f1 = search.f1.sqrt();
+                        search.f2 = search.f2.sqrt();
+                    },
+                    RangeFunction::Minkowski(p) => {
+                        search.f1 = search.f1.powf(1.0 / p);
+                        search.f2 = search.f2.powf(1.0 / p);
+                    },
+                    _ => {},
+                }
+                search
+            }
 
-        let point = &math::mul2(point, self.frequency);
+            #[inline(always)]
+            fn $search_with<F>(
+                &self,
+                point: $point<f64>,
+                second: bool,
+                quadratic: bool,
+                range: F,
+            ) -> Search<$point<isize>, $point<f64>>
+            where
+                F: Fn($point<f64>) -> f64,
+            {
+                let center = point.map(|x| x.round() as isize);
+                let reach = $reach * self.jitter;
+                let mut search = Search::new(center, point, second);
+
+                // Along each axis, the distance of the point from the center
+                // and the side of the center it's on.
+                let mut fracs = [0.0; $dim];
+                let mut signs = [1; $dim];
+                for axis in 0..$dim {
+                    let frac = point[axis] - center[axis] as f64;
+                    fracs[axis] = frac.abs();
+                    if frac < 0.0 {
+                        signs[axis] = -1;
+                    }
+                }
 
-        let cell = math::map2(*point, f64::floor);
-        let whole = math::to_isize2(cell);
-        let frac = math::sub2(*point, cell);
+                // Whether the seed point of a cell, with the given gaps to it
+                // along each axis, can't be nearer than the points needed so
+                // far.
+                let ruled_out = |search: &Search<$point<isize>, $point<f64>>, gap: $point<f64>| {
+                    range(gap) >= search.limit()
+                };
+
+                // A function rather than a closure so that it can be forced
+                // inline, as it's called from several places.
+                #[inline(always)]
+                fn test_cell<Source, F>(
+                    worley: &Worley<Source>,
+                    search: &mut Search<$point<isize>, $point<f64>>,
+                    point: $point<f64>,
+                    cell: $point<isize>,
+                    range: &F,
+                ) where
+                    F: Fn($point<f64>) -> f64,
+                {
+                    let mut seed_point = $get_vec(worley.perm_table.$get(cell));
+                    let mut offset = point;
+                    for ((x, &whole), offset) in
+                        seed_point.iter_mut().zip(&cell).zip(offset.iter_mut())
+                    {
+                        *x = *x * worley.jitter + whole as f64;
+                        *offset -= *x;
+                    }
+                    search.test(range(offset), cell, seed_point);
+                }
 
-        let x_half = frac[0] > 0.5;
-        let y_half = frac[1] > 0.5;
+                let test = |search: &mut Search<$point<isize>, $point<f64>>,
+                            cell: $point<isize>| {
+                    test_cell(self, search, point, cell, &range)
+                };
+
+                test(&mut search, center);
+                if quadratic {
+                    // Skipping a cell doesn't mean its seed point is further
+                    // away, so the second-nearest seed point is looked for in
+                    // the skipped cells too, among those no nearer than the
+                    // nearest.
+                    let reach = 0.5 * self.jitter;
+                    let mut skipped = [center; 1 << $dim];
+                    let mut skipped_count = 0;
+                    for &near in near_cells($dim).iter() {
+                        let mut cell = center;
+                        let mut bound: f64 = 0.0;
+                        for axis in 0..$dim {
+                            let index = (near >> axis) & 1;
+                            cell[axis] += axis_step(index, signs[axis]);
+                            let gap = axis_gap(index, fracs[axis], reach);
+                            bound = bound.max(gap * gap);
+                        }
+                        if bound < search.f1 {
+                            test(&mut search, cell);
+                        } else {
+                            skipped[skipped_count] = cell;
+                            skipped_count += 1;
+                        }
+                    }
+                    if second {
+                        for &cell in &skipped[..skipped_count] {
+                            let mut other = Search::new(cell, point, false);
+                            test(&mut other, cell);
+                            if other.f1 >= search.f1 && other.f1 < search.f2 {
+                                search.f2 = other.f1;
+                            }
+                        }
+                    }
+                    return search;
+                }
 
-        let near = [
-            whole[0] + (x_half as isize),
-            whole[1] + (y_half as isize),
-        ];
-        let far = [
-            whole[0] + (!x_half as isize),
-            whole[1] + (!y_half as isize),
-        ];
+                // The axes along which the cell a step from the center towards
+                // the point could hold a nearer seed point. Along the others,
+                // no cell but the center can.
+                let limit = search.limit();
+                let mut open = 0;
+                for axis in 0..$dim {
+                    let mut gap = [0.0; $dim];
+                    gap[axis] = axis_gap(1, fracs[axis], reach);
+                    if range(gap) < limit {
+                        open |= 1 << axis;
+                    }
+                }
+                if open == 0 {
+                    return search;
+                }
 
-        let mut seed_cell = near;
-        let seed_point = get_point(&self.perm_table, near);
-        let mut range = calculate_range(self.range_function, point, &seed_point);
+                // The nearest seed point almost always lies in one of the cells
+                // next to the point, so those are tested first to narrow down
+                // the rest.
+                for &near in near_cells($dim).iter() {
+                    if near & !open != 0 {
+                        continue;
+                    }
+                    let mut cell = center;
+                    let mut gap = [0.0; $dim];
+                    for axis in 0..$dim {
+                        let index = (near >> axis) & 1;
+                        cell[axis] += axis_step(index, signs[axis]);
+                        gap[axis] = axis_gap(index, fracs[axis], reach);
+                    }
+                    if !ruled_out(&search, gap) {
+                        test(&mut search, cell);
+                    }
+                }
 
-        let x_range = (0.5 - frac[0]) * (0.5 - frac[0]); // x-distance squared to center line
-        let y_range = (0.5 - frac[1]) * (0.5 - frac[1]); // y-distance squared to center line
+                // Along each axis, count the steps that could still hold a
+                // nearer point, then test the cells within those counts that
+                // are further than one step from the center along some axis.
+                let limit = search.limit();
+                let mut counts = [2; $dim];
+                for (axis, count) in counts.iter_mut().enumerate() {
+                    if open & 1 << axis == 0 {
+                        continue;
+                    }
+                    let mut gap = [0.0; $dim];
+                    while *count < SEARCH_STEPS && {
+                        gap[axis] = axis_gap(*count, fracs[axis], reach);
+                        range(gap) < limit
+                    } {
+                        *count += 1;
+                    }
+                }
+                if counts.iter().all(|&count| count == 2) {
+                    return search;
+                }
 
-        macro_rules! test_point(
-            [$x:expr, $y:expr] => {
-                {
-                    let cur_point = get_point(&self.perm_table, [$x, $y]);
-                    let cur_range = calculate_range(self.range_function, point, &cur_point);
-                    if cur_range < range {
-                        range = cur_range;
-                        seed_cell = [$x, $y];
+                let mut indices = [0; $dim];
+                let mut cell = center;
+                let mut gap = [0.0; $dim];
+                for axis in 0..$dim {
+                    gap[axis] = axis_gap(0, fracs[axis], reach);
+                }
+                'cells: loop {
+                    // Cells further along any axis than one that was ruled out
+                    // are ruled out too, so past the first axis with a nonzero
+                    // index, the odometer carries into the next one.
+                    let mut carry = 0;
+                    if indices.iter().any(|&index| index > 1) {
+                        if !ruled_out(&search, gap) {
+                            test(&mut search, cell);
+                        } else {
+                            carry = indices
+                                .iter()
+                                .position(|&index| index > 0)
+                                .map_or(0, |axis| axis + 1);
+                        }
+                    }
+
+                    for axis in 0..$dim {
+                        if axis >= carry && indices[axis] + 1 < counts[axis] {
+                            indices[axis] += 1;
+                        } else {
+                            indices[axis] = 0;
+                        }
+                        cell[axis] = center[axis] + axis_step(indices[axis], signs[axis]);
+                        gap[axis] = axis_gap(indices[axis], fracs[axis], reach);
+                        if indices[axis] > 0 {
+                            continue 'cells;
+                        }
                     }
+                    return search;
                 }
             }
-        );
-
-        if x_range < range {
-            test_point![far[0], near[1]];
         }
 
-        if y_range < range {
-            test_point![near[0], far[1]];
-        }
+        impl<Source> WorleyFn<$point<f64>> for Worley<Source>
+        where
+            Source: NoiseFn<$point<f64>>,
+        {
+            fn get_all(&self, point: $point<f64>) -> WorleyOutput<$point<f64>> {
+                let scaled = point.map(|x| x * self.frequency);
+                if scaled.iter().any(|x| !x.is_finite()) {
+                    return WorleyOutput {
+                        value: f64::NAN,
+                        f1: f64::NAN,
+                        f2: f64::NAN,
+                        cell_id: 0,
+                        seed_point: point.map(|_| f64::NAN),
+                    };
+                }
+
+                let search = self.$search(scaled, true);
+                let seed_point = search.seed_point.map(|x| x / self.frequency);
 
-        if x_range < range && y_range < range {
-            test_point![far[0], far[1]];
+                WorleyOutput {
+                    value: self.cell_value(self.perm_table.$get(search.cell), seed_point),
+                    f1: search.f1,
+                    f2: search.f2,
+                    cell_id: self.cell_id(&search.cell),
+                    seed_point: seed_point,
+                }
+            }
         }
 
-        let value = if self.enable_range {
-            range
-        } else {
-            self.displacement * self.perm_table.get2(seed_cell) as f64 / 255.0
-        };
+        impl<Source> NoiseFn<$point<f64>> for Worley<Source>
+        where
+            Source: NoiseFn<$point<f64>>,
+        {
+            fn get(&self, point: $point<f64>) -> f64 {
+                let scaled = point.map(|x| x * self.frequency);
+                if scaled.iter().any(|x| !x.is_finite()) {
+                    return f64::NAN;
+                }
 
-        value * 2.0 - 1.0
-    }
+                let second = match self.return_type {
+                    ReturnType::F2 | ReturnType::F2MinusF1 => true,
+                    ReturnType::Value | ReturnType::F1 | ReturnType::CellId => false,
+                };
+                let search = self.$search(scaled, second);
+
+                // Only work out the output that is returned.
+                match self.return_type {
+                    ReturnType::Value => {
+                        let seed_point = search.seed_point.map(|x| x / self.frequency);
+                        self.cell_value(self.perm_table.$get(search.cell), seed_point)
+                    },
+                    ReturnType::F1 => search.f1 * 2.0 - 1.0,
+                    ReturnType::F2 => search.f2 * 2.0 - 1.0,
+                    ReturnType::F2MinusF1 => (search.f2 - search.f1) * 2.0 - 1.0,
+                    ReturnType::CellId => f64::from(self.cell_id(&search.cell)),
+                }
+            }
+        }
+    };
 }
 
+impl_worley!(Point2, 2, get2, get_vec2, search2, search_with2, 0.5);
+impl_worley!(Point3, 3, get3, get_vec3, search3, search_with3, 0.5);
+impl_worley!(Point4, 4, get4, get_vec4, search4, search_with4, REACH4);
+
 #[inline(always)]
 #[cfg_attr(rustfmt, rustfmt_skip)]
 fn get_vec2(index: usize) -> Point2<f64> {
@@ -287,89 +965,6 @@ fn get_vec2(index: usize) -> Point2<f64> {
     }
 }
 
-impl NoiseFn<Point3<f64>> for Worley {
-    fn get(&self, point: Point3<f64>) -> f64 {
-        #[inline(always)]
-        fn get_point(perm_table: &PermutationTable, whole: Point3<isize>) -> Point3<f64> {
-            math::add3(get_vec3(perm_table.get3(whole)), math::to_f643(whole))
-        }
-
-        let point = &math::mul3(point, self.frequency);
-
-        let cell = math::map3(*point, f64::floor);
-        let whole = math::to_isize3(cell);
-        let frac = math::sub3(*point, cell);
-
-        let x_half = frac[0] > 0.5;
-        let y_half = frac[1] > 0.5;
-        let z_half = frac[2] > 0.5;
-
-        let near = [
-            whole[0] + (x_half as isize),
-            whole[1] + (y_half as isize),
-            whole[2] + (z_half as isize),
-        ];
-        let far = [
-            whole[0] + (!x_half as isize),
-            whole[1] + (!y_half as isize),
-            whole[2] + (!z_half as isize),
-        ];
-
-        let mut seed_cell = near;
-        let seed_point = get_point(&self.perm_table, near);
-        let mut range = calculate_range(self.range_function, point, &seed_point);
-
-        let x_range = (0.5 - frac[0]) * (0.5 - frac[0]); // x-distance squared to center line
-        let y_range = (0.5 - frac[1]) * (0.5 - frac[1]); // y-distance squared to center line
-        let z_range = (0.5 - frac[2]) * (0.5 - frac[2]); // z-distance squared to center line
-
-        macro_rules! test_point(
-            [$x:expr, $y:expr, $z:expr] => {
-                {
-                    let cur_point = get_point(&self.perm_table, [$x, $y, $z]);
-                    let cur_range = calculate_range(self.range_function, point, &cur_point);
-                    if cur_range < range {
-                        range = cur_range;
-                        seed_cell = [$x, $y, $z];
-                    }
-                }
-            }
-        );
-
-        if x_range < range {
-            test_point![far[0], near[1], near[2]];
-        }
-        if y_range < range {
-            test_point![near[0], far[1], near[2]];
-        }
-        if z_range < range {
-            test_point![near[0], near[1], far[2]];
-        }
-
-        if x_range < range && y_range < range {
-            test_point![far[0], far[1], near[2]];
-        }
-        if x_range < range && z_range < range {
-            test_point![far[0], near[1], far[2]];
-        }
-        if y_range < range && z_range < range {
-            test_point![near[0], far[1], far[2]];
-        }
-
-        if x_range < range && y_range < range && z_range < range {
-            test_point![far[0], far[1], far[2]];
-        }
-
-        let value = if self.enable_range {
-            range
-        } else {
-            self.displacement * self.perm_table.get3(seed_cell) as f64 / 255.0
-        };
-
-        value * 2.0 - 1.0
-    }
-}
-
 #[inline(always)]
 #[cfg_attr(rustfmt, rustfmt_skip)]
 fn get_vec3(index: usize) -> Point3<f64> {
@@ -398,123 +993,17 @@ fn get_vec3(index: usize) -> Point3<f64> {
     }
 }
 
-impl NoiseFn<Point4<f64>> for Worley {
-    fn get(&self, point: Point4<f64>) -> f64 {
-        #[inline(always)]
-        fn get_point(perm_table: &PermutationTable, whole: Point4<isize>) -> Point4<f64> {
-            math::add4(get_vec4(perm_table.get4(whole)), math::to_f644(whole))
-        }
-
-        let point = &math::mul4(point, self.frequency);
-
-        let cell = math::map4(*point, f64::floor);
-        let whole = math::to_isize4(cell);
-        let frac = math::sub4(*point, cell);
-
-        let x_half = frac[0] > 0.5;
-        let y_half = frac[1] > 0.5;
-        let z_half = frac[2] > 0.5;
-        let w_half = frac[3] > 0.5;
-
-        let near = [
-            whole[0] + (x_half as isize),
-            whole[1] + (y_half as isize),
-            whole[2] + (z_half as isize),
-            whole[3] + (w_half as isize),
-        ];
-        let far = [
-            whole[0] + (!x_half as isize),
-            whole[1] + (!y_half as isize),
-            whole[2] + (!z_half as isize),
-            whole[3] + (!w_half as isize),
-        ];
-
-        let mut seed_cell = near;
-        let seed_point = get_point(&self.perm_table, near);
-        let mut range = calculate_range(self.range_function, point, &seed_point);
-
-        let x_range = (0.5 - frac[0]) * (0.5 - frac[0]); // x-distance squared to center line
-        let y_range = (0.5 - frac[1]) * (0.5 - frac[1]); // y-distance squared to center line
-        let z_range = (0.5 - frac[2]) * (0.5 - frac[2]); // z-distance squared to center line
-        let w_range = (0.5 - frac[3]) * (0.5 - frac[3]); // w-distance squared to center line
-
-        macro_rules! test_point(
-            [$x:expr, $y:expr, $z:expr, $w:expr] => {
-                {
-                    let cur_point = get_point(&self.perm_table, [$x, $y, $z, $w]);
-                    let cur_range = calculate_range(self.range_function, point, &cur_point);
-                    if cur_range < range {
-                        range = cur_range;
-                        seed_cell = [$x, $y, $z, $w];
-                    }
-                }
-            }
-        );
-
-        if x_range < range {
-            test_point![far[0], near[1], near[2], near[3]];
-        }
-        if y_range < range {
-            test_point![near[0], far[1], near[2], near[3]];
-        }
-        if z_range < range {
-            test_point![near[0], near[1], far[2], near[3]];
-        }
-        if w_range < range {
-            test_point![near[0], near[1], near[2], far[3]];
-        }
-
-        if x_range < range && y_range < range {
-            test_point![far[0], far[1], near[2], near[3]];
-        }
-        if x_range < range && z_range < range {
-            test_point![far[0], near[1], far[2], near[3]];
-        }
-        if x_range < range && w_range < range {
-            test_point![far[0], near[1], near[2], far[3]];
-        }
-        if y_range < range && z_range < range {
-            test_point![near[0], far[1], far[2], near[3]];
-        }
-        if y_range < range && w_range < range {
-            test_point![near[0], far[1], near[2], far[3]];
-        }
-        if z_range < range && w_range < range {
-            test_point![near[0], near[1], far[2], far[3]];
-        }
-
-        if x_range < range && y_range < range && z_range < range {
-            test_point![far[0], far[1], far[2], near[3]];
-        }
-        if x_range < range && y_range < range && w_range < range {
-            test_point![far[0], far[1], near[2], far[3]];
-        }
-        if x_range < range && z_range < range && w_range < range {
-            test_point![far[0], near[1], far[2], far[3]];
-        }
-        if y_range < range && z_range < range && w_range < range {
-            test_point![near[0], far[1], far[2], far[3]];
-        }
-
-        if x_range < range && y_range < range && z_range < range && w_range < range {
-            test_point![far[0], far[1], far[2], far[3]];
-        }
-
-        let value = if self.enable_range {
-            range
-        } else {
-            self.displacement * self.perm_table.get4(seed_cell) as f64 / 255.0
-        };
-
-        value * 2.0 - 1.0
-    }
-}
+/// Component of the unit vectors along the diagonals used for the 4D offsets.
+const DIAG4: f64 = 0.5773502691896258;
+/// Furthest a 4D seed point lies from its lattice point along any axis, as the
+/// offsets all point along diagonals.
+const REACH4: f64 = 0.5 * DIAG4;
 
 #[inline(always)]
 #[cfg_attr(rustfmt, rustfmt_skip)]
 fn get_vec4(index: usize) -> Point4<f64> {
     let length = ((index & 0xE0) >> 5) as f64 * 0.5 / 7.0;
-    let diag = length * 0.5773502691896258;
+    let diag = length * DIAG4;
     match index % 32 {
         0  => [ diag,  diag,  diag,  0.0],
         1  => [ diag, -diag,  diag,  0.0],
@@ -551,3 +1040,304 @@ fn get_vec4(index: usize) -> Point4<f64> {
         _ => panic!("Attempt to access 4D gradient {} of 32", index % 32),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worley_outputs() {
+        let worley = Worley::new().set_seed(7).set_frequency(1.5);
+
+        for &point in &[[0.3, 1.7], [-4.2, 0.6], [12.5, -8.25]] {
+            let output = worley.get_all(point);
+
+            // Compare against a brute force search of the nearby cells.
+            let scaled = [point[0] * 1.5, point[1] * 1.5];
+            let mut ranges = Vec::new();
+            for x in -3..4 {
+                for y in -3..4 {
+                    let cell = [
+                        scaled[0].floor() as isize + x,
+                        scaled[1].floor() as isize + y,
+                    ];
+                    let offset = get_vec2(worley.perm_table.get2(cell));
+                    let seed_point = [cell[0] as f64 + offset[0], cell[1] as f64 + offset[1]];
+                    let offset = [scaled[0] - seed_point[0], scaled[1] - seed_point[1]];
+                    ranges.push(math::dot2(offset, offset).sqrt());
+                }
+            }
+            ranges.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            assert_eq!((output.f1, output.f2), (ranges[0], ranges[1]));
+
+            let seed_point = [output.seed_point[0] * 1.5, output.seed_point[1] * 1.5];
+            let offset = [scaled[0] - seed_point[0], scaled[1] - seed_point[1]];
+            assert!((math::dot2(offset, offset).sqrt() - output.f1).abs() < 1e-12);
+
+            for &return_type in &[
+                ReturnType::Value,
+                ReturnType::F1,
+                ReturnType::F2,
+                ReturnType::F2MinusF1,
+                ReturnType::CellId,
+            ] {
+                let value = worley.set_return_type(return_type).get(point);
+                assert_eq!(value, output.get(return_type));
+            }
+        }
+
+        // Cell identifiers don't depend on the frequency, and follow the
+        // period.
+        let id = |worley: Worley, point| worley.set_return_type(ReturnType::CellId).get(point);
+        let periodic = Worley::new().set_period(Some(4));
+        assert_eq!(
+            id(Worley::new(), [2.1, 3.2]),
+            id(Worley::new().set_frequency(2.0), [1.05, 1.6])
+        );
+        assert_eq!(id(periodic, [0.1, 0.1]), id(periodic, [4.1, -3.9]));
+    }
+
+    #[test]
+    fn test_worley_non_finite_points() {
+        for &return_type in &[ReturnType::Value, ReturnType::F2, ReturnType::CellId] {
+            let worley = Worley::new().set_return_type(return_type);
+            assert!(worley.get([f64::NAN, 0.5]).is_nan());
+            assert!(worley.get([0.5, f64::INFINITY, 1.5]).is_nan());
+            assert!(worley.get([0.5, 1.5, 2.5, f64::NEG_INFINITY]).is_nan());
+        }
+
+        let output = Worley::new().get_all([f64::NAN, 0.5]);
+        assert!(output.f1.is_nan() && output.f2.is_nan() && output.value.is_nan());
+        assert_eq!(output.cell_id, 0);
+    }
+
+    #[test]
+    fn test_worley_jitter_and_range_functions() {
+        let grid = Worley::new().set_jitter(0.0).get_all([2.3, -1.4, 0.6]);
+        assert_eq!(grid.seed_point, [2.0, -1.0, 1.0]);
+
+        fn hexagonal(offset: &[f64]) -> f64 {
+            let (x, y) = (offset[0].abs(), offset[1].abs());
+            x.max(x * 0.5 + y * 0.75_f64.sqrt())
+        }
+
+        let minkowski = |p| Worley::new().set_range_function(RangeFunction::Minkowski(p));
+        let manhattan = Worley::new().set_range_function(RangeFunction::Manhattan);
+        let euclidean = Worley::new().set_return_type(ReturnType::F2MinusF1);
+        let custom = Worley::new().set_range_function(RangeFunction::Custom(hexagonal));
+
+        for &point in &[[0.3, 1.7], [-4.2, 0.6], [12.5, -8.25]] {
+            let (a, b) = (minkowski(1.0).get_all(point), manhattan.get_all(point));
+            assert!((a.f1 - b.f1).abs() < 1e-12 && (a.f2 - b.f2).abs() < 1e-12);
+
+            let value = minkowski(2.0)
+                .set_return_type(ReturnType::F2MinusF1)
+                .get(point);
+            assert!((value - euclidean.get(point)).abs() < 1e-12);
+
+            let output = custom.get_all(point);
+            let offset = [
+                point[0] - output.seed_point[0],
+                point[1] - output.seed_point[1],
+            ];
+            assert_eq!(output.f1, hexagonal(&offset));
+        }
+    }
+
+    #[test]
+    fn test_worley_range_function_golden_values() {
+        // F1 and F2 of each range function at each point in 2, 3 and 4
+        // dimensions, compared up to rounding.
+        let points = [
+            [0.3, 1.7, -2.2, 0.9],
+            [-4.2, 0.6, 5.1, -1.3],
+            [12.5, -8.25, 3.75, 7.6],
+        ];
+        let cases = [
+            (
+                RangeFunction::Euclidean,
+                [
+                    [0.7354480605079642, 0.8758769719377352],
+                    [0.7096923009105193, 0.7330395206095713],
+                    [0.6431745248280245, 0.726262259487109],
+                    [0.4625406020370303, 0.8531023143755525],
+                    [0.6403124237432849, 0.6931148268976118],
+                    [0.5477225575051662, 0.6395009353209896],
+                    [0.5005849855754353, 0.665843243728474],
+                    [0.5691920518039011, 0.7266782124777074],
+                    [0.6995893619530822, 0.8507654260031746],
+                ],
+            ),
+            (
+                RangeFunction::EuclideanSquared,
+                [
+                    [0.5408838497049261, 0.7671604699708161],
+                    [0.503663161971667, 0.5373469387755102],
+                    [0.4136734693877551, 0.5274568695553208],
+                    [0.21394380853277845, 0.727783558792924],
+                    [0.41000000000000003, 0.4804081632653064],
+                    [0.30000000000000004, 0.4089614462764206],
+                    [0.25058532778355874, 0.44334722521885606],
+                    [0.3239795918367348, 0.528061224489796],
+                    [0.4894252753579206, 0.7238018100823631],
+                ],
+            ),
+            (
+                RangeFunction::Manhattan,
+                [
+                    [1.0, 1.0842968850192394],
+                    [0.9857142857142859, 1.0142857142857142],
+                    [1.0423074889580901, 1.2237179148263486],
+                    [0.6322580645161292, 0.9474375080153916],
+                    [0.8999999999999998, 1.057142857142857],
+                    [0.9814104258682572, 0.9999999999999999],
+                    [0.5241935483870961, 0.8951612903225801],
+                    [0.8571428571428577, 1.2142857142857144],
+                    [1.3175213901157683, 1.6474358296526974],
+                ],
+            ),
+            (
+                RangeFunction::Chebyshev,
+                [
+                    [0.619338546342312, 0.64297525958173],
+                    [0.5020305089104424, 0.7],
+                    [0.5474358296526968, 0.5762820851736516],
+                    [0.4, 0.6064516129032258],
+                    [0.4969542366343367, 0.5571428571428574],
+                    [0.4, 0.5113248654051867],
+                    [0.5, 0.5789257787451909],
+                    [0.5, 0.5],
+                    [0.5, 0.5025641703473038],
+                ],
+            ),
+            (
+                RangeFunction::Quadratic,
+                [
+                    [5.551115123125783e-17, 0.18964620187304887],
+                    [0.04000000000000006, 0.1616285300947896],
+                    [0.002762992004300552, 0.17953687134478868],
+                    [0.3997502601456818, 0.8976378315944153],
+                    [0.24999999999999942, 0.24999999999999944],
+                    [0.039999999999999744, 0.05819640224895489],
+                    [0.034404266389177984, 0.06250000000000006],
+                    [0.045918367346938826, 0.3551432704806809],
+                    [0.09692317181955752, 0.09692317181955762],
+                ],
+            ),
+            (
+                RangeFunction::Minkowski(3.0),
+                [
+                    [0.6777533804831845, 0.7803176715479702],
+                    [0.6023139118381026, 0.7058220129351336],
+                    [0.5792642729721704, 0.631903780032767],
+                    [0.4245626588795384, 0.7600386247851608],
+                    [0.60822019955734, 0.6196648300810313],
+                    [0.464158883361278, 0.6022886961857762],
+                    [0.5000188808284248, 0.6123523748143018],
+                    [0.5215326530919624, 0.6220561263894356],
+                    [0.5833050852831542, 0.6911333714909058],
+                ],
+            ),
+        ];
+
+        let assert_matches = |output: [f64; 2], expected: &[f64; 2]| {
+            assert!(
+                (output[0] - expected[0]).abs() < 1e-12 && (output[1] - expected[1]).abs() < 1e-12,
+                "{:?} != {:?}",
+                output,
+                expected
+            );
+        };
+
+        for &(range_function, ref expected) in &cases {
+            let worley = Worley::new().set_range_function(range_function);
+            let mut outputs = Vec::new();
+            for point in &points {
+                let output = worley.get_all([point[0], point[1]]);
+                outputs.push([output.f1, output.f2]);
+                let output = worley.get_all([point[0], point[1], point[2]]);
+                outputs.push([output.f1, output.f2]);
+                let output = worley.get_all(*point);
+                outputs.push([output.f1, output.f2]);
+            }
+            for (&output, expected) in outputs.iter().zip(expected) {
+                assert_matches(output, expected);
+            }
+
+            // The search only looks for the second-nearest point when it's
+            // returned, which mustn't change the nearest one.
+            let f1 = worley.set_return_type(ReturnType::F1);
+            let f2 = f1.set_return_type(ReturnType::F2);
+            for point in &points {
+                let output = f1.get_all(*point);
+                assert_eq!(f1.get(*point), output.get(ReturnType::F1));
+                assert_eq!(f2.get(*point), output.get(ReturnType::F2));
+            }
+        }
+
+        // A custom hexagonal range function in 2 dimensions.
+        fn hexagonal(offset: &[f64]) -> f64 {
+            let (x, y) = (offset[0].abs(), offset[1].abs());
+            x.max(x * 0.5 + y * 0.75_f64.sqrt())
+        }
+
+        let worley = Worley::new().set_range_function(RangeFunction::Custom(hexagonal));
+        let expected = [
+            [0.64297525958173, 0.8460321878465239],
+            [0.46253919377184005, 0.7935220137373993],
+            [0.5, 0.66582732067162],
+        ];
+        for (point, expected) in points.iter().zip(&expected) {
+            let output = worley.get_all([point[0], point[1]]);
+            assert_matches([output.f1, output.f2], expected);
+        }
+    }
+
+    #[test]
+    fn test_worley_nan_range_is_infinite() {
+        fn nan(_: &[f64]) -> f64 {
+            f64::NAN
+        }
+
+        let output = Worley::new()
+            .set_range_function(RangeFunction::Custom(nan))
+            .get_all([0.3, 1.7]);
+        assert_eq!((output.f1, output.f2), (f64::INFINITY, f64::INFINITY));
+    }
+
+    #[test]
+    #[should_panic(expected = "the jitter must not be NaN")]
+    fn test_worley_nan_jitter_panics() {
+        Worley::new().set_jitter(f64::NAN);
+    }
+
+    #[test]
+    #[should_panic(expected = "the Minkowski p must be greater than zero")]
+    fn test_worley_invalid_minkowski_panics() {
+        Worley::new().set_range_function(RangeFunction::Minkowski(0.0));
+    }
+
+    #[test]
+    fn test_worley_value_source() {
+        use noise_fns::{Fbm, MultiFractal};
+
+        let source = Fbm::new().set_seed(5).set_octaves(3);
+        let worley = Worley::new()
+            .set_frequency(2.0)
+            .set_value_source(source.clone());
+
+        // Each cell takes the value of the source at its seed point.
+        for &point in &[[0.3, 1.7, -2.2], [-4.2, 0.6, 5.1]] {
+            let output = worley.get_all(point);
+            assert_eq!(output.value, source.get(output.seed_point));
+            assert_eq!(worley.get(point), output.value);
+        }
+    }
+
+    #[test]
+    fn test_periodic_noise_tiles() {
+        use noise_fns::tests::assert_tiles;
+
+        assert_tiles(&Worley::new().set_frequency(2.0).set_period(Some(5)), 5.0);
+    }
+}
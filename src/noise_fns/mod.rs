@@ -8,16 +8,22 @@
 
 pub use self::cache::*;
 pub use self::combiners::*;
+pub use self::ext::NoiseFnExt;
 pub use self::generators::*;
 pub use self::modifiers::*;
+pub use self::ops::NoiseFnOps;
 pub use self::selectors::*;
+pub use self::sync_cache::*;
 pub use self::transformers::*;
 
 mod combiners;
+mod ext;
 mod generators;
 mod modifiers;
+mod ops;
 mod selectors;
 mod cache;
+mod sync_cache;
 mod transformers;
 
 /// Base trait for noise functions.
@@ -33,22 +39,241 @@ mod transformers;
 /// * Mathematically changing the output value from another noise function
 ///     in various ways.
 /// * Combining the output values from two noise functions in various ways.
+///
+/// `get_many` evaluates a whole slice of points, such as a grid, in one call.
 pub trait NoiseFn<T> {
     fn get(&self, point: T) -> f64;
+
+    /// Evaluates the function at each of `points`, writing the results to the
+    /// matching elements of `out`.
+    ///
+    /// The results are identical to calling `get` for each point.
+    ///
+    /// `Value`, and `Perlin` and `OpenSimplex` in 2D and 3D, reuse the hashes
+    /// of the cells shared by neighbouring points, so they are faster than
+    /// calling `get` in a loop.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `points` and `out` have different lengths.
+    fn get_many(&self, points: &[T], out: &mut [f64])
+    where
+        T: Copy,
+    {
+        assert_eq!(points.len(), out.len());
+        for (&point, out) in points.iter().zip(out.iter_mut()) {
+            *out = self.get(point);
+        }
+    }
 }
 
-impl<'a, T, M: NoiseFn<T>> NoiseFn<T> for &'a M {
+impl<'a, T, M: NoiseFn<T> + ?Sized> NoiseFn<T> for &'a M {
     #[inline]
     fn get(&self, point: T) -> f64 {
         M::get(*self, point)
     }
+
+    #[inline]
+    fn get_many(&self, points: &[T], out: &mut [f64])
+    where
+        T: Copy,
+    {
+        M::get_many(*self, points, out)
+    }
+}
+
+impl<T, M: NoiseFn<T> + ?Sized> NoiseFn<T> for Box<M> {
+    #[inline]
+    fn get(&self, point: T) -> f64 {
+        M::get(self, point)
+    }
+
+    #[inline]
+    fn get_many(&self, points: &[T], out: &mut [f64])
+    where
+        T: Copy,
+    {
+        M::get_many(self, points, out)
+    }
+}
+
+/// Trait for noise functions that can calculate their gradient analytically.
+///
+/// `get_with_gradient` returns the same value as `get`, along with the vector
+/// of partial derivatives of the noise function at that point. The gradient
+/// has the same dimensions as the input point, so for `Point3<f64>` it is the
+/// `[df/dx, df/dy, df/dz]` vector.
+///
+/// This is considerably cheaper than estimating the gradient with finite
+/// differences, which needs at least one extra call to `get` per dimension.
+pub trait NoiseFnWithGradient<T>: NoiseFn<T> {
+    fn get_with_gradient(&self, point: T) -> (f64, T);
+}
+
+impl<T, M: NoiseFnWithGradient<T> + ?Sized> NoiseFnWithGradient<T> for &M {
+    #[inline]
+    fn get_with_gradient(&self, point: T) -> (f64, T) {
+        M::get_with_gradient(*self, point)
+    }
 }
 
 /// Trait for functions that require a seed before generating their values
 pub trait Seedable {
     /// Set the seed for the function implementing the `Seedable` trait
-    fn set_seed(self, seed: u32) -> Self;
+    fn set_seed(self, seed: u64) -> Self;
 
     /// Getter to retrieve the seed from the function
-    fn seed(&self) -> u32;
+    fn seed(&self) -> u64;
+
+    /// Sets the seed to the hash of some bytes, such as a string. See
+    /// `seed_from_bytes`.
+    fn set_seed_from_bytes<B: AsRef<[u8]>>(self, bytes: B) -> Self
+    where
+        Self: Sized,
+    {
+        self.set_seed(seed_from_bytes(bytes))
+    }
+}
+
+/// Trait for noise functions that can be made to repeat after a period, so
+/// that they tile seamlessly.
+///
+/// Only the generators built on a square lattice, `Perlin`, `Value` and
+/// `Worley`, and the fractals built from them, can be made periodic.
+pub trait Periodic {
+    /// Sets the period, in input units, after which the noise repeats along
+    /// every axis. `None` removes the period.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the period is `Some(0)`.
+    fn set_period(self, period: Option<usize>) -> Self;
+
+    /// Returns the period after which the noise repeats, if any.
+    fn period(&self) -> Option<usize>;
+}
+
+/// Trait for noise functions that can reseed themselves and all of their
+/// source functions from a single seed.
+///
+/// Generators and fractals are given the seed directly. Every other noise
+/// function passes each of its source functions a different seed derived from
+/// its own with `derive_seed`, so no two nodes in a graph end up with the same
+/// or neighbouring seeds, and reseeding a graph with the same seed always
+/// gives the same result. Noise functions without a seed are left unchanged.
+pub trait Reseedable {
+    /// Reseeds the noise function and all of its source functions.
+    fn reseed(self, seed: u64) -> Self;
+}
+
+/// Derives the seed for source function `index` of a noise function reseeded
+/// with `seed`.
+///
+/// The seeds are mixed by a hash function, so neighbouring seeds and indices
+/// give unrelated results.
+pub fn derive_seed(seed: u64, index: u32) -> u64 {
+    // SplitMix64, stepped once per index.
+    let mut x = seed.wrapping_add((u64::from(index) + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// Hashes arbitrary bytes, such as a string, into a seed.
+///
+/// The hash is stable across platforms and versions of this crate, so the
+/// same string always gives the same seed.
+pub fn seed_from_bytes<B: AsRef<[u8]>>(bytes: B) -> u64 {
+    // FNV-1a, followed by the SplitMix64 finalizer to mix the high bits.
+    let mut x = bytes
+        .as_ref()
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325, |hash: u64, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use {Add, Blend, Constant, Fbm, NoiseFn, NoiseFnWithGradient, Perlin};
+
+    /// Asserts that `get_many` returns exactly what `get` does, for points
+    /// spread over several cells.
+    pub fn assert_get_many_matches_get<T, M>(noise: &M)
+    where
+        T: Copy + Default + AsMut<[f64]>,
+        M: NoiseFn<T>,
+    {
+        let points: Vec<T> = (0..18)
+            .map(|i| {
+                let mut point = T::default();
+                for (axis, x) in point.as_mut().iter_mut().enumerate() {
+                    *x = f64::from(i) * (0.37 - 0.29 * axis as f64) + axis as f64 - 3.0;
+                }
+                point
+            })
+            .collect();
+        let mut values = vec![0.0; points.len()];
+        noise.get_many(&points, &mut values);
+
+        for (&point, &value) in points.iter().zip(&values) {
+            assert_eq!(value, noise.get(point));
+        }
+    }
+
+    /// Asserts that the analytic gradient of `noise` agrees with central
+    /// differences at each of `points`.
+    pub fn assert_gradient_matches<T, M>(noise: &M, points: &[T])
+    where
+        T: Copy + AsRef<[f64]> + AsMut<[f64]>,
+        M: NoiseFnWithGradient<T>,
+    {
+        let h = 1e-6;
+        for &point in points {
+            let (value, gradient) = noise.get_with_gradient(point);
+            assert_eq!(value, noise.get(point));
+
+            for axis in 0..point.as_ref().len() {
+                let (mut above, mut below) = (point, point);
+                above.as_mut()[axis] += h;
+                below.as_mut()[axis] -= h;
+                let estimate = (noise.get(above) - noise.get(below)) / (2.0 * h);
+                assert!((estimate - gradient.as_ref()[axis]).abs() < 1e-6);
+            }
+        }
+    }
+
+    /// Asserts that `noise` repeats every `period` units along each axis,
+    /// and not after a third of the period.
+    pub fn assert_tiles<M: NoiseFn<[f64; 3]>>(noise: &M, period: f64) {
+        for &[x, y, z] in &[[0.3, 1.7, -2.1], [4.2, -0.6, 8.9], [-7.5, 3.25, 0.0]] {
+            let value = noise.get([x, y, z]);
+            assert!(value != noise.get([x + period / 3.0, y, z]));
+            for &shifted in &[[x + period, y, z], [x, y - period, z + 2.0 * period]] {
+                assert!((value - noise.get(shifted)).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_owned_graph_is_clone_send_and_static() {
+        fn assert_owned<T: Clone + Send + 'static>(_: &T) {}
+
+        let graph = Blend::new(
+            Fbm::<Perlin>::new(),
+            Add::new(Perlin::new(), Constant::new(0.5)),
+            Perlin::new(),
+        );
+        assert_owned(&graph);
+
+        let copy = graph.clone();
+        let value = thread::spawn(move || copy.get([0.5, 1.5, 2.5]))
+            .join()
+            .unwrap();
+        assert_eq!(value, graph.get([0.5, 1.5, 2.5]));
+    }
 }
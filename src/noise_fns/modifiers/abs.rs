@@ -6,22 +6,38 @@
 // project carrying such notice may not be copied, modified, or distributed
 // except according to those terms.
 
-use noise_fns::NoiseFn;
+use noise_fns::{NoiseFn, Reseedable, derive_seed};
 
 /// Noise function that outputs the absolute value of the output value from the
 /// source function.
-pub struct Abs<'a, T: 'a> {
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Abs<Source> {
     /// Outputs a value.
-    pub source: &'a NoiseFn<T>,
+    pub source: Source,
 }
 
-impl<'a, T> Abs<'a, T> {
-    pub fn new(source: &'a NoiseFn<T>) -> Abs<'a, T> {
+impl<Source> Abs<Source> {
+    pub fn new(source: Source) -> Abs<Source> {
         Abs { source: source }
     }
 }
 
-impl<'a, T> NoiseFn<T> for Abs<'a, T> {
+impl<Source> Reseedable for Abs<Source>
+where
+    Source: Reseedable,
+{
+    fn reseed(self, seed: u64) -> Abs<Source> {
+        Abs {
+            source: self.source.reseed(derive_seed(seed, 0)),
+        }
+    }
+}
+
+impl<Source, T> NoiseFn<T> for Abs<Source>
+where
+    Source: NoiseFn<T>,
+{
     fn get(&self, point: T) -> f64 {
         (self.source.get(point)).abs()
     }
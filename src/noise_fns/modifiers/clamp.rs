@@ -7,13 +7,15 @@
 // except according to those terms.
 
 use math;
-use noise_fns::NoiseFn;
+use noise_fns::{NoiseFn, Reseedable, derive_seed};
 
 /// Noise function that clamps the output value from the source function to a
 /// range of values.
-pub struct Clamp<'a, T: 'a> {
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Clamp<Source> {
     /// Outputs a value.
-    pub source: &'a NoiseFn<T>,
+    pub source: Source,
 
     /// Lower bound of the clamping range. Default is -1.0.
     pub lower_bound: f64,
@@ -22,8 +24,8 @@ pub struct Clamp<'a, T: 'a> {
     pub upper_bound: f64,
 }
 
-impl<'a, T> Clamp<'a, T> {
-    pub fn new(source: &'a NoiseFn<T>) -> Clamp<'a, T> {
+impl<Source> Clamp<Source> {
+    pub fn new(source: Source) -> Clamp<Source> {
         Clamp {
             source: source,
             lower_bound: -1.0,
@@ -31,21 +33,21 @@ impl<'a, T> Clamp<'a, T> {
         }
     }
 
-    pub fn set_lower_bound(self, lower_bound: f64) -> Clamp<'a, T> {
+    pub fn set_lower_bound(self, lower_bound: f64) -> Clamp<Source> {
         Clamp {
             lower_bound: lower_bound,
             ..self
         }
     }
 
-    pub fn set_upper_bound(self, upper_bound: f64) -> Clamp<'a, T> {
+    pub fn set_upper_bound(self, upper_bound: f64) -> Clamp<Source> {
         Clamp {
             upper_bound: upper_bound,
             ..self
         }
     }
 
-    pub fn set_bounds(self, lower_bound: f64, upper_bound: f64) -> Clamp<'a, T> {
+    pub fn set_bounds(self, lower_bound: f64, upper_bound: f64) -> Clamp<Source> {
         Clamp {
             lower_bound: lower_bound,
             upper_bound: upper_bound,
@@ -54,7 +56,22 @@ impl<'a, T> Clamp<'a, T> {
     }
 }
 
-impl<'a, T> NoiseFn<T> for Clamp<'a, T> {
+impl<Source> Reseedable for Clamp<Source>
+where
+    Source: Reseedable,
+{
+    fn reseed(self, seed: u64) -> Clamp<Source> {
+        Clamp {
+            source: self.source.reseed(derive_seed(seed, 0)),
+            ..self
+        }
+    }
+}
+
+impl<Source, T> NoiseFn<T> for Clamp<Source>
+where
+    Source: NoiseFn<T>,
+{
     fn get(&self, point: T) -> f64 {
         let value = self.source.get(point);
 
@@ -7,7 +7,7 @@
 // except according to those terms.
 
 use math::{clamp, interp};
-use noise_fns::NoiseFn;
+use noise_fns::{NoiseFn, Reseedable, derive_seed};
 use std;
 
 /// Noise function that maps the output value from the source function onto an
@@ -24,28 +24,32 @@ use std;
 /// four control points to the curve. If there is less than four control
 /// points, the get() method panics. Each control point can have any input
 /// and output value, although no two control points can have the same input.
-pub struct Curve<'a, T: 'a> {
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Curve<Source> {
     /// Outputs a value.
-    pub source: &'a NoiseFn<T>,
+    pub source: Source,
 
     /// Vec that stores the control points.
     control_points: Vec<ControlPoint<f64>>,
 }
 
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 struct ControlPoint<T> {
     input: T,
     output: T,
 }
 
-impl<'a, T> Curve<'a, T> {
-    pub fn new(source: &'a NoiseFn<T>) -> Curve<'a, T> {
+impl<Source> Curve<Source> {
+    pub fn new(source: Source) -> Curve<Source> {
         Curve {
             source: source,
             control_points: Vec::with_capacity(4),
         }
     }
 
-    pub fn add_control_point(mut self, input_value: f64, output_value: f64) -> Curve<'a, T> {
+    pub fn add_control_point(mut self, input_value: f64, output_value: f64) -> Curve<Source> {
         // check to see if the vector already contains the input point.
         if !self.control_points
             .iter()
@@ -71,7 +75,22 @@ impl<'a, T> Curve<'a, T> {
     }
 }
 
-impl<'a, T> NoiseFn<T> for Curve<'a, T> {
+impl<Source> Reseedable for Curve<Source>
+where
+    Source: Reseedable,
+{
+    fn reseed(self, seed: u64) -> Curve<Source> {
+        Curve {
+            source: self.source.reseed(derive_seed(seed, 0)),
+            ..self
+        }
+    }
+}
+
+impl<Source, T> NoiseFn<T> for Curve<Source>
+where
+    Source: NoiseFn<T>,
+{
     fn get(&self, point: T) -> f64 {
         // confirm that there's at least 4 control points in the vector.
         assert!(self.control_points.len() >= 4);
@@ -6,7 +6,7 @@
 // project carrying such notice may not be copied, modified, or distributed
 // except according to those terms.
 
-use noise_fns::NoiseFn;
+use noise_fns::{NoiseFn, Reseedable, derive_seed};
 
 /// Noise function that maps the output value from the source function onto an
 /// exponential curve.
@@ -15,24 +15,26 @@ use noise_fns::NoiseFn;
 /// this noise function first normalizes the output value (the range becomes 0.0
 /// to 1.0), maps that value onto an exponential curve, then rescales that
 /// value back to the original range.
-pub struct Exponent<'a, T: 'a> {
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Exponent<Source> {
     /// Outputs a value.
-    pub source: &'a NoiseFn<T>,
+    pub source: Source,
 
     /// Exponent to apply to the output value from the source function. Default
     /// is 1.0.
     pub exponent: f64,
 }
 
-impl<'a, T> Exponent<'a, T> {
-    pub fn new(source: &'a NoiseFn<T>) -> Exponent<'a, T> {
+impl<Source> Exponent<Source> {
+    pub fn new(source: Source) -> Exponent<Source> {
         Exponent {
             source: source,
             exponent: 1.0,
         }
     }
 
-    pub fn set_exponent(self, exponent: f64) -> Exponent<'a, T> {
+    pub fn set_exponent(self, exponent: f64) -> Exponent<Source> {
         Exponent {
             exponent: exponent,
             ..self
@@ -40,7 +42,22 @@ impl<'a, T> Exponent<'a, T> {
     }
 }
 
-impl<'a, T> NoiseFn<T> for Exponent<'a, T> {
+impl<Source> Reseedable for Exponent<Source>
+where
+    Source: Reseedable,
+{
+    fn reseed(self, seed: u64) -> Exponent<Source> {
+        Exponent {
+            source: self.source.reseed(derive_seed(seed, 0)),
+            ..self
+        }
+    }
+}
+
+impl<Source, T> NoiseFn<T> for Exponent<Source>
+where
+    Source: NoiseFn<T>,
+{
     fn get(&self, point: T) -> f64 {
         let mut value = self.source.get(point);
         value = (value + 1.0) / 2.0;
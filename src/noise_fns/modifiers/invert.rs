@@ -6,21 +6,37 @@
 // project carrying such notice may not be copied, modified, or distributed
 // except according to those terms.
 
-use noise_fns::NoiseFn;
+use noise_fns::{NoiseFn, Reseedable, derive_seed};
 
 /// Noise function that inverts the output value from the source function.
-pub struct Invert<'a, T: 'a> {
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Invert<Source> {
     /// Outputs a value.
-    pub source: &'a NoiseFn<T>,
+    pub source: Source,
 }
 
-impl<'a, T> Invert<'a, T> {
-    pub fn new(source: &'a NoiseFn<T>) -> Invert<'a, T> {
+impl<Source> Invert<Source> {
+    pub fn new(source: Source) -> Invert<Source> {
         Invert { source: source }
     }
 }
 
-impl<'a, T> NoiseFn<T> for Invert<'a, T> {
+impl<Source> Reseedable for Invert<Source>
+where
+    Source: Reseedable,
+{
+    fn reseed(self, seed: u64) -> Invert<Source> {
+        Invert {
+            source: self.source.reseed(derive_seed(seed, 0)),
+        }
+    }
+}
+
+impl<Source, T> NoiseFn<T> for Invert<Source>
+where
+    Source: NoiseFn<T>,
+{
     fn get(&self, point: T) -> f64 {
         -self.source.get(point)
     }
@@ -6,16 +6,23 @@
 // project carrying such notice may not be copied, modified, or distributed
 // except according to those terms.
 
-use noise_fns::NoiseFn;
+use math;
+use math::{Point2, Point3, Point4, Vector2, Vector3, Vector4};
+use noise_fns::{NoiseFn, NoiseFnWithGradient, Reseedable, derive_seed};
 
 /// Noise function that applies a scaling factor and a bias to the output value
 /// from the source function.
 ///
 /// The function retrieves the output value from the source function, multiplies
 /// it with the scaling factor, adds the bias to it, then outputs the value.
-pub struct ScaleBias<'a, T: 'a> {
+///
+/// If the source function provides gradients, so does `ScaleBias`; the
+/// gradient is multiplied by the scaling factor.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScaleBias<Source> {
     /// Outputs a value.
-    pub source: &'a NoiseFn<T>,
+    pub source: Source,
 
     /// Scaling factor to apply to the output value from the source function.
     /// The default value is 1.0.
@@ -26,8 +33,8 @@ pub struct ScaleBias<'a, T: 'a> {
     pub bias: f64,
 }
 
-impl<'a, T> ScaleBias<'a, T> {
-    pub fn new(source: &'a NoiseFn<T>) -> ScaleBias<'a, T> {
+impl<Source> ScaleBias<Source> {
+    pub fn new(source: Source) -> ScaleBias<Source> {
         ScaleBias {
             source: source,
             scale: 1.0,
@@ -35,20 +42,79 @@ impl<'a, T> ScaleBias<'a, T> {
         }
     }
 
-    pub fn set_scale(self, scale: f64) -> ScaleBias<'a, T> {
+    pub fn set_scale(self, scale: f64) -> ScaleBias<Source> {
         ScaleBias {
             scale: scale,
             ..self
         }
     }
 
-    pub fn set_bias(self, bias: f64) -> ScaleBias<'a, T> {
+    pub fn set_bias(self, bias: f64) -> ScaleBias<Source> {
         ScaleBias { bias: bias, ..self }
     }
 }
 
-impl<'a, T> NoiseFn<T> for ScaleBias<'a, T> {
+impl<Source> Reseedable for ScaleBias<Source>
+where
+    Source: Reseedable,
+{
+    fn reseed(self, seed: u64) -> ScaleBias<Source> {
+        ScaleBias {
+            source: self.source.reseed(derive_seed(seed, 0)),
+            ..self
+        }
+    }
+}
+
+impl<Source, T> NoiseFn<T> for ScaleBias<Source>
+where
+    Source: NoiseFn<T>,
+{
     fn get(&self, point: T) -> f64 {
         (self.source.get(point)).mul_add(self.scale, self.bias)
     }
 }
+
+impl<Source> NoiseFnWithGradient<Point2<f64>> for ScaleBias<Source>
+where
+    Source: NoiseFnWithGradient<Point2<f64>>,
+{
+    fn get_with_gradient(&self, point: Point2<f64>) -> (f64, Vector2<f64>) {
+        let (value, gradient) = self.source.get_with_gradient(point);
+        (value.mul_add(self.scale, self.bias), math::mul2(gradient, self.scale))
+    }
+}
+
+impl<Source> NoiseFnWithGradient<Point3<f64>> for ScaleBias<Source>
+where
+    Source: NoiseFnWithGradient<Point3<f64>>,
+{
+    fn get_with_gradient(&self, point: Point3<f64>) -> (f64, Vector3<f64>) {
+        let (value, gradient) = self.source.get_with_gradient(point);
+        (value.mul_add(self.scale, self.bias), math::mul3(gradient, self.scale))
+    }
+}
+
+impl<Source> NoiseFnWithGradient<Point4<f64>> for ScaleBias<Source>
+where
+    Source: NoiseFnWithGradient<Point4<f64>>,
+{
+    fn get_with_gradient(&self, point: Point4<f64>) -> (f64, Vector4<f64>) {
+        let (value, gradient) = self.source.get_with_gradient(point);
+        (value.mul_add(self.scale, self.bias), math::mul4(gradient, self.scale))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use noise_fns::tests::assert_gradient_matches;
+    use {Perlin, ScaleBias};
+
+    #[test]
+    fn test_gradient_matches_finite_differences() {
+        let scale_bias = ScaleBias::new(Perlin::new()).set_scale(2.5).set_bias(-0.5);
+        assert_gradient_matches(&scale_bias, &[[1.3, -2.7], [-0.6, 8.2]]);
+        assert_gradient_matches(&scale_bias, &[[1.3, -2.7, 0.4], [-0.6, 8.2, -3.3]]);
+        assert_gradient_matches(&scale_bias, &[[1.3, -2.7, 0.4, 5.1], [-0.6, 8.2, -3.3, 0.9]]);
+    }
+}
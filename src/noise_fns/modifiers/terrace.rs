@@ -7,7 +7,7 @@
 // except according to those terms.
 
 use math::{clamp, interp};
-use noise_fns::NoiseFn;
+use noise_fns::{NoiseFn, Reseedable, derive_seed};
 use std;
 
 /// Noise function that maps the output value from the source function onto a
@@ -33,9 +33,11 @@ use std;
 ///
 /// This noise function is often used to generate terrain features such as the
 /// stereotypical desert canyon.
-pub struct Terrace<'a, T: 'a> {
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Terrace<Source> {
     /// Outputs a value.
-    pub source: &'a NoiseFn<T>,
+    pub source: Source,
 
     /// Determines if the terrace-forming curve between all control points is
     /// inverted.
@@ -45,8 +47,8 @@ pub struct Terrace<'a, T: 'a> {
     control_points: Vec<f64>,
 }
 
-impl<'a, T> Terrace<'a, T> {
-    pub fn new(source: &'a NoiseFn<T>) -> Terrace<'a, T> {
+impl<Source> Terrace<Source> {
+    pub fn new(source: Source) -> Terrace<Source> {
         Terrace {
             source: source,
             invert_terraces: false,
@@ -61,7 +63,7 @@ impl<'a, T> Terrace<'a, T> {
     /// At the control points, its slope resets to zero.
     ///
     /// It does not matter which order these points are added in.
-    pub fn add_control_point(mut self, control_point: f64) -> Terrace<'a, T> {
+    pub fn add_control_point(mut self, control_point: f64) -> Terrace<Source> {
         // check to see if the vector already contains the input point.
         if !self.control_points
             .iter()
@@ -84,7 +86,7 @@ impl<'a, T> Terrace<'a, T> {
 
     /// Enables or disables the inversion of the terrain-forming curve between
     /// the control points.
-    pub fn invert_terraces(self, invert_terraces: bool) -> Terrace<'a, T> {
+    pub fn invert_terraces(self, invert_terraces: bool) -> Terrace<Source> {
         Terrace {
             invert_terraces: invert_terraces,
             ..self
@@ -92,7 +94,22 @@ impl<'a, T> Terrace<'a, T> {
     }
 }
 
-impl<'a, T> NoiseFn<T> for Terrace<'a, T> {
+impl<Source> Reseedable for Terrace<Source>
+where
+    Source: Reseedable,
+{
+    fn reseed(self, seed: u64) -> Terrace<Source> {
+        Terrace {
+            source: self.source.reseed(derive_seed(seed, 0)),
+            ..self
+        }
+    }
+}
+
+impl<Source, T> NoiseFn<T> for Terrace<Source>
+where
+    Source: NoiseFn<T>,
+{
     fn get(&self, point: T) -> f64 {
         // confirm that there's at least 2 control points in the vector.
         assert!(self.control_points.len() >= 2);
@@ -0,0 +1,320 @@
+// Copyright (c) 2017 The Noise-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. All files in the
+// project carrying such notice may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Arithmetic operators for composing noise functions.
+//!
+//! The operators build the same nodes that can be built by hand:
+//!
+//! * `a + b` and `a * b` build `Add` and `Multiply`.
+//! * `a - b` builds `Add` of `a` and `Invert` of `b`.
+//! * `a / b` builds `Multiply` of `a` and `Power` of `b` to a `Constant` -1.
+//! * `-a` builds `Invert`.
+//! * Adding, subtracting, multiplying or dividing by an `f64` builds a
+//!   `ScaleBias`. Further scalar operations on a `ScaleBias` update its scale
+//!   and bias rather than wrapping it again.
+//!
+//! The left-hand side of an operator can be any noise function of this crate
+//! or a boxed `dyn NoiseFn` trait object. The standard operator traits can't
+//! be implemented for noise function types of other crates, so those can only
+//! appear on the right-hand side, once they implement `NoiseFnOps`.
+
+use noise_fns::*;
+use std::ops;
+
+/// Marker trait for noise function types that can be used with the arithmetic
+/// operators.
+///
+/// Every noise function in this crate implements it, as do `dyn NoiseFn`
+/// trait objects, such as the `BoxedNoiseFn` built by the `graph` module, and
+/// references and boxes holding any of them. It tells noise functions apart
+/// from the `f64` scalars that the operators also accept.
+///
+/// It can't be implemented for every `NoiseFn<T>`, as most noise functions
+/// implement `NoiseFn` for several point types, so the point type of a call
+/// such as `perlin.abs()` couldn't be inferred. Implementing it for your own
+/// noise function type, with `impl NoiseFnOps for MyNoise {}`, gives it the
+/// methods of `NoiseFnExt` and lets it be used on the right-hand side of an
+/// operator.
+pub trait NoiseFnOps {}
+
+impl<M: NoiseFnOps + ?Sized> NoiseFnOps for &M {}
+
+impl<M: NoiseFnOps + ?Sized> NoiseFnOps for Box<M> {}
+
+impl<'a, T> NoiseFnOps for dyn NoiseFn<T> + 'a {}
+
+impl<'a, T> NoiseFnOps for dyn NoiseFn<T> + Send + 'a {}
+
+impl<'a, T> NoiseFnOps for dyn NoiseFn<T> + Send + Sync + 'a {}
+
+/// Implements the operators between a noise function type and other noise
+/// functions.
+macro_rules! impl_noise_fn_ops {
+    ([$($param:ident),*] $ty:ty) => {
+        impl<$($param,)* Rhs> ops::Add<Rhs> for $ty
+        where
+            Rhs: NoiseFnOps,
+        {
+            type Output = Add<Self, Rhs>;
+
+            fn add(self, rhs: Rhs) -> Self::Output {
+                Add::new(self, rhs)
+            }
+        }
+
+        impl<$($param,)* Rhs> ops::Sub<Rhs> for $ty
+        where
+            Rhs: NoiseFnOps,
+        {
+            type Output = Add<Self, Invert<Rhs>>;
+
+            fn sub(self, rhs: Rhs) -> Self::Output {
+                Add::new(self, Invert::new(rhs))
+            }
+        }
+
+        impl<$($param,)* Rhs> ops::Mul<Rhs> for $ty
+        where
+            Rhs: NoiseFnOps,
+        {
+            type Output = Multiply<Self, Rhs>;
+
+            fn mul(self, rhs: Rhs) -> Self::Output {
+                Multiply::new(self, rhs)
+            }
+        }
+
+        impl<$($param,)* Rhs> ops::Div<Rhs> for $ty
+        where
+            Rhs: NoiseFnOps,
+        {
+            type Output = Multiply<Self, Power<Rhs, Constant>>;
+
+            fn div(self, rhs: Rhs) -> Self::Output {
+                Multiply::new(self, Power::new(rhs, Constant::new(-1.0)))
+            }
+        }
+
+        impl<$($param),*> ops::Neg for $ty {
+            type Output = Invert<Self>;
+
+            fn neg(self) -> Self::Output {
+                Invert::new(self)
+            }
+        }
+
+        impl<$($param),*> ops::Add<$ty> for f64 {
+            type Output = ScaleBias<$ty>;
+
+            fn add(self, rhs: $ty) -> Self::Output {
+                ScaleBias::new(rhs).set_bias(self)
+            }
+        }
+
+        impl<$($param),*> ops::Sub<$ty> for f64 {
+            type Output = ScaleBias<$ty>;
+
+            fn sub(self, rhs: $ty) -> Self::Output {
+                ScaleBias::new(rhs).set_scale(-1.0).set_bias(self)
+            }
+        }
+
+        impl<$($param),*> ops::Mul<$ty> for f64 {
+            type Output = ScaleBias<$ty>;
+
+            fn mul(self, rhs: $ty) -> Self::Output {
+                ScaleBias::new(rhs).set_scale(self)
+            }
+        }
+
+        impl<$($param),*> ops::Div<$ty> for f64 {
+            type Output = ScaleBias<Power<$ty, Constant>>;
+
+            fn div(self, rhs: $ty) -> Self::Output {
+                ScaleBias::new(Power::new(rhs, Constant::new(-1.0))).set_scale(self)
+            }
+        }
+    };
+}
+
+/// Implements the operators between a noise function type and `f64` scalars,
+/// wrapping it in a `ScaleBias`.
+macro_rules! impl_scalar_ops {
+    ([$($param:ident),*] $ty:ty) => {
+        impl<$($param),*> ops::Add<f64> for $ty {
+            type Output = ScaleBias<Self>;
+
+            fn add(self, rhs: f64) -> Self::Output {
+                ScaleBias::new(self).set_bias(rhs)
+            }
+        }
+
+        impl<$($param),*> ops::Sub<f64> for $ty {
+            type Output = ScaleBias<Self>;
+
+            fn sub(self, rhs: f64) -> Self::Output {
+                ScaleBias::new(self).set_bias(-rhs)
+            }
+        }
+
+        impl<$($param),*> ops::Mul<f64> for $ty {
+            type Output = ScaleBias<Self>;
+
+            fn mul(self, rhs: f64) -> Self::Output {
+                ScaleBias::new(self).set_scale(rhs)
+            }
+        }
+
+        impl<$($param),*> ops::Div<f64> for $ty {
+            type Output = ScaleBias<Self>;
+
+            fn div(self, rhs: f64) -> Self::Output {
+                ScaleBias::new(self).set_scale(1.0 / rhs)
+            }
+        }
+    };
+}
+
+macro_rules! impl_ops {
+    ([$($param:ident),*] $ty:ty) => {
+        impl<$($param),*> NoiseFnOps for $ty {}
+
+        impl_noise_fn_ops!([$($param),*] $ty);
+        impl_scalar_ops!([$($param),*] $ty);
+    };
+}
+
+impl_ops!([] Checkerboard);
+impl_ops!([] Constant);
+impl_ops!([] Cylinders);
+impl_ops!([] NoiseMapSource);
+impl_ops!([] OpenSimplex);
+impl_ops!([] Perlin);
+impl_ops!([] SuperSimplex);
+impl_ops!([] Value);
+
+impl_ops!([T] BasicMulti<T>);
+impl_ops!([T] Billow<T>);
+impl_ops!([T] Fbm<T>);
+impl_ops!([T] HybridMulti<T>);
+impl_ops!([T] RidgedMulti<T>);
+
+impl_ops!([Source] Worley<Source>);
+
+impl_ops!([Source] Abs<Source>);
+impl_ops!([Source] Clamp<Source>);
+impl_ops!([Source] Curve<Source>);
+impl_ops!([Source] Exponent<Source>);
+impl_ops!([Source] Invert<Source>);
+impl_ops!([Source] Terrace<Source>);
+
+impl_ops!([Source1, Source2] Add<Source1, Source2>);
+impl_ops!([Source1, Source2] Max<Source1, Source2>);
+impl_ops!([Source1, Source2] Min<Source1, Source2>);
+impl_ops!([Source1, Source2] Multiply<Source1, Source2>);
+impl_ops!([Source1, Source2] Power<Source1, Source2>);
+
+impl_ops!([Source1, Source2, Control] Blend<Source1, Source2, Control>);
+impl_ops!([Source1, Source2, Control] Select<Source1, Source2, Control>);
+
+impl_ops!([Source, X, Y, Z, U] Displace<Source, X, Y, Z, U>);
+impl_ops!([Source] RotatePoint<Source>);
+impl_ops!([Source] ScalePoint<Source>);
+impl_ops!([Source] TranslatePoint<Source>);
+impl_ops!([Source] Turbulence<Source>);
+
+impl_ops!([Source] Cache<Source>);
+impl_ops!([Source] SyncCache<Source>);
+
+// Boxed trait objects, such as the `BoxedNoiseFn` built by the `graph` module,
+// are already marked through `Box<M>`, so they only need the operators.
+impl_noise_fn_ops!([T] Box<dyn NoiseFn<T>>);
+impl_noise_fn_ops!([T] Box<dyn NoiseFn<T> + Send>);
+impl_noise_fn_ops!([T] Box<dyn NoiseFn<T> + Send + Sync>);
+impl_scalar_ops!([T] Box<dyn NoiseFn<T>>);
+impl_scalar_ops!([T] Box<dyn NoiseFn<T> + Send>);
+impl_scalar_ops!([T] Box<dyn NoiseFn<T> + Send + Sync>);
+
+// Scalar operations on a `ScaleBias` fold into its scale and bias.
+impl<Source> NoiseFnOps for ScaleBias<Source> {}
+impl_noise_fn_ops!([Source] ScaleBias<Source>);
+
+impl<Source> ops::Add<f64> for ScaleBias<Source> {
+    type Output = ScaleBias<Source>;
+
+    fn add(self, rhs: f64) -> Self::Output {
+        let bias = self.bias + rhs;
+        self.set_bias(bias)
+    }
+}
+
+impl<Source> ops::Sub<f64> for ScaleBias<Source> {
+    type Output = ScaleBias<Source>;
+
+    fn sub(self, rhs: f64) -> Self::Output {
+        let bias = self.bias - rhs;
+        self.set_bias(bias)
+    }
+}
+
+impl<Source> ops::Mul<f64> for ScaleBias<Source> {
+    type Output = ScaleBias<Source>;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        let (scale, bias) = (self.scale * rhs, self.bias * rhs);
+        self.set_scale(scale).set_bias(bias)
+    }
+}
+
+impl<Source> ops::Div<f64> for ScaleBias<Source> {
+    type Output = ScaleBias<Source>;
+
+    fn div(self, rhs: f64) -> Self::Output {
+        let (scale, bias) = (self.scale / rhs, self.bias / rhs);
+        self.set_scale(scale).set_bias(bias)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Add, Fbm, Invert, NoiseFn, Perlin, ScaleBias, Value};
+
+    #[test]
+    fn test_operators_build_nodes() {
+        let fbm = Fbm::new();
+        let perlin = Perlin::new();
+        let value = Value::new();
+
+        let scaled: ScaleBias<Fbm> = fbm.clone() * 0.5 + 0.25;
+        assert_eq!((scaled.scale, scaled.bias), (0.5, 0.25));
+
+        let difference: Add<Fbm, Invert<&Perlin>> = fbm.clone() - &perlin;
+        let inverted: ScaleBias<Value> = 1.0 - value.clone();
+
+        let point = [0.3, 1.7, -2.1];
+        assert_eq!(scaled.get(point), fbm.get(point) * 0.5 + 0.25);
+        assert_eq!(difference.get(point), fbm.get(point) - perlin.get(point));
+        assert_eq!(inverted.get(point), 1.0 - value.get(point));
+    }
+
+    #[test]
+    fn test_operators_on_boxed_noise_fn() {
+        let fbm = Fbm::new();
+        let perlin = Perlin::new();
+
+        let boxed: Box<dyn NoiseFn<[f64; 3]>> = Box::new(fbm.clone());
+        let sum = boxed * 0.5 + &perlin;
+
+        let boxed: Box<dyn NoiseFn<[f64; 3]> + Send + Sync> = Box::new(perlin.clone());
+        let difference = 1.0 - boxed;
+
+        let point = [0.3, 1.7, -2.1];
+        assert_eq!(sum.get(point), fbm.get(point) * 0.5 + perlin.get(point));
+        assert_eq!(difference.get(point), 1.0 - perlin.get(point));
+    }
+}
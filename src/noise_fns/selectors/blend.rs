@@ -7,33 +7,35 @@
 // except according to those terms.
 
 use math::interp;
-use noise_fns::NoiseFn;
+use noise_fns::{NoiseFn, Reseedable, derive_seed};
 
 /// Noise function that outputs a weighted blend of the output values from two
 /// source functions given the output value supplied by a control function.
 ///
 /// This noise function uses linear interpolation to perform the blending
 /// operation.
-pub struct Blend<'a, T: 'a> {
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Blend<Source1, Source2, Control> {
     /// Outputs one of the values to blend.
-    pub source1: &'a NoiseFn<T>,
+    pub source1: Source1,
 
     /// Outputs one of the values to blend.
-    pub source2: &'a NoiseFn<T>,
+    pub source2: Source2,
 
     /// Determines the weight of the blending operation. Negative values weight
     /// the blend towards the output value from the `source1` function. Positive
     /// values weight the blend towards the output value from the `source2`
     /// function.
-    pub control: &'a NoiseFn<T>,
+    pub control: Control,
 }
 
-impl<'a, T> Blend<'a, T> {
+impl<Source1, Source2, Control> Blend<Source1, Source2, Control> {
     pub fn new(
-        source1: &'a NoiseFn<T>,
-        source2: &'a NoiseFn<T>,
-        control: &'a NoiseFn<T>,
-    ) -> Blend<'a, T> {
+        source1: Source1,
+        source2: Source2,
+        control: Control,
+    ) -> Blend<Source1, Source2, Control> {
         Blend {
             source1: source1,
             source2: source2,
@@ -42,9 +44,27 @@ impl<'a, T> Blend<'a, T> {
     }
 }
 
-impl<'a, T> NoiseFn<T> for Blend<'a, T>
+impl<Source1, Source2, Control> Reseedable for Blend<Source1, Source2, Control>
+where
+    Source1: Reseedable,
+    Source2: Reseedable,
+    Control: Reseedable,
+{
+    fn reseed(self, seed: u64) -> Blend<Source1, Source2, Control> {
+        Blend {
+            source1: self.source1.reseed(derive_seed(seed, 0)),
+            source2: self.source2.reseed(derive_seed(seed, 1)),
+            control: self.control.reseed(derive_seed(seed, 2)),
+        }
+    }
+}
+
+impl<Source1, Source2, Control, T> NoiseFn<T> for Blend<Source1, Source2, Control>
 where
     T: Copy,
+    Source1: NoiseFn<T>,
+    Source2: NoiseFn<T>,
+    Control: NoiseFn<T>,
 {
     fn get(&self, point: T) -> f64 {
         let lower = self.source1.get(point);
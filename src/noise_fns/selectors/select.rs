@@ -7,22 +7,24 @@
 // except according to those terms.
 
 use math::interp;
-use noise_fns::NoiseFn;
+use noise_fns::{NoiseFn, Reseedable, derive_seed};
 
 /// Noise function that outputs the value selected from one of two source
 /// functions chosen by the output value from a control function.
-pub struct Select<'a, T: 'a> {
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Select<Source1, Source2, Control> {
     /// Outputs a value.
-    pub source1: &'a NoiseFn<T>,
+    pub source1: Source1,
 
     /// Outputs a value.
-    pub source2: &'a NoiseFn<T>,
+    pub source2: Source2,
 
     /// Determines the value to select. If the output value from
     /// the control function is within a range of values know as the _selection
     /// range_, this noise function outputs the value from `source2`.
     /// Otherwise, this noise function outputs the value from `source1`.
-    pub control: &'a NoiseFn<T>,
+    pub control: Control,
 
     /// Lower bound of the selection range. Default is 0.0.
     pub lower_bound: f64,
@@ -34,12 +36,12 @@ pub struct Select<'a, T: 'a> {
     pub edge_falloff: f64,
 }
 
-impl<'a, T> Select<'a, T> {
+impl<Source1, Source2, Control> Select<Source1, Source2, Control> {
     pub fn new(
-        source1: &'a NoiseFn<T>,
-        source2: &'a NoiseFn<T>,
-        control: &'a NoiseFn<T>,
-    ) -> Select<'a, T> {
+        source1: Source1,
+        source2: Source2,
+        control: Control,
+    ) -> Select<Source1, Source2, Control> {
         Select {
             source1: source1,
             source2: source2,
@@ -50,7 +52,7 @@ impl<'a, T> Select<'a, T> {
         }
     }
 
-    pub fn set_bounds(self, lower: f64, upper: f64) -> Select<'a, T> {
+    pub fn set_bounds(self, lower: f64, upper: f64) -> Select<Source1, Source2, Control> {
         Select {
             lower_bound: lower,
             upper_bound: upper,
@@ -58,7 +60,7 @@ impl<'a, T> Select<'a, T> {
         }
     }
 
-    pub fn set_edge_falloff(self, falloff: f64) -> Select<'a, T> {
+    pub fn set_edge_falloff(self, falloff: f64) -> Select<Source1, Source2, Control> {
         Select {
             edge_falloff: falloff,
             ..self
@@ -66,9 +68,28 @@ impl<'a, T> Select<'a, T> {
     }
 }
 
-impl<'a, T> NoiseFn<T> for Select<'a, T>
+impl<Source1, Source2, Control> Reseedable for Select<Source1, Source2, Control>
+where
+    Source1: Reseedable,
+    Source2: Reseedable,
+    Control: Reseedable,
+{
+    fn reseed(self, seed: u64) -> Select<Source1, Source2, Control> {
+        Select {
+            source1: self.source1.reseed(derive_seed(seed, 0)),
+            source2: self.source2.reseed(derive_seed(seed, 1)),
+            control: self.control.reseed(derive_seed(seed, 2)),
+            ..self
+        }
+    }
+}
+
+impl<Source1, Source2, Control, T> NoiseFn<T> for Select<Source1, Source2, Control>
 where
     T: Copy,
+    Source1: NoiseFn<T>,
+    Source2: NoiseFn<T>,
+    Control: NoiseFn<T>,
 {
     fn get(&self, point: T) -> f64 {
         let control_value = self.control.get(point);
@@ -0,0 +1,282 @@
+// Copyright (c) 2017 The Noise-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. All files in the
+// project carrying such notice may not be copied, modified, or distributed
+// except according to those terms.
+
+use math::{Point2, Point3, Point4};
+use noise_fns::{NoiseFn, Reseedable};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "serde")]
+use serde::ser::SerializeStruct;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering, fence};
+
+/// Default number of entries in a `SyncCache`.
+pub const DEFAULT_SYNC_CACHE_ENTRIES: usize = 256;
+
+/// Noise function that caches recent output values generated by the source
+/// function, and can be shared between threads.
+///
+/// `Cache` remembers only the last value, and can't be shared between threads.
+/// `SyncCache` instead keeps a fixed number of entries keyed on the input
+/// coordinates, and is `Sync`, so one noise function graph can be evaluated
+/// from many worker threads at once.
+///
+/// Each point is assigned to one entry by hashing its coordinates, and
+/// replaces whichever point was previously stored there. The entries are
+/// updated without locking: if a thread finds its entry being written by
+/// another thread, it calls `Source::get` itself rather than waiting. The
+/// output is always the same as calling the source function directly.
+pub struct SyncCache<Source> {
+    /// Outputs the values to be cached.
+    pub source: Source,
+
+    entries: Vec<Entry>,
+}
+
+impl<Source> SyncCache<Source> {
+    pub fn new(source: Source) -> SyncCache<Source> {
+        SyncCache {
+            source: source,
+            entries: build_entries(DEFAULT_SYNC_CACHE_ENTRIES),
+        }
+    }
+
+    /// Sets the number of entries in the cache, discarding any cached values.
+    ///
+    /// The number is rounded up to the next power of two.
+    pub fn set_entries(self, entries: usize) -> SyncCache<Source> {
+        SyncCache {
+            entries: build_entries(entries),
+            ..self
+        }
+    }
+
+    /// Returns the number of entries in the cache.
+    pub fn entries(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn get_or_insert<F>(&self, point: &[f64], get: F) -> f64
+    where
+        F: FnOnce() -> f64,
+    {
+        let entry = &self.entries[hash(point) & (self.entries.len() - 1)];
+
+        // The sequence number is odd while the entry is being written, and
+        // changes with every write, so an entry read between two loads of the
+        // same even sequence number is consistent.
+        let sequence = entry.sequence.load(Ordering::Acquire);
+        if sequence & 1 == 0 {
+            let hit = entry.dimensions.load(Ordering::Relaxed) == point.len() &&
+                point
+                    .iter()
+                    .zip(&entry.point)
+                    .all(|(&x, cached)| cached.load(Ordering::Relaxed) == x.to_bits());
+            let value = entry.value.load(Ordering::Relaxed);
+
+            fence(Ordering::Acquire);
+            if hit && entry.sequence.load(Ordering::Relaxed) == sequence {
+                return f64::from_bits(value);
+            }
+        }
+
+        let value = get();
+
+        // Only one thread may write an entry at a time. Any other thread that
+        // misses on it in the meantime just doesn't store its value.
+        let sequence = entry.sequence.load(Ordering::Relaxed);
+        if sequence & 1 == 0 &&
+            entry
+                .sequence
+                .compare_exchange(sequence, sequence + 1, Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+        {
+            fence(Ordering::Release);
+
+            entry.dimensions.store(point.len(), Ordering::Relaxed);
+            for (&x, cached) in point.iter().zip(&entry.point) {
+                cached.store(x.to_bits(), Ordering::Relaxed);
+            }
+            entry.value.store(value.to_bits(), Ordering::Relaxed);
+
+            entry.sequence.store(sequence + 2, Ordering::Release);
+        }
+
+        value
+    }
+}
+
+/// Cloning a `SyncCache` clones its source function, with an empty cache of
+/// the same size.
+impl<Source> Clone for SyncCache<Source>
+where
+    Source: Clone,
+{
+    fn clone(&self) -> SyncCache<Source> {
+        SyncCache {
+            source: self.source.clone(),
+            entries: build_entries(self.entries.len()),
+        }
+    }
+}
+
+impl<Source> fmt::Debug for SyncCache<Source>
+where
+    Source: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SyncCache")
+            .field("source", &self.source)
+            .field("entries", &self.entries.len())
+            .finish()
+    }
+}
+
+/// Reseeding a `SyncCache` reseeds its source function and discards the cached
+/// values.
+impl<Source> Reseedable for SyncCache<Source>
+where
+    Source: Reseedable,
+{
+    fn reseed(self, seed: u64) -> SyncCache<Source> {
+        let entries = self.entries.len();
+        SyncCache::new(self.source.reseed(seed)).set_entries(entries)
+    }
+}
+
+/// Only the source function and the number of entries are serialized.
+#[cfg(feature = "serde")]
+impl<Source> Serialize for SyncCache<Source>
+where
+    Source: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("SyncCache", 2)?;
+        state.serialize_field("source", &self.source)?;
+        state.serialize_field("entries", &self.entries.len())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, Source> Deserialize<'de> for SyncCache<Source>
+where
+    Source: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct SyncCacheConfig<Source> {
+            source: Source,
+            entries: usize,
+        }
+
+        let config = SyncCacheConfig::deserialize(deserializer)?;
+        Ok(SyncCache::new(config.source).set_entries(config.entries))
+    }
+}
+
+impl<Source> NoiseFn<Point2<f64>> for SyncCache<Source>
+where
+    Source: NoiseFn<Point2<f64>>,
+{
+    fn get(&self, point: Point2<f64>) -> f64 {
+        self.get_or_insert(&point, || self.source.get(point))
+    }
+}
+
+impl<Source> NoiseFn<Point3<f64>> for SyncCache<Source>
+where
+    Source: NoiseFn<Point3<f64>>,
+{
+    fn get(&self, point: Point3<f64>) -> f64 {
+        self.get_or_insert(&point, || self.source.get(point))
+    }
+}
+
+impl<Source> NoiseFn<Point4<f64>> for SyncCache<Source>
+where
+    Source: NoiseFn<Point4<f64>>,
+{
+    fn get(&self, point: Point4<f64>) -> f64 {
+        self.get_or_insert(&point, || self.source.get(point))
+    }
+}
+
+/// A single cached point and its value.
+struct Entry {
+    /// Incremented before and after every write, so that it is odd while the
+    /// entry is being written.
+    sequence: AtomicUsize,
+
+    /// Number of coordinates in `point`, or 0 if the entry is empty.
+    dimensions: AtomicUsize,
+
+    /// Bits of the coordinates of the cached point.
+    point: [AtomicU64; 4],
+
+    /// Bits of the cached value.
+    value: AtomicU64,
+}
+
+impl Entry {
+    fn new() -> Entry {
+        Entry {
+            sequence: AtomicUsize::new(0),
+            dimensions: AtomicUsize::new(0),
+            point: [
+                AtomicU64::new(0),
+                AtomicU64::new(0),
+                AtomicU64::new(0),
+                AtomicU64::new(0),
+            ],
+            value: AtomicU64::new(0),
+        }
+    }
+}
+
+fn build_entries(entries: usize) -> Vec<Entry> {
+    let entries = entries.max(1).next_power_of_two();
+    (0..entries).map(|_| Entry::new()).collect()
+}
+
+#[inline]
+fn hash(point: &[f64]) -> usize {
+    let mut hash = point.len() as u64;
+    for &x in point {
+        hash = (hash.rotate_left(5) ^ x.to_bits()).wrapping_mul(0x517c_c1b7_2722_0a95);
+    }
+
+    // The low bits of the product are poorly mixed, so fold in the high bits.
+    (hash ^ (hash >> 32)) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use {NoiseFn, Perlin, SyncCache};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn test_shared_between_threads() {
+        let cache = Arc::new(SyncCache::new(Perlin::new()).set_entries(16));
+
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let cache = cache.clone();
+                thread::spawn(move || for i in 0..1000 {
+                    let point = [(i % 37) as f64 * 0.1, (i % 23) as f64 * 0.3];
+                    assert_eq!(cache.get(point), Perlin::new().get(point));
+                })
+            })
+            .collect();
+
+        for thread in threads {
+            thread.join().unwrap();
+        }
+    }
+}
@@ -7,10 +7,12 @@
 // except according to those terms.
 
 use math::{Point2, Point3, Point4};
-use noise_fns::NoiseFn;
+use noise_fns::{NoiseFn, Reseedable, derive_seed};
 
 /// Noise function that uses multiple source functions to displace each coordinate
 /// of the input value before returning the output value from the `source` function.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Displace<Source, XDisplace, YDisplace, ZDisplace, UDisplace> {
     /// Source function that outputs a value
     pub source: Source,
@@ -52,6 +54,26 @@ impl<Source, XDisplace, YDisplace, ZDisplace, UDisplace>
 }
 
 #[cfg_attr(rustfmt, rustfmt_skip)]
+impl<Source, XDisplace, YDisplace, ZDisplace, UDisplace> Reseedable
+    for Displace<Source, XDisplace, YDisplace, ZDisplace, UDisplace>
+where
+    Source: Reseedable,
+    XDisplace: Reseedable,
+    YDisplace: Reseedable,
+    ZDisplace: Reseedable,
+    UDisplace: Reseedable,
+{
+    fn reseed(self, seed: u64) -> Self {
+        Displace {
+            source: self.source.reseed(derive_seed(seed, 0)),
+            x_displace: self.x_displace.reseed(derive_seed(seed, 1)),
+            y_displace: self.y_displace.reseed(derive_seed(seed, 2)),
+            z_displace: self.z_displace.reseed(derive_seed(seed, 3)),
+            u_displace: self.u_displace.reseed(derive_seed(seed, 4)),
+        }
+    }
+}
+
 impl<Source, XDisplace, YDisplace, ZDisplace, UDisplace> NoiseFn<Point2<f64>>
     for Displace<Source, XDisplace, YDisplace, ZDisplace, UDisplace>
     where Source: NoiseFn<Point2<f64>>,
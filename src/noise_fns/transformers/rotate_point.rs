@@ -7,7 +7,7 @@
 // except according to those terms.
 
 use math::{Point2, Point3, Point4};
-use noise_fns::NoiseFn;
+use noise_fns::{NoiseFn, Reseedable, derive_seed};
 
 /// Noise function that rotates the input value around the origin before
 /// returning the output value from the source function.
@@ -17,6 +17,8 @@ use noise_fns::NoiseFn;
 ///
 /// The coordinate system of the input value is assumed to be "right-handed"
 /// (_x_ increases to the right, _y_ increases upward, and _z_ increases inward).
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RotatePoint<Source> {
     /// Source function that outputs a value
     pub source: Source,
@@ -104,6 +106,18 @@ impl<Source> RotatePoint<Source> {
     }
 }
 
+impl<Source> Reseedable for RotatePoint<Source>
+where
+    Source: Reseedable,
+{
+    fn reseed(self, seed: u64) -> RotatePoint<Source> {
+        RotatePoint {
+            source: self.source.reseed(derive_seed(seed, 0)),
+            ..self
+        }
+    }
+}
+
 impl<Source> NoiseFn<Point2<f64>> for RotatePoint<Source>
 where
     Source: NoiseFn<Point2<f64>>,
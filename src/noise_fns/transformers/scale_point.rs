@@ -7,13 +7,15 @@
 // except according to those terms.
 
 use math::{Point2, Point3, Point4};
-use noise_fns::NoiseFn;
+use noise_fns::{NoiseFn, Reseedable, derive_seed};
 
 /// Noise function that scales the coordinates of the input value before
 /// returning the output value from the source function.
 ///
 /// The get() method multiplies the coordinates of the input value with a
 /// scaling factor before returning the output value from the source function.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ScalePoint<Source> {
     /// Source function that outputs a value
     pub source: Source,
@@ -112,6 +114,18 @@ impl<Source> ScalePoint<Source> {
     }
 }
 
+impl<Source> Reseedable for ScalePoint<Source>
+where
+    Source: Reseedable,
+{
+    fn reseed(self, seed: u64) -> ScalePoint<Source> {
+        ScalePoint {
+            source: self.source.reseed(derive_seed(seed, 0)),
+            ..self
+        }
+    }
+}
+
 impl<Source> NoiseFn<Point2<f64>> for ScalePoint<Source>
 where
     Source: NoiseFn<Point2<f64>>,
@@ -7,13 +7,15 @@
 // except according to those terms.
 
 use math::{Point2, Point3, Point4};
-use noise_fns::NoiseFn;
+use noise_fns::{NoiseFn, Reseedable, derive_seed};
 
 /// Noise function that moves the coordinates of the input value before
 /// returning the output value from the source function.
 ///
 /// The get() method moves the coordinates of the input value by a translation
 /// amount before returning the output value from the source function.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TranslatePoint<Source> {
     /// Source function that outputs a value
     pub source: Source,
@@ -112,6 +114,18 @@ impl<Source> TranslatePoint<Source> {
     }
 }
 
+impl<Source> Reseedable for TranslatePoint<Source>
+where
+    Source: Reseedable,
+{
+    fn reseed(self, seed: u64) -> TranslatePoint<Source> {
+        TranslatePoint {
+            source: self.source.reseed(derive_seed(seed, 0)),
+            ..self
+        }
+    }
+}
+
 impl<Source> NoiseFn<Point2<f64>> for TranslatePoint<Source>
 where
     Source: NoiseFn<Point2<f64>>,
@@ -7,10 +7,10 @@
 // except according to those terms.
 
 use math::{Point2, Point3, Point4};
-use noise_fns::{Fbm, MultiFractal, NoiseFn, Seedable};
+use noise_fns::{Fbm, MultiFractal, NoiseFn, Reseedable, Seedable, derive_seed};
 
 /// Default seed for the Turbulence noise function.
-pub const DEFAULT_TURBULENCE_SEED: u32 = 0;
+pub const DEFAULT_TURBULENCE_SEED: u64 = 0;
 /// Default frequency for the Turbulence noise function.
 pub const DEFAULT_TURBULENCE_FREQUENCY: f64 = 1.0;
 /// Default power for the turbulence noise function.
@@ -27,6 +27,8 @@ pub const DEFAULT_TURBULENCE_ROUGHNESS: usize = 3;
 /// turbulence, an application can modify its frequency, its power, and its
 /// roughness.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "TurbulenceConfig<Source>"))]
 pub struct Turbulence<Source> {
     /// Source function that outputs a value.
     pub source: Source,
@@ -41,13 +43,40 @@ pub struct Turbulence<Source> {
     /// Affects the roughness of the turbulence. Higher values are rougher.
     pub roughness: usize,
 
-    seed: u32,
+    seed: u64,
+    #[cfg_attr(feature = "serde", serde(skip_serializing))]
     x_distort_function: Fbm,
+    #[cfg_attr(feature = "serde", serde(skip_serializing))]
     y_distort_function: Fbm,
+    #[cfg_attr(feature = "serde", serde(skip_serializing))]
     z_distort_function: Fbm,
+    #[cfg_attr(feature = "serde", serde(skip_serializing))]
     u_distort_function: Fbm,
 }
 
+/// Serialized form of `Turbulence`. The distortion functions are rebuilt from
+/// the seed.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct TurbulenceConfig<Source> {
+    source: Source,
+    frequency: f64,
+    power: f64,
+    roughness: usize,
+    seed: u64,
+}
+
+#[cfg(feature = "serde")]
+impl<Source> From<TurbulenceConfig<Source>> for Turbulence<Source> {
+    fn from(config: TurbulenceConfig<Source>) -> Turbulence<Source> {
+        Turbulence::new(config.source)
+            .set_seed(config.seed)
+            .set_frequency(config.frequency)
+            .set_power(config.power)
+            .set_roughness(config.roughness)
+    }
+}
+
 impl<Source> Turbulence<Source> {
     pub fn new(source: Source) -> Turbulence<Source> {
         Turbulence {
@@ -106,22 +135,34 @@ impl<Source> Turbulence<Source> {
 }
 
 impl<Source> Seedable for Turbulence<Source> {
-    fn set_seed(self, seed: u32) -> Turbulence<Source> {
+    fn set_seed(self, seed: u64) -> Turbulence<Source> {
         Turbulence {
             seed: seed,
             x_distort_function: self.x_distort_function.set_seed(seed),
-            y_distort_function: self.y_distort_function.set_seed(seed + 1),
-            z_distort_function: self.z_distort_function.set_seed(seed + 2),
-            u_distort_function: self.u_distort_function.set_seed(seed + 3),
+            y_distort_function: self.y_distort_function.set_seed(seed.wrapping_add(1)),
+            z_distort_function: self.z_distort_function.set_seed(seed.wrapping_add(2)),
+            u_distort_function: self.u_distort_function.set_seed(seed.wrapping_add(3)),
             ..self
         }
     }
 
-    fn seed(&self) -> u32 {
+    fn seed(&self) -> u64 {
         self.seed
     }
 }
 
+impl<Source> Reseedable for Turbulence<Source>
+where
+    Source: Reseedable,
+{
+    fn reseed(self, seed: u64) -> Turbulence<Source> {
+        Turbulence {
+            source: self.source.reseed(derive_seed(seed, 0)),
+            ..self
+        }.set_seed(derive_seed(seed, 1))
+    }
+}
+
 impl<Source> NoiseFn<Point2<f64>> for Turbulence<Source>
 where
     Source: NoiseFn<Point2<f64>>,
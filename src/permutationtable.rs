@@ -7,11 +7,40 @@
 // except according to those terms.
 
 use math::{Point2, Point3, Point4};
-use rand::{Rand, Rng, SeedableRng, XorShiftRng};
+#[cfg(feature = "rand")]
+use rand::{Rand, Rng};
+#[cfg(feature = "serde")]
+use serde::de::Error;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 
 const TABLE_SIZE: usize = 256;
 
+/// How a `PermutationTable` hashes lattice points.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LatticeMode {
+    /// Look each coordinate up in the permutation table in turn. This is fast,
+    /// but only the low 8 bits of each coordinate are used, so the noise
+    /// repeats every 256 lattice units along each axis.
+    Permutation,
+
+    /// Hash the full coordinates together with a key derived from the seed
+    /// using 64-bit integer arithmetic. This is slightly slower, but the noise
+    /// effectively never repeats.
+    Hash,
+}
+
+/// Default `LatticeMode` for permutation tables.
+pub const DEFAULT_LATTICE_MODE: LatticeMode = LatticeMode::Permutation;
+
+impl Default for LatticeMode {
+    fn default() -> Self {
+        DEFAULT_LATTICE_MODE
+    }
+}
+
 /// A seed table, required by all noise functions.
 ///
 /// Table creation is expensive, so in most circumstances you'll only want to
@@ -19,64 +48,458 @@ const TABLE_SIZE: usize = 256;
 #[derive(Copy)]
 pub struct PermutationTable {
     values: [u8; TABLE_SIZE],
+    mode: LatticeMode,
+
+    /// Period the lattice coordinates are wrapped to before hashing, if any.
+    period: Option<isize>,
+
+    /// Key mixed into every hash in `LatticeMode::Hash`.
+    key: u64,
+
+    /// Whether the table was generated from `key` as a seed.
+    seeded: bool,
 }
 
+#[cfg(feature = "rand")]
 impl Rand for PermutationTable {
     /// Generates a PermutationTable using a random seed.
     fn rand<R: Rng>(rng: &mut R) -> PermutationTable {
         let mut seq: Vec<u8> = (0..TABLE_SIZE).map(|x| x as u8).collect();
-        rng.shuffle(&mut *seq);
+        rng.shuffle(&mut seq);
 
         // It's unfortunate that this double-initializes the array, but Rust
         // doesn't currently provide a clean way to do this in one pass. Hopefully
         // it won't matter, as Seed creation will usually be a one-time event.
-        let mut perm_table = PermutationTable { values: [0; TABLE_SIZE] };
+        let mut values = [0; TABLE_SIZE];
         let seq_it = seq.iter();
-        for (x, y) in perm_table.values.iter_mut().zip(seq_it) {
+        for (x, y) in values.iter_mut().zip(seq_it) {
             *x = *y
         }
-        perm_table
+        PermutationTable::from_values(values)
     }
 }
 
 impl PermutationTable {
-    /// Deterministically generates a new permutation table based on a `u32` seed value.
+    /// Deterministically generates a new permutation table based on a `u64` seed value.
     ///
-    /// Internally this uses a `XorShiftRng`, but we don't really need to worry
-    /// about cryptographic security when working with procedural noise.
-    pub fn new(seed: u32) -> PermutationTable {
-        let mut rng: XorShiftRng = SeedableRng::from_seed([1, seed, seed, seed]);
+    /// The table is a shuffle of `0..256` by an `XorShift128` generator seeded
+    /// with `[1, low, low, low ^ high]`, where `low` and `high` are the low and
+    /// high 32 bits of the seed. Both the generator and the shuffle are part of
+    /// this crate, so the table for a seed never changes. Seeds that fit in a
+    /// `u32` give the same tables as they did when seeds were 32-bit.
+    pub fn new(seed: u64) -> PermutationTable {
+        let low = seed as u32;
+        let high = (seed >> 32) as u32;
+        let mut rng = XorShift128::new([1, low, low, low ^ high]);
+
+        let mut values = [0; TABLE_SIZE];
+        for (i, value) in values.iter_mut().enumerate() {
+            *value = i as u8;
+        }
+        rng.shuffle(&mut values);
+
+        PermutationTable {
+            values: values,
+            mode: DEFAULT_LATTICE_MODE,
+            period: None,
+            key: seed,
+            seeded: true,
+        }
+    }
+
+    /// Generates a new permutation table by shuffling with the given random
+    /// number generator.
+    #[cfg(feature = "rand")]
+    pub fn from_rng<R: Rng>(rng: &mut R) -> PermutationTable {
         rng.gen()
     }
 
+    /// Uses the given array as the permutation table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` is not a permutation of `0..256`, i.e. if any value
+    /// appears more than once.
+    pub fn from_permutation(values: [u8; TABLE_SIZE]) -> PermutationTable {
+        if let Some(value) = repeated_value(&values) {
+            panic!("{} appears more than once in the permutation", value);
+        }
+
+        PermutationTable::from_values(values)
+    }
+
+    /// Builds a table from a permutation, deriving the hash key from its
+    /// first values.
+    fn from_values(values: [u8; TABLE_SIZE]) -> PermutationTable {
+        let key = values[..8]
+            .iter()
+            .fold(0, |key, &value| (key << 8) | u64::from(value));
+
+        PermutationTable {
+            values: values,
+            mode: DEFAULT_LATTICE_MODE,
+            period: None,
+            key: key,
+            seeded: false,
+        }
+    }
+
+    /// Returns whether the table was generated from a seed by
+    /// `PermutationTable::new`, rather than built from a random number
+    /// generator or an explicit permutation.
+    ///
+    /// Noise functions only serialize their permutation table when it wasn't
+    /// generated from a seed, as it's otherwise rebuilt from their seed.
+    pub fn is_seeded(&self) -> bool {
+        self.seeded
+    }
+
+    /// Sets how lattice points are hashed.
+    pub fn set_mode(self, mode: LatticeMode) -> PermutationTable {
+        PermutationTable {
+            mode: mode,
+            ..self
+        }
+    }
+
+    /// Returns how lattice points are hashed.
+    pub fn mode(&self) -> LatticeMode {
+        self.mode
+    }
+
+    /// Sets the period the lattice coordinates are wrapped to before they are
+    /// hashed, making the lattice repeat every `period` points along each
+    /// axis. `None` leaves the coordinates as they are.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the period is zero.
+    pub fn set_period(self, period: Option<usize>) -> PermutationTable {
+        assert!(period != Some(0), "the period of a permutation table must not be zero");
+
+        PermutationTable {
+            period: period.map(|period| period as isize),
+            ..self
+        }
+    }
+
+    /// Returns the period the lattice coordinates are wrapped to, if any.
+    pub fn period(&self) -> Option<usize> {
+        self.period.map(|period| period as usize)
+    }
+
+    #[inline(always)]
+    fn wrap(&self, x: isize) -> isize {
+        match self.period {
+            Some(period) => x.rem_euclid(period),
+            None => x,
+        }
+    }
+
+    /// Wrapped coordinates of the two lattice points of a cell along one axis.
+    #[inline(always)]
+    fn axis(&self, x: isize) -> [isize; 2] {
+        [self.wrap(x), self.wrap(x + 1)]
+    }
+
     #[inline(always)]
     pub fn get1(&self, x: isize) -> usize {
+        let x = self.wrap(x);
+        match self.mode {
+            LatticeMode::Permutation => self.lookup1(x),
+            LatticeMode::Hash => finish(step(self.key, x)),
+        }
+    }
+
+    #[inline(always)]
+    pub fn get2(&self, pos: Point2<isize>) -> usize {
+        let pos = [self.wrap(pos[0]), self.wrap(pos[1])];
+        match self.mode {
+            LatticeMode::Permutation => self.lookup2(pos),
+            LatticeMode::Hash => finish(step(step(self.key, pos[0]), pos[1])),
+        }
+    }
+
+    #[inline(always)]
+    pub fn get3(&self, pos: Point3<isize>) -> usize {
+        let pos = [self.wrap(pos[0]), self.wrap(pos[1]), self.wrap(pos[2])];
+        match self.mode {
+            LatticeMode::Permutation => self.lookup3(pos),
+            LatticeMode::Hash => finish(step(step(step(self.key, pos[0]), pos[1]), pos[2])),
+        }
+    }
+
+    #[inline(always)]
+    pub fn get4(&self, pos: Point4<isize>) -> usize {
+        let pos = [
+            self.wrap(pos[0]),
+            self.wrap(pos[1]),
+            self.wrap(pos[2]),
+            self.wrap(pos[3]),
+        ];
+        match self.mode {
+            LatticeMode::Permutation => self.lookup4(pos),
+            LatticeMode::Hash => {
+                let xyz = step(step(step(self.key, pos[0]), pos[1]), pos[2]);
+                finish(step(xyz, pos[3]))
+            },
+        }
+    }
+
+    #[inline(always)]
+    fn lookup1(&self, x: isize) -> usize {
         let x = (x & 0xff) as usize;
         self.values[x] as usize
     }
 
     #[inline(always)]
-    pub fn get2(&self, pos: Point2<isize>) -> usize {
+    fn lookup2(&self, pos: Point2<isize>) -> usize {
         let y = (pos[1] & 0xff) as usize;
-        self.values[self.get1(pos[0]) ^ y] as usize
+        self.values[self.lookup1(pos[0]) ^ y] as usize
     }
 
     #[inline(always)]
-    pub fn get3(&self, pos: Point3<isize>) -> usize {
+    fn lookup3(&self, pos: Point3<isize>) -> usize {
         let z = (pos[2] & 0xff) as usize;
-        self.values[self.get2([pos[0], pos[1]]) ^ z] as usize
+        self.values[self.lookup2([pos[0], pos[1]]) ^ z] as usize
     }
 
     #[inline(always)]
-    pub fn get4(&self, pos: Point4<isize>) -> usize {
+    fn lookup4(&self, pos: Point4<isize>) -> usize {
         let w = (pos[3] & 0xff) as usize;
-        self.values[self.get3([pos[0], pos[1], pos[2]]) ^ w] as usize
+        self.values[self.lookup3([pos[0], pos[1], pos[2]]) ^ w] as usize
+    }
+
+    /// Hashes every corner of the lattice cell whose near corner is `cell`.
+    /// Bit n of the index into the result selects the far corner along axis n,
+    /// so `corners2(cell)[i]` equals `get2` of that corner. The lookups for
+    /// the lower dimensions are shared between the corners.
+    #[inline(always)]
+    pub fn corners2(&self, cell: Point2<isize>) -> [usize; 4] {
+        let (x, y) = (self.axis(cell[0]), self.axis(cell[1]));
+        match self.mode {
+            LatticeMode::Permutation => self.lookup_corners2(x, y),
+            LatticeMode::Hash => {
+                let mut corners = [0; 4];
+                for (corner, &hash) in corners.iter_mut().zip(&self.hash_corners2(x, y)) {
+                    *corner = finish(hash);
+                }
+                corners
+            },
+        }
+    }
+
+    #[inline(always)]
+    pub fn corners3(&self, cell: Point3<isize>) -> [usize; 8] {
+        let (x, y, z) = (self.axis(cell[0]), self.axis(cell[1]), self.axis(cell[2]));
+        let mut corners = [0; 8];
+        match self.mode {
+            LatticeMode::Permutation => {
+                let xy = self.lookup_corners2(x, y);
+                for (i, corner) in corners.iter_mut().enumerate() {
+                    *corner = self.values[xy[i & 3] ^ (z[i >> 2] & 0xff) as usize] as usize;
+                }
+            },
+            LatticeMode::Hash => {
+                for (corner, &hash) in corners.iter_mut().zip(&self.hash_corners3(x, y, z)) {
+                    *corner = finish(hash);
+                }
+            },
+        }
+        corners
+    }
+
+    #[inline(always)]
+    pub fn corners4(&self, cell: Point4<isize>) -> [usize; 16] {
+        let (x, y) = (self.axis(cell[0]), self.axis(cell[1]));
+        let (z, w) = (self.axis(cell[2]), self.axis(cell[3]));
+        let mut corners = [0; 16];
+        match self.mode {
+            LatticeMode::Permutation => {
+                let xy = self.lookup_corners2(x, y);
+                let mut xyz = [0; 8];
+                for (i, corner) in xyz.iter_mut().enumerate() {
+                    *corner = self.values[xy[i & 3] ^ (z[i >> 2] & 0xff) as usize] as usize;
+                }
+                for (i, corner) in corners.iter_mut().enumerate() {
+                    *corner = self.values[xyz[i & 7] ^ (w[i >> 3] & 0xff) as usize] as usize;
+                }
+            },
+            LatticeMode::Hash => {
+                let xyz = self.hash_corners3(x, y, z);
+                for (i, corner) in corners.iter_mut().enumerate() {
+                    *corner = finish(step(xyz[i & 7], w[i >> 3]));
+                }
+            },
+        }
+        corners
+    }
+
+    /// Permutation table lookups of the corners of a 2-dimensional cell, given
+    /// the wrapped coordinates along each axis, in the order of `corners2`.
+    #[inline(always)]
+    fn lookup_corners2(&self, x: [isize; 2], y: [isize; 2]) -> [usize; 4] {
+        let x = [self.lookup1(x[0]), self.lookup1(x[1])];
+        let mut lookups = [0; 4];
+        for (i, lookup) in lookups.iter_mut().enumerate() {
+            *lookup = self.values[x[i & 1] ^ (y[i >> 1] & 0xff) as usize] as usize;
+        }
+        lookups
+    }
+
+    /// Unfinished hashes of the corners of a 2-dimensional cell, given the
+    /// wrapped coordinates along each axis, in the order of `corners2`.
+    #[inline(always)]
+    fn hash_corners2(&self, x: [isize; 2], y: [isize; 2]) -> [u64; 4] {
+        let x = [step(self.key, x[0]), step(self.key, x[1])];
+        let mut hashes = [0; 4];
+        for (i, hash) in hashes.iter_mut().enumerate() {
+            *hash = step(x[i & 1], y[i >> 1]);
+        }
+        hashes
+    }
+
+    #[inline(always)]
+    fn hash_corners3(&self, x: [isize; 2], y: [isize; 2], z: [isize; 2]) -> [u64; 8] {
+        let xy = self.hash_corners2(x, y);
+        let mut hashes = [0; 8];
+        for (i, hash) in hashes.iter_mut().enumerate() {
+            *hash = step(xy[i & 3], z[i >> 2]);
+        }
+        hashes
+    }
+}
+
+/// Mixes one coordinate into a lattice hash.
+///
+/// The multiplication only carries bits upwards, so the high half is folded
+/// back down to keep every coordinate bit affecting the low bits.
+#[inline(always)]
+fn step(hash: u64, coordinate: isize) -> u64 {
+    let hash = (hash ^ coordinate as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    hash ^ (hash >> 32)
+}
+
+/// Finishes a lattice hash, returning a value in `0..256` like the
+/// permutation table does.
+#[inline(always)]
+fn finish(hash: u64) -> usize {
+    let hash = (hash ^ (hash >> 29)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    ((hash ^ (hash >> 32)) & 0xff) as usize
+}
+
+/// Marsaglia's xorshift generator with 128 bits of state.
+///
+/// This produces the same sequence as `XorShiftRng` from `rand` 0.3, which
+/// earlier versions of this crate used to build their tables.
+struct XorShift128 {
+    x: u32,
+    y: u32,
+    z: u32,
+    w: u32,
+}
+
+impl XorShift128 {
+    /// The seed must not be all zeros.
+    fn new(seed: [u32; 4]) -> XorShift128 {
+        debug_assert!(seed.iter().any(|&x| x != 0));
+        XorShift128 {
+            x: seed[0],
+            y: seed[1],
+            z: seed[2],
+            w: seed[3],
+        }
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        let t = self.x ^ (self.x << 11);
+        self.x = self.y;
+        self.y = self.z;
+        self.z = self.w;
+        self.w = self.w ^ (self.w >> 19) ^ (t ^ (t >> 8));
+        self.w
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let high = u64::from(self.next_u32());
+        let low = u64::from(self.next_u32());
+        (high << 32) | low
+    }
+
+    /// Returns a uniformly distributed value in `0..bound`.
+    ///
+    /// Values from the generator that fall in the incomplete last multiple of
+    /// `bound` are rejected, so that every result is equally likely.
+    fn below(&mut self, bound: u64) -> u64 {
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+
+    /// Shuffles `values` with the Fisher-Yates algorithm, fixing each element
+    /// from the last to the second in turn.
+    fn shuffle<T>(&mut self, values: &mut [T]) {
+        let mut i = values.len();
+        while i >= 2 {
+            i -= 1;
+            let j = self.below(i as u64 + 1) as usize;
+            values.swap(i, j);
+        }
+    }
+}
+
+/// Returns the first value that appears more than once in `values`, if any.
+fn repeated_value(values: &[u8; TABLE_SIZE]) -> Option<u8> {
+    let mut seen = [false; TABLE_SIZE];
+    for &value in values.iter() {
+        if seen[value as usize] {
+            return Some(value);
+        }
+        seen[value as usize] = true;
+    }
+    None
+}
+
+/// Serializes the permutation itself. The lattice mode and period are set
+/// again by the noise function that owns the table.
+#[cfg(feature = "serde")]
+impl Serialize for PermutationTable {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.values[..].serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for PermutationTable {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let permutation = Vec::<u8>::deserialize(deserializer)?;
+        if permutation.len() != TABLE_SIZE {
+            return Err(D::Error::invalid_length(
+                permutation.len(),
+                &"a permutation of 0..256",
+            ));
+        }
+
+        let mut values = [0; TABLE_SIZE];
+        values.copy_from_slice(&permutation);
+        if let Some(value) = repeated_value(&values) {
+            return Err(D::Error::custom(format!(
+                "{} appears more than once in the permutation",
+                value
+            )));
+        }
+
+        Ok(PermutationTable::from_values(values))
     }
 }
 
 impl Clone for PermutationTable {
     fn clone(&self) -> PermutationTable {
-        PermutationTable { values: self.values }
+        *self
     }
 }
 
@@ -88,8 +511,8 @@ impl fmt::Debug for PermutationTable {
 
 #[cfg(test)]
 mod tests {
-    use {NoiseFn, Perlin, Seedable};
-    use rand::random;
+    use {LatticeMode, NoiseFn, PermutationTable, Perlin, Seedable, seed_from_bytes};
+    use rand::{Rng, SeedableRng, XorShiftRng, random};
 
     #[test]
     fn test_random_seed() {
@@ -102,4 +525,134 @@ mod tests {
         let perlin = Perlin::new();
         let _ = perlin.get([-1.0, 2.0, 3.0]);
     }
+
+    #[test]
+    fn test_golden_tables() {
+        // These tables must never change, or every saved world changes with
+        // them.
+        let golden: [(u64, [u8; 16]); 5] = [
+            (0, [86, 32, 144, 238, 44, 29, 222, 64, 179, 180, 143, 189, 254, 45, 249, 102]),
+            (42, [37, 79, 77, 193, 229, 227, 173, 67, 220, 69, 144, 175, 48, 27, 202, 250]),
+            (
+                0xffff_ffff,
+                [53, 251, 21, 97, 190, 79, 151, 164, 117, 9, 126, 116, 201, 146, 158, 215],
+            ),
+            (
+                0x1_0000_0000,
+                [46, 145, 167, 94, 176, 20, 153, 181, 137, 62, 246, 39, 227, 243, 69, 182],
+            ),
+            (
+                0x0123_4567_89ab_cdef,
+                [144, 217, 63, 141, 251, 132, 248, 213, 190, 167, 65, 175, 67, 246, 60, 180],
+            ),
+        ];
+
+        for &(seed, ref prefix) in golden.iter() {
+            assert_eq!(&PermutationTable::new(seed).values[..16], &prefix[..]);
+        }
+    }
+
+    // `rand` 0.3 draws `usize` values for the shuffle, which are only 64 bits
+    // wide on 64-bit targets.
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn test_tables_match_rand_xorshift() {
+        for &seed in &[0, 1, 42, 0xffff_ffff, 0x1_0000_0000, 0x0123_4567_89ab_cdef] {
+            let low = seed as u32;
+            let high = (seed >> 32) as u32;
+            let mut rng: XorShiftRng = SeedableRng::from_seed([1, low, low, low ^ high]);
+            let mut expected: Vec<u8> = (0..256).map(|x| x as u8).collect();
+            rng.shuffle(&mut expected);
+
+            assert_eq!(&PermutationTable::new(seed).values[..], &expected[..]);
+        }
+    }
+
+    #[test]
+    fn test_seed_from_bytes() {
+        assert_eq!(seed_from_bytes("world"), seed_from_bytes(b"world".to_vec()));
+        assert!(seed_from_bytes("world") != seed_from_bytes("worle"));
+
+        let perlin = Perlin::new().set_seed_from_bytes("world");
+        assert_eq!(perlin.seed(), seed_from_bytes("world"));
+    }
+
+    #[test]
+    fn test_from_permutation() {
+        let mut values = [0; 256];
+        for (i, value) in values.iter_mut().enumerate() {
+            *value = 255 - i as u8;
+        }
+
+        let perlin = Perlin::new().set_permutation_table(PermutationTable::from_permutation(values));
+        assert!(perlin.get([0.5, 1.5, 2.5]) != Perlin::new().get([0.5, 1.5, 2.5]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_from_permutation_rejects_repeats() {
+        PermutationTable::from_permutation([7; 256]);
+    }
+
+    #[test]
+    fn test_hash_mode() {
+        let table = PermutationTable::new(42).set_mode(LatticeMode::Hash);
+
+        // The corners match single lookups, as in permutation mode.
+        let cell = [-3, 255, 1 << 40, 7];
+        let corners = table.corners4(cell);
+        for (i, &corner) in corners.iter().enumerate() {
+            let offset = |axis: usize| cell[axis] + ((i >> axis) & 1) as isize;
+            assert_eq!(corner, table.get4([offset(0), offset(1), offset(2), offset(3)]));
+        }
+
+        // Unlike permutation mode, the lattice doesn't repeat every 256 units.
+        let repeats = (0..64)
+            .filter(|&x| table.get2([x, 5]) == table.get2([x + 256, 5]))
+            .count();
+        assert!(repeats < 8);
+
+        let perlin = Perlin::new().set_seed(7);
+        let hashed = perlin.set_lattice_mode(LatticeMode::Hash);
+        assert_eq!(hashed.set_seed(8).lattice_mode(), LatticeMode::Hash);
+        assert_eq!(perlin.get([1.5, 2.5]), perlin.get([257.5, 2.5]));
+        assert!(hashed.get([1.5, 2.5]) != hashed.get([257.5, 2.5]));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_keeps_custom_tables() {
+        use serde_json;
+        use Worley;
+
+        let mut values = [0; 256];
+        for (i, value) in values.iter_mut().enumerate() {
+            *value = 255 - i as u8;
+        }
+        let table = PermutationTable::from_permutation(values);
+        assert!(!table.is_seeded() && PermutationTable::new(3).is_seeded());
+
+        let perlin = Perlin::new().set_seed(3).set_permutation_table(table);
+        let json = serde_json::to_string(&perlin).unwrap();
+        let copy: Perlin = serde_json::from_str(&json).unwrap();
+
+        let worley = Worley::new()
+            .set_lattice_mode(LatticeMode::Hash)
+            .set_permutation_table(table);
+        let worley_copy: Worley =
+            serde_json::from_str(&serde_json::to_string(&worley).unwrap()).unwrap();
+
+        for &point in &[[0.5, 1.5, 2.5], [-3.2, 0.7, 9.1]] {
+            assert_eq!(perlin.get(point), copy.get(point));
+            assert_eq!(worley.get(point), worley_copy.get(point));
+        }
+
+        let seeded = serde_json::to_string(&Perlin::new().set_seed(3)).unwrap();
+        assert!(json.contains("perm_table") && !seeded.contains("perm_table"));
+
+        let repeated = json.replacen("[255,254,", "[254,254,", 1);
+        assert!(serde_json::from_str::<Perlin>(&repeated).is_err());
+        let short = json.replacen("[255,254,", "[254,", 1);
+        assert!(serde_json::from_str::<Perlin>(&short).is_err());
+    }
 }
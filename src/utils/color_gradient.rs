@@ -0,0 +1,117 @@
+// Copyright (c) 2017 The Noise-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. All files in the
+// project carrying such notice may not be copied, modified, or distributed
+// except according to those terms.
+
+use math;
+
+/// An RGBA color with 8 bits per channel.
+pub type Color = [u8; 4];
+
+/// A color at a position in a `ColorGradient`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GradientPoint {
+    pub position: f64,
+    pub color: Color,
+}
+
+/// Maps noise values to colors by blending between colors at arbitrary
+/// positions.
+///
+/// Values below the first position get the first color, and values above the
+/// last position get the last color.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ColorGradient {
+    points: Vec<GradientPoint>,
+}
+
+impl ColorGradient {
+    /// Creates a gradient without any points.
+    pub fn new() -> ColorGradient {
+        ColorGradient { points: Vec::new() }
+    }
+
+    /// Creates a gradient from black at -1.0 to white at 1.0.
+    pub fn grayscale() -> ColorGradient {
+        ColorGradient::new()
+            .add_gradient_point(-1.0, [0, 0, 0, 255])
+            .add_gradient_point(1.0, [255, 255, 255, 255])
+    }
+
+    /// Creates a gradient from deep water at -1.0, through sand at 0.0, to
+    /// snow at 1.0.
+    pub fn terrain() -> ColorGradient {
+        ColorGradient::new()
+            .add_gradient_point(-1.0, [0, 0, 128, 255])
+            .add_gradient_point(-0.25, [0, 0, 255, 255])
+            .add_gradient_point(0.0, [0, 128, 255, 255])
+            .add_gradient_point(0.0625, [240, 240, 64, 255])
+            .add_gradient_point(0.125, [32, 160, 0, 255])
+            .add_gradient_point(0.375, [224, 224, 0, 255])
+            .add_gradient_point(0.75, [128, 128, 128, 255])
+            .add_gradient_point(1.0, [255, 255, 255, 255])
+    }
+
+    /// Adds a color at the given position, replacing the color already at
+    /// that position, if any.
+    pub fn add_gradient_point(mut self, position: f64, color: Color) -> ColorGradient {
+        let point = GradientPoint {
+            position: position,
+            color: color,
+        };
+
+        match self
+            .points
+            .iter()
+            .position(|point| point.position >= position)
+        {
+            Some(index) if self.points[index].position == position => self.points[index] = point,
+            Some(index) => self.points.insert(index, point),
+            None => self.points.push(point),
+        }
+
+        self
+    }
+
+    /// Returns the points of the gradient, ordered by position.
+    pub fn points(&self) -> &[GradientPoint] {
+        &self.points
+    }
+
+    /// Returns the color at the given position. A gradient without any points
+    /// is transparent black everywhere.
+    pub fn get_color(&self, position: f64) -> Color {
+        let upper = match self
+            .points
+            .iter()
+            .position(|point| point.position > position)
+        {
+            Some(0) => return self.points[0].color,
+            Some(index) => index,
+            None => {
+                return match self.points.last() {
+                    Some(point) => point.color,
+                    None => [0; 4],
+                }
+            },
+        };
+
+        let (lower, upper) = (self.points[upper - 1], self.points[upper]);
+        let alpha = (position - lower.position) / (upper.position - lower.position);
+
+        let mut color = [0; 4];
+        for (channel, (&lower, &upper)) in color
+            .iter_mut()
+            .zip(lower.color.iter().zip(upper.color.iter()))
+        {
+            let value = math::interp::linear(f64::from(lower), f64::from(upper), alpha);
+            *channel = math::clamp(value.round(), 0.0, 255.0) as u8;
+        }
+        color
+    }
+}
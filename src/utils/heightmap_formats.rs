@@ -0,0 +1,478 @@
+// Copyright (c) 2017 The Noise-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. All files in the
+// project carrying such notice may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Readers and writers of `NoiseMap`s in common heightmap formats.
+//!
+//! Integer formats map the normalization range of the map to the full range of
+//! their values, and are read back into a given range. Floating point formats
+//! store the values as they are.
+
+use std::io::{self, Read, Write};
+use std::str::{self, FromStr};
+use utils::{NoiseMap, Normalization};
+
+/// Order of the bytes of multi-byte values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteOrder {
+    LittleEndian,
+    BigEndian,
+}
+
+/// Georeferencing of an ESRI ASCII grid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AsciiGridHeader {
+    /// X coordinate of the lower left corner of the grid.
+    pub x_corner: f64,
+
+    /// Y coordinate of the lower left corner of the grid.
+    pub y_corner: f64,
+
+    /// Width and height of each cell.
+    pub cell_size: f64,
+
+    /// Value marking cells without data, if any.
+    pub no_data_value: Option<f64>,
+}
+
+impl Default for AsciiGridHeader {
+    fn default() -> Self {
+        AsciiGridHeader {
+            x_corner: 0.0,
+            y_corner: 0.0,
+            cell_size: 1.0,
+            no_data_value: None,
+        }
+    }
+}
+
+impl NoiseMap {
+    /// Writes the map as headerless unsigned 16-bit values, row by row, as
+    /// read by most game engines.
+    pub fn write_raw16<W: Write>(
+        &self,
+        mut writer: W,
+        byte_order: ByteOrder,
+        normalization: Normalization,
+    ) -> io::Result<()> {
+        let mut buffer = Vec::with_capacity(self.values().len() * 2);
+        for &value in &self.normalized_values(normalization) {
+            push_u16(&mut buffer, (value * 65535.0).round() as u16, byte_order);
+        }
+        writer.write_all(&buffer)
+    }
+
+    /// Reads a map of the given size from headerless unsigned 16-bit values,
+    /// mapping 0 to `lower` and 65535 to `upper`.
+    pub fn read_raw16<R: Read>(
+        reader: R,
+        (width, height): (usize, usize),
+        byte_order: ByteOrder,
+        (lower, upper): (f64, f64),
+    ) -> io::Result<NoiseMap> {
+        let length = data_length(width, height, 2)?;
+        let mut buffer = Vec::new();
+        reader.take(length as u64).read_to_end(&mut buffer)?;
+        if buffer.len() < length {
+            return Err(invalid_data("unexpected end of data"));
+        }
+
+        let mut map = NoiseMap::new(width, height);
+        for (value, bytes) in map.values_mut().iter_mut().zip(buffer.chunks(2)) {
+            let raw = read_u16([bytes[0], bytes[1]], byte_order);
+            *value = lower + f64::from(raw) / 65535.0 * (upper - lower);
+        }
+        Ok(map)
+    }
+
+    /// Writes the map as a binary 16-bit PGM (portable graymap) image.
+    pub fn write_pgm<W: Write>(
+        &self,
+        mut writer: W,
+        normalization: Normalization,
+    ) -> io::Result<()> {
+        let (width, height) = self.size();
+        writeln!(writer, "P5\n{} {}\n65535", width, height)?;
+        self.write_raw16(writer, ByteOrder::BigEndian, normalization)
+    }
+
+    /// Reads a binary or plain PGM image, mapping 0 to `lower` and the
+    /// largest value of the image to `upper`.
+    pub fn read_pgm<R: Read>(mut reader: R, (lower, upper): (f64, f64)) -> io::Result<NoiseMap> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+
+        let mut header = Header::new(&data);
+        let binary = match header.token()? {
+            "P5" => true,
+            "P2" => false,
+            _ => return Err(invalid_data("expected a PGM image")),
+        };
+        let (width, height) = header.size()?;
+        let max_value: u16 = header.parse()?;
+        if max_value == 0 {
+            return Err(invalid_data("expected a maximum value greater than 0"));
+        }
+
+        let mut raw = Vec::new();
+        if binary {
+            let bytes_per_value = if max_value < 256 { 1 } else { 2 };
+            let pixels = header.binary(data_length(width, height, bytes_per_value)?)?;
+            if bytes_per_value == 1 {
+                raw.extend(pixels.iter().map(|&byte| u16::from(byte)));
+            } else {
+                raw.extend(
+                    pixels
+                        .chunks(2)
+                        .map(|bytes| read_u16([bytes[0], bytes[1]], ByteOrder::BigEndian)),
+                );
+            }
+        } else {
+            for _ in 0..data_length(width, height, 1)? {
+                raw.push(header.parse()?);
+            }
+        }
+
+        let mut map = NoiseMap::new(width, height);
+        let scale = (upper - lower) / f64::from(max_value);
+        for (value, &raw) in map.values_mut().iter_mut().zip(&raw) {
+            *value = lower + f64::from(raw) * scale;
+        }
+        Ok(map)
+    }
+
+    /// Writes the map as a grayscale PFM (portable floatmap) image of 32-bit
+    /// floats. As the format requires, the rows are stored from the bottom of
+    /// the map up.
+    pub fn write_pfm<W: Write>(&self, mut writer: W, byte_order: ByteOrder) -> io::Result<()> {
+        let (width, height) = self.size();
+        let scale = match byte_order {
+            ByteOrder::LittleEndian => "-1.0",
+            ByteOrder::BigEndian => "1.0",
+        };
+        writeln!(writer, "Pf\n{} {}\n{}", width, height, scale)?;
+
+        let mut buffer = Vec::with_capacity(width * height * 4);
+        for row in self.values().chunks(width.max(1)).rev() {
+            for &value in row {
+                let bits = (value as f32).to_bits();
+                match byte_order {
+                    ByteOrder::LittleEndian => buffer.extend_from_slice(&bits.to_le_bytes()),
+                    ByteOrder::BigEndian => buffer.extend_from_slice(&bits.to_be_bytes()),
+                }
+            }
+        }
+        writer.write_all(&buffer)
+    }
+
+    /// Reads a grayscale PFM image.
+    pub fn read_pfm<R: Read>(mut reader: R) -> io::Result<NoiseMap> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+
+        let mut header = Header::new(&data);
+        if header.token()? != "Pf" {
+            return Err(invalid_data("expected a grayscale PFM image"));
+        }
+        let (width, height) = header.size()?;
+        let scale: f64 = header.parse()?;
+        let byte_order = if scale < 0.0 {
+            ByteOrder::LittleEndian
+        } else {
+            ByteOrder::BigEndian
+        };
+        let pixels = header.binary(data_length(width, height, 4)?)?;
+
+        let mut map = NoiseMap::new(width, height);
+        for (y, row) in pixels.chunks(width * 4).enumerate() {
+            for (x, bytes) in row.chunks(4).enumerate() {
+                let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+                let bits = match byte_order {
+                    ByteOrder::LittleEndian => u32::from_le_bytes(bytes),
+                    ByteOrder::BigEndian => u32::from_be_bytes(bytes),
+                };
+                map.set_value(x, height - 1 - y, f64::from(f32::from_bits(bits)));
+            }
+        }
+        Ok(map)
+    }
+
+    /// Writes the map as an ESRI ASCII grid, with the first row of the map as
+    /// the northernmost row of the grid.
+    pub fn write_asc<W: Write>(&self, mut writer: W, header: &AsciiGridHeader) -> io::Result<()> {
+        let (width, height) = self.size();
+        writeln!(writer, "ncols {}\nnrows {}", width, height)?;
+        writeln!(writer, "xllcorner {}", header.x_corner)?;
+        writeln!(writer, "yllcorner {}", header.y_corner)?;
+        writeln!(writer, "cellsize {}", header.cell_size)?;
+        if let Some(no_data_value) = header.no_data_value {
+            writeln!(writer, "NODATA_value {}", no_data_value)?;
+        }
+
+        for row in self.values().chunks(width.max(1)) {
+            let row: Vec<String> = row.iter().map(|value| value.to_string()).collect();
+            writeln!(writer, "{}", row.join(" "))?;
+        }
+        Ok(())
+    }
+
+    /// Reads an ESRI ASCII grid, returning the map and its header. Cells
+    /// without data keep the no data value.
+    pub fn read_asc<R: Read>(mut reader: R) -> io::Result<(NoiseMap, AsciiGridHeader)> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let mut tokens = text.split_whitespace().peekable();
+
+        let (mut width, mut height) = (None, None);
+        let (mut x, mut y, mut centered) = (0.0, 0.0, false);
+        let mut header = AsciiGridHeader::default();
+        while let Some(&key) = tokens.peek() {
+            if !key.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                break;
+            }
+            tokens.next();
+            let value = tokens
+                .next()
+                .ok_or_else(|| invalid_data("expected a header value"))?;
+
+            match key.to_ascii_lowercase().as_str() {
+                "ncols" => width = Some(parse(value)?),
+                "nrows" => height = Some(parse(value)?),
+                "xllcorner" => x = parse(value)?,
+                "yllcorner" => y = parse(value)?,
+                "xllcenter" => {
+                    x = parse(value)?;
+                    centered = true;
+                },
+                "yllcenter" => {
+                    y = parse(value)?;
+                    centered = true;
+                },
+                "cellsize" => header.cell_size = parse(value)?,
+                "nodata_value" => header.no_data_value = Some(parse(value)?),
+                _ => return Err(invalid_data("unknown ESRI ASCII grid header")),
+            }
+        }
+
+        let width = width.ok_or_else(|| invalid_data("expected ncols"))?;
+        let height = height.ok_or_else(|| invalid_data("expected nrows"))?;
+        let length = data_length(width, height, 1)?;
+        let offset = if centered {
+            header.cell_size / 2.0
+        } else {
+            0.0
+        };
+        header.x_corner = x - offset;
+        header.y_corner = y - offset;
+
+        let mut values = Vec::new();
+        for token in tokens.take(length) {
+            values.push(parse(token)?);
+        }
+        if values.len() < length {
+            return Err(invalid_data("expected ncols * nrows values"));
+        }
+
+        let mut map = NoiseMap::new(width, height);
+        map.values_mut().copy_from_slice(&values);
+        Ok((map, header))
+    }
+}
+
+/// Parser of the whitespace separated header of a PGM or PFM image.
+struct Header<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Header<'a> {
+    fn new(data: &'a [u8]) -> Header<'a> {
+        Header {
+            data: data,
+            position: 0,
+        }
+    }
+
+    /// Returns the next token, skipping whitespace and comments.
+    fn token(&mut self) -> io::Result<&'a str> {
+        let mut comment = false;
+        while let Some(&byte) = self.data.get(self.position) {
+            if byte == b'#' {
+                comment = true;
+            } else if byte == b'\n' {
+                comment = false;
+            } else if !comment && !byte.is_ascii_whitespace() {
+                break;
+            }
+            self.position += 1;
+        }
+
+        let start = self.position;
+        while let Some(&byte) = self.data.get(self.position) {
+            if byte.is_ascii_whitespace() {
+                break;
+            }
+            self.position += 1;
+        }
+
+        if start == self.position {
+            return Err(invalid_data("unexpected end of header"));
+        }
+        str::from_utf8(&self.data[start..self.position])
+            .map_err(|_| invalid_data("expected an ASCII header"))
+    }
+
+    fn parse<T: FromStr>(&mut self) -> io::Result<T> {
+        parse(self.token()?)
+    }
+
+    /// Parses the width and height of the image, which must not be zero.
+    fn size(&mut self) -> io::Result<(usize, usize)> {
+        let width: usize = self.parse()?;
+        let height: usize = self.parse()?;
+        if width == 0 || height == 0 {
+            return Err(invalid_data("expected a width and height greater than 0"));
+        }
+        Ok((width, height))
+    }
+
+    /// Skips the single whitespace character ending the header, and returns
+    /// the given number of bytes after it.
+    fn binary(&mut self, length: usize) -> io::Result<&'a [u8]> {
+        let start = self.position + 1;
+        start
+            .checked_add(length)
+            .and_then(|end| self.data.get(start..end))
+            .ok_or_else(|| invalid_data("unexpected end of image data"))
+    }
+}
+
+fn parse<T: FromStr>(token: &str) -> io::Result<T> {
+    token
+        .parse()
+        .map_err(|_| invalid_data(&format!("could not parse {:?}", token)))
+}
+
+/// Returns the number of bytes taken by `width * height` values of
+/// `bytes_per_value` bytes each, or an error if that overflows.
+fn data_length(width: usize, height: usize, bytes_per_value: usize) -> io::Result<usize> {
+    width
+        .checked_mul(height)
+        .and_then(|values| values.checked_mul(bytes_per_value))
+        .ok_or_else(|| invalid_data("the map is too large"))
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn push_u16(buffer: &mut Vec<u8>, value: u16, byte_order: ByteOrder) {
+    match byte_order {
+        ByteOrder::LittleEndian => buffer.extend_from_slice(&value.to_le_bytes()),
+        ByteOrder::BigEndian => buffer.extend_from_slice(&value.to_be_bytes()),
+    }
+}
+
+fn read_u16(bytes: [u8; 2], byte_order: ByteOrder) -> u16 {
+    match byte_order {
+        ByteOrder::LittleEndian => u16::from_le_bytes(bytes),
+        ByteOrder::BigEndian => u16::from_be_bytes(bytes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use utils::*;
+
+    fn ramp() -> NoiseMap {
+        let mut map = NoiseMap::new(3, 2);
+        for (i, value) in map.values_mut().iter_mut().enumerate() {
+            *value = i as f64 * 0.4 - 1.0;
+        }
+        map
+    }
+
+    #[test]
+    fn test_heightmap_round_trips() {
+        let map = ramp();
+
+        for &byte_order in &[ByteOrder::LittleEndian, ByteOrder::BigEndian] {
+            let mut raw = Vec::new();
+            map.write_raw16(&mut raw, byte_order, Normalization::Fixed)
+                .unwrap();
+            let read = NoiseMap::read_raw16(&raw[..], (3, 2), byte_order, (-1.0, 1.0)).unwrap();
+            for (a, b) in map.values().iter().zip(read.values()) {
+                assert!((a - b).abs() < 1e-4);
+            }
+
+            let mut pfm = Vec::new();
+            map.write_pfm(&mut pfm, byte_order).unwrap();
+            let read = NoiseMap::read_pfm(&pfm[..]).unwrap();
+            for (a, b) in map.values().iter().zip(read.values()) {
+                assert!((a - b).abs() < 1e-6);
+            }
+        }
+
+        let mut raw = Vec::new();
+        map.write_raw16(&mut raw, ByteOrder::LittleEndian, Normalization::Auto)
+            .unwrap();
+        assert_eq!(raw[..2], [0, 0]);
+        assert_eq!(raw[10..], [255, 255]);
+
+        let mut pgm = Vec::new();
+        map.write_pgm(&mut pgm, Normalization::Range(-1.0, 1.0))
+            .unwrap();
+        let read = NoiseMap::read_pgm(&pgm[..], (-1.0, 1.0)).unwrap();
+        assert!((read.get_value(2, 1) - 1.0).abs() < 1e-4);
+
+        let plain = b"P2\n# A comment\n2 1\n4\n0 3\n";
+        let read = NoiseMap::read_pgm(&plain[..], (0.0, 8.0)).unwrap();
+        assert_eq!(read.values(), &[0.0, 6.0]);
+
+        let header = AsciiGridHeader {
+            x_corner: 100.0,
+            no_data_value: Some(-9999.0),
+            ..AsciiGridHeader::default()
+        };
+        let mut asc = Vec::new();
+        map.write_asc(&mut asc, &header).unwrap();
+        let (read, read_header) = NoiseMap::read_asc(&asc[..]).unwrap();
+        assert_eq!((read, read_header), (map, header));
+
+        let centered = "NCOLS 1\nNROWS 1\nXLLCENTER 0.5\nYLLCENTER 2.5\nCELLSIZE 1\n7\n";
+        let (read, header) = NoiseMap::read_asc(centered.as_bytes()).unwrap();
+        assert_eq!(
+            (read.values(), header.x_corner, header.y_corner),
+            (&[7.0][..], 0.0, 2.0)
+        );
+    }
+
+    #[test]
+    fn test_invalid_sizes_are_rejected() {
+        use std::io::ErrorKind;
+
+        let pgm = |data: &[u8]| NoiseMap::read_pgm(data, (0.0, 1.0)).unwrap_err().kind();
+        let huge = format!("P5\n{} {}\n65535\n", usize::MAX, usize::MAX);
+        assert_eq!(pgm(b"P5\n100000 100000\n65535\n"), ErrorKind::InvalidData);
+        assert_eq!(pgm(huge.as_bytes()), ErrorKind::InvalidData);
+        assert_eq!(pgm(b"P2\n2 2\n4\n0 1 2\n"), ErrorKind::InvalidData);
+
+        let pfm = |data: &[u8]| NoiseMap::read_pfm(data).unwrap_err().kind();
+        assert_eq!(pfm(b"Pf\n0 3\n-1.0\n"), ErrorKind::InvalidData);
+        assert_eq!(pfm(b"Pf\n100000 100000\n-1.0\n"), ErrorKind::InvalidData);
+
+        let asc = NoiseMap::read_asc(&b"ncols 100000\nnrows 100000\n1 2 3\n"[..]);
+        assert_eq!(asc.unwrap_err().kind(), ErrorKind::InvalidData);
+
+        let raw16 =
+            |size| NoiseMap::read_raw16(&[0; 6][..], size, ByteOrder::BigEndian, (0.0, 1.0));
+        let error = |size| raw16(size).unwrap_err().kind();
+        assert_eq!(error((100_000, 100_000)), ErrorKind::InvalidData);
+        assert_eq!(error((usize::MAX, 2)), ErrorKind::InvalidData);
+        assert_eq!(raw16((3, 1)).unwrap().values(), &[0.0; 3]);
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright (c) 2017 The Noise-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. All files in the
+// project carrying such notice may not be copied, modified, or distributed
+// except according to those terms.
+
+//! PNG export of noise maps and images, behind the `image` feature.
+
+use image::png::PNGEncoder;
+use image::ColorType;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use utils::{NoiseImage, NoiseMap, Normalization};
+
+impl NoiseMap {
+    /// Writes the map as an 8-bit grayscale PNG, mapping the normalization
+    /// range to black through white.
+    pub fn write_png<W: Write>(&self, writer: W, normalization: Normalization) -> io::Result<()> {
+        let buffer: Vec<u8> = self
+            .normalized_values(normalization)
+            .iter()
+            .map(|&value| (value * 255.0).round() as u8)
+            .collect();
+
+        encode_png(writer, &buffer, self.size(), ColorType::Gray(8))
+    }
+
+    /// Writes the map as a 16-bit grayscale PNG, mapping the normalization
+    /// range to black through white. The extra precision avoids visible steps
+    /// when the PNG is used as a heightmap.
+    pub fn write_png16<W: Write>(&self, writer: W, normalization: Normalization) -> io::Result<()> {
+        let mut buffer = Vec::with_capacity(self.values().len() * 2);
+        for &value in &self.normalized_values(normalization) {
+            let value = (value * 65535.0).round() as u16;
+            buffer.push((value >> 8) as u8);
+            buffer.push(value as u8);
+        }
+
+        encode_png(writer, &buffer, self.size(), ColorType::Gray(16))
+    }
+
+    /// Saves the map to a file as an 8-bit grayscale PNG. See `write_png`.
+    pub fn save_png<P: AsRef<Path>>(
+        &self,
+        path: P,
+        normalization: Normalization,
+    ) -> io::Result<()> {
+        self.write_png(BufWriter::new(File::create(path)?), normalization)
+    }
+
+    /// Saves the map to a file as a 16-bit grayscale PNG. See `write_png16`.
+    pub fn save_png16<P: AsRef<Path>>(
+        &self,
+        path: P,
+        normalization: Normalization,
+    ) -> io::Result<()> {
+        self.write_png16(BufWriter::new(File::create(path)?), normalization)
+    }
+}
+
+impl NoiseImage {
+    /// Writes the image as an 8-bit RGBA PNG.
+    pub fn write_png<W: Write>(&self, writer: W) -> io::Result<()> {
+        encode_png(writer, &self.to_rgba(), self.size(), ColorType::RGBA(8))
+    }
+
+    /// Saves the image to a file as an 8-bit RGBA PNG.
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write_png(BufWriter::new(File::create(path)?))
+    }
+}
+
+fn encode_png<W: Write>(
+    writer: W,
+    buffer: &[u8],
+    (width, height): (usize, usize),
+    color: ColorType,
+) -> io::Result<()> {
+    PNGEncoder::new(writer).encode(buffer, width as u32, height as u32, color)
+}
+
+#[cfg(test)]
+mod tests {
+    use image;
+    use utils::*;
+
+    #[test]
+    fn test_png_export() {
+        let mut map = NoiseMap::new(3, 2);
+        map.set_value(0, 0, -1.0);
+        map.set_value(1, 0, 0.5);
+        map.set_value(2, 1, 3.0);
+
+        let mut png = Vec::new();
+        map.write_png(&mut png, Normalization::Fixed).unwrap();
+        let decoded = image::load_from_memory(&png).unwrap().to_luma();
+        assert_eq!(decoded.into_raw(), vec![0, 191, 128, 128, 128, 255]);
+
+        let mut png = Vec::new();
+        map.write_png(&mut png, Normalization::Auto).unwrap();
+        let decoded = image::load_from_memory(&png).unwrap().to_luma();
+        assert_eq!(decoded.into_raw(), vec![0, 96, 64, 64, 64, 255]);
+
+        // The IHDR chunk records a bit depth of 16 and the grayscale color type.
+        let mut png = Vec::new();
+        map.write_png16(&mut png, Normalization::Range(0.0, 2.0))
+            .unwrap();
+        assert_eq!((png[24], png[25]), (16, 0));
+
+        let image = ImageRenderer::new().render(&map);
+        let mut png = Vec::new();
+        image.write_png(&mut png).unwrap();
+        let decoded = image::load_from_memory(&png).unwrap().to_rgba();
+        assert_eq!(decoded.into_raw(), image.to_rgba());
+    }
+}
@@ -0,0 +1,258 @@
+// Copyright (c) 2017 The Noise-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. All files in the
+// project carrying such notice may not be copied, modified, or distributed
+// except according to those terms.
+
+use math;
+use std::f64::consts::SQRT_2;
+use utils::{Color, ColorGradient, NoiseImage, NoiseMap};
+
+/// Renders a `NoiseMap` to a `NoiseImage` by mapping each value to a color
+/// with a `ColorGradient`.
+///
+/// The colors can be lit by a distant light source, which shades the map as if
+/// its values were heights, and composited over a background image.
+///
+/// Directions on the map follow the image: east is towards the last column and
+/// north is towards the first row.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ImageRenderer {
+    /// Maps the values of the noise map to colors. Default is a grayscale
+    /// gradient from black at -1.0 to white at 1.0.
+    pub gradient: ColorGradient,
+
+    /// Determines if the image is lit by the light source.
+    pub light_enabled: bool,
+
+    /// Determines if the left and right edges, and the top and bottom edges,
+    /// of the noise map are treated as neighbours when lighting it. Enable it
+    /// for maps that tile.
+    pub wrap_enabled: bool,
+
+    /// Direction the light comes from, in degrees counterclockwise from east.
+    /// Default is 45.0.
+    pub light_azimuth: f64,
+
+    /// Angle of the light above the horizon, in degrees. Default is 45.0.
+    pub light_elevation: f64,
+
+    /// How strongly the slopes of the noise map are shaded. Default is 1.0.
+    pub light_contrast: f64,
+
+    /// Multiplier applied to the light. Default is 1.0.
+    pub light_brightness: f64,
+
+    /// Color of the light. Default is white.
+    pub light_color: Color,
+}
+
+impl ImageRenderer {
+    pub fn new() -> ImageRenderer {
+        ImageRenderer {
+            gradient: ColorGradient::grayscale(),
+            light_enabled: false,
+            wrap_enabled: false,
+            light_azimuth: 45.0,
+            light_elevation: 45.0,
+            light_contrast: 1.0,
+            light_brightness: 1.0,
+            light_color: [255; 4],
+        }
+    }
+
+    pub fn set_gradient(self, gradient: ColorGradient) -> ImageRenderer {
+        ImageRenderer {
+            gradient: gradient,
+            ..self
+        }
+    }
+
+    /// Enables or disables lighting the image.
+    pub fn enable_light(self, light_enabled: bool) -> ImageRenderer {
+        ImageRenderer {
+            light_enabled: light_enabled,
+            ..self
+        }
+    }
+
+    /// Enables or disables wrapping around the edges of the noise map when
+    /// lighting it.
+    pub fn enable_wrap(self, wrap_enabled: bool) -> ImageRenderer {
+        ImageRenderer {
+            wrap_enabled: wrap_enabled,
+            ..self
+        }
+    }
+
+    pub fn set_light_azimuth(self, light_azimuth: f64) -> ImageRenderer {
+        ImageRenderer {
+            light_azimuth: light_azimuth,
+            ..self
+        }
+    }
+
+    pub fn set_light_elevation(self, light_elevation: f64) -> ImageRenderer {
+        ImageRenderer {
+            light_elevation: light_elevation,
+            ..self
+        }
+    }
+
+    pub fn set_light_contrast(self, light_contrast: f64) -> ImageRenderer {
+        ImageRenderer {
+            light_contrast: light_contrast,
+            ..self
+        }
+    }
+
+    pub fn set_light_brightness(self, light_brightness: f64) -> ImageRenderer {
+        ImageRenderer {
+            light_brightness: light_brightness,
+            ..self
+        }
+    }
+
+    pub fn set_light_color(self, light_color: Color) -> ImageRenderer {
+        ImageRenderer {
+            light_color: light_color,
+            ..self
+        }
+    }
+
+    /// Renders the noise map over an opaque white background.
+    pub fn render(&self, map: &NoiseMap) -> NoiseImage {
+        self.render_over(map, None)
+    }
+
+    /// Renders the noise map over the background image. The colors of the
+    /// gradient are blended with the background by their alpha. Points outside
+    /// of the background use its border color.
+    pub fn render_with_background(&self, map: &NoiseMap, background: &NoiseImage) -> NoiseImage {
+        self.render_over(map, Some(background))
+    }
+
+    fn render_over(&self, map: &NoiseMap, background: Option<&NoiseImage>) -> NoiseImage {
+        let (width, height) = map.size();
+        let mut image = NoiseImage::new(width, height);
+
+        // Weights of the horizontal, vertical and flat parts of the light.
+        let azimuth = self.light_azimuth.to_radians();
+        let elevation = self.light_elevation.to_radians();
+        let flat = SQRT_2 * elevation.sin() / 2.0;
+        let slope = (1.0 - flat) * self.light_contrast * SQRT_2 * elevation.cos();
+        let (east, north) = (slope * azimuth.cos(), slope * azimuth.sin());
+
+        for y in 0..height {
+            let (north_y, south_y) = super::neighbours(y, height, self.wrap_enabled);
+
+            for x in 0..width {
+                let color = self.gradient.get_color(map.get_value(x, y));
+
+                let intensity = if self.light_enabled {
+                    let (west_x, east_x) = super::neighbours(x, width, self.wrap_enabled);
+                    let east_slope = map.get_value(west_x, y) - map.get_value(east_x, y);
+                    let north_slope = map.get_value(x, south_y) - map.get_value(x, north_y);
+
+                    let intensity = east * east_slope + north * north_slope + flat;
+                    intensity.max(0.0) * self.light_brightness
+                } else {
+                    1.0
+                };
+
+                let background = match background {
+                    Some(background) => background.get_value(x, y),
+                    None => [255; 4],
+                };
+
+                image.set_value(x, y, self.blend(color, background, intensity));
+            }
+        }
+
+        image
+    }
+
+    /// Composites a color over the background and applies the light.
+    fn blend(&self, color: Color, background: Color, intensity: f64) -> Color {
+        let alpha = f64::from(color[3]) / 255.0;
+
+        let mut blended = [0; 4];
+        for channel in 0..3 {
+            let mut value = math::interp::linear(
+                f64::from(background[channel]) / 255.0,
+                f64::from(color[channel]) / 255.0,
+                alpha,
+            );
+
+            if self.light_enabled {
+                value *= intensity * f64::from(self.light_color[channel]) / 255.0;
+            }
+
+            blended[channel] = (math::clamp(value, 0.0, 1.0) * 255.0).round() as u8;
+        }
+        blended[3] = color[3].max(background[3]);
+
+        blended
+    }
+}
+
+impl Default for ImageRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use utils::*;
+
+    #[test]
+    fn test_gradient_and_lighting() {
+        let gradient = ColorGradient::new()
+            .add_gradient_point(1.0, [255, 0, 0, 255])
+            .add_gradient_point(-1.0, [0, 0, 255, 255])
+            .add_gradient_point(1.0, [255, 255, 255, 255]);
+        assert_eq!(gradient.get_color(-2.0), [0, 0, 255, 255]);
+        assert_eq!(gradient.get_color(0.0), [128, 128, 255, 255]);
+        assert_eq!(gradient.get_color(3.0), [255, 255, 255, 255]);
+
+        // A ramp rising towards the east, which is unlit from the west.
+        let mut map = NoiseMap::new(4, 3);
+        for y in 0..3 {
+            for x in 0..4 {
+                map.set_value(x, y, x as f64 * 0.5 - 1.0);
+            }
+        }
+
+        let image = ImageRenderer::new().render(&map);
+        assert_eq!(image.get_value(0, 0), [0, 0, 0, 255]);
+        assert_eq!(image.get_value(3, 2), [191, 191, 191, 255]);
+
+        let lit = ImageRenderer::new()
+            .enable_light(true)
+            .set_light_azimuth(0.0)
+            .set_light_elevation(0.0);
+        assert_eq!(lit.render(&map).get_value(1, 1), [0, 0, 0, 255]);
+
+        // The east edge only sees the drop back to the west edge when wrapped.
+        let lit = lit
+            .set_light_azimuth(180.0)
+            .set_gradient(ColorGradient::new());
+        let clamped = lit.render(&map);
+        let wrapped = lit.clone().enable_wrap(true).render(&map);
+        assert_eq!(clamped.get_value(3, 1), [180, 180, 180, 255]);
+        assert_eq!(wrapped.get_value(3, 1), [0, 0, 0, 255]);
+
+        // A transparent gradient shows the background.
+        let mut background = NoiseImage::new(4, 3);
+        background.set_value(2, 2, [10, 20, 30, 40]);
+        let image = lit
+            .enable_light(false)
+            .render_with_background(&map, &background);
+        assert_eq!(image.get_value(2, 2), [10, 20, 30, 40]);
+        assert_eq!(image.to_rgba()[..4], [0, 0, 0, 0]);
+    }
+}
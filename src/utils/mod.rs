@@ -0,0 +1,47 @@
+// Copyright (c) 2017 The Noise-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. All files in the
+// project carrying such notice may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Utilities for sampling noise functions into maps and rendering them to
+//! images.
+
+pub use self::color_gradient::*;
+pub use self::heightmap_formats::*;
+pub use self::image_renderer::*;
+pub use self::noise_image::*;
+pub use self::noise_map::*;
+pub use self::noise_map_builder::*;
+pub use self::normal_map_renderer::*;
+
+mod color_gradient;
+mod heightmap_formats;
+#[cfg(feature = "image")]
+mod image_export;
+mod image_renderer;
+mod noise_image;
+mod noise_map;
+mod noise_map_builder;
+mod normal_map_renderer;
+
+/// Returns the previous and next indices along an axis of the given size. At
+/// the edges, these wrap around to the opposite edge, or stay at the edge.
+fn neighbours(index: usize, size: usize, wrap: bool) -> (usize, usize) {
+    let previous = match index {
+        0 if wrap => size - 1,
+        0 => 0,
+        _ => index - 1,
+    };
+    let next = if index + 1 < size {
+        index + 1
+    } else if wrap {
+        0
+    } else {
+        index
+    };
+
+    (previous, next)
+}
@@ -0,0 +1,91 @@
+// Copyright (c) 2017 The Noise-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. All files in the
+// project carrying such notice may not be copied, modified, or distributed
+// except according to those terms.
+
+use utils::Color;
+
+/// A two-dimensional grid of RGBA colors, stored row by row.
+///
+/// Noise images are usually rendered from a `NoiseMap` by an `ImageRenderer`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NoiseImage {
+    width: usize,
+    height: usize,
+
+    /// Color returned by `get_value` for points outside of the image. Default
+    /// is transparent black.
+    pub border_color: Color,
+
+    pixels: Vec<Color>,
+}
+
+impl NoiseImage {
+    /// Creates an image of the given size with every pixel transparent black.
+    pub fn new(width: usize, height: usize) -> NoiseImage {
+        NoiseImage {
+            width: width,
+            height: height,
+            border_color: [0; 4],
+            pixels: vec![[0; 4]; width * height],
+        }
+    }
+
+    pub fn set_border_color(self, border_color: Color) -> NoiseImage {
+        NoiseImage {
+            border_color: border_color,
+            ..self
+        }
+    }
+
+    /// Returns the width and height of the image.
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Returns the color at the given point, or the border color if the point
+    /// is outside of the image.
+    pub fn get_value(&self, x: usize, y: usize) -> Color {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x]
+        } else {
+            self.border_color
+        }
+    }
+
+    /// Sets the color at the given point. Points outside of the image are
+    /// ignored.
+    pub fn set_value(&mut self, x: usize, y: usize, color: Color) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = color;
+        }
+    }
+
+    /// Returns the pixels of the image, row by row.
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    /// Returns the pixels of the image as a buffer of RGB bytes, row by row,
+    /// dropping the alpha channel.
+    pub fn to_rgb(&self) -> Vec<u8> {
+        let mut buffer = Vec::with_capacity(self.pixels.len() * 3);
+        for pixel in &self.pixels {
+            buffer.extend_from_slice(&pixel[..3]);
+        }
+        buffer
+    }
+
+    /// Returns the pixels of the image as a buffer of RGBA bytes, row by row.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        let mut buffer = Vec::with_capacity(self.pixels.len() * 4);
+        for pixel in &self.pixels {
+            buffer.extend_from_slice(pixel);
+        }
+        buffer
+    }
+}
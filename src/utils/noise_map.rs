@@ -0,0 +1,212 @@
+// Copyright (c) 2017 The Noise-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. All files in the
+// project carrying such notice may not be copied, modified, or distributed
+// except according to those terms.
+
+use math;
+#[cfg(feature = "serde")]
+use std::convert::TryFrom;
+
+/// How the values of a `NoiseMap` are mapped onto the range of an output
+/// format, such as the 0 to 255 of an 8-bit image.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Normalization {
+    /// Maps -1.0 to 1.0, the usual range of noise functions.
+    Fixed,
+
+    /// Maps the lowest to the highest value in the map.
+    Auto,
+
+    /// Maps the given lower to the given upper value.
+    Range(f64, f64),
+}
+
+impl Normalization {
+    /// Returns the values of the map that are mapped to the bottom and the top
+    /// of the output range.
+    pub fn bounds(&self, map: &NoiseMap) -> (f64, f64) {
+        match *self {
+            Normalization::Fixed => (-1.0, 1.0),
+            Normalization::Auto => map.bounds().unwrap_or((0.0, 0.0)),
+            Normalization::Range(lower, upper) => (lower, upper),
+        }
+    }
+}
+
+/// A two-dimensional grid of noise values, stored row by row.
+///
+/// Noise maps are usually filled by one of the `NoiseMapBuilder`s.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "NoiseMapConfig"))]
+pub struct NoiseMap {
+    width: usize,
+    height: usize,
+
+    /// Value returned by `get_value` for points outside of the map. Default is
+    /// 0.0.
+    pub border_value: f64,
+
+    values: Vec<f64>,
+}
+
+/// Serialized form of `NoiseMap`, which is only accepted if it holds a value
+/// for every point of the map.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct NoiseMapConfig {
+    width: usize,
+    height: usize,
+    border_value: f64,
+    values: Vec<f64>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<NoiseMapConfig> for NoiseMap {
+    type Error = String;
+
+    fn try_from(config: NoiseMapConfig) -> Result<NoiseMap, String> {
+        match config.width.checked_mul(config.height) {
+            Some(length) if length == config.values.len() => Ok(NoiseMap {
+                width: config.width,
+                height: config.height,
+                border_value: config.border_value,
+                values: config.values,
+            }),
+            Some(length) => Err(format!(
+                "expected {} values for a {}x{} map, found {}",
+                length,
+                config.width,
+                config.height,
+                config.values.len()
+            )),
+            None => Err("the map is too large".to_owned()),
+        }
+    }
+}
+
+impl NoiseMap {
+    /// Creates a map of the given size with every value set to 0.0.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width * height` overflows.
+    pub fn new(width: usize, height: usize) -> NoiseMap {
+        let length = width
+            .checked_mul(height)
+            .unwrap_or_else(|| panic!("a {}x{} map is too large", width, height));
+
+        NoiseMap {
+            width: width,
+            height: height,
+            border_value: 0.0,
+            values: vec![0.0; length],
+        }
+    }
+
+    pub fn set_border_value(self, border_value: f64) -> NoiseMap {
+        NoiseMap {
+            border_value: border_value,
+            ..self
+        }
+    }
+
+    /// Returns the width and height of the map.
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Returns the value at the given point, or the border value if the point
+    /// is outside of the map.
+    pub fn get_value(&self, x: usize, y: usize) -> f64 {
+        if x < self.width && y < self.height {
+            self.values[y * self.width + x]
+        } else {
+            self.border_value
+        }
+    }
+
+    /// Sets the value at the given point. Points outside of the map are
+    /// ignored.
+    pub fn set_value(&mut self, x: usize, y: usize, value: f64) {
+        if x < self.width && y < self.height {
+            self.values[y * self.width + x] = value;
+        }
+    }
+
+    /// Returns the values of the map, row by row.
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Returns the values of the map, row by row, for modification.
+    pub fn values_mut(&mut self) -> &mut [f64] {
+        &mut self.values
+    }
+
+    /// Returns the values of the map, row by row, mapped to the range 0.0 to
+    /// 1.0 and clamped to it. If the normalization range is empty, every value
+    /// is mapped to 0.0.
+    pub fn normalized_values(&self, normalization: Normalization) -> Vec<f64> {
+        let (lower, upper) = normalization.bounds(self);
+        let extent = upper - lower;
+
+        self.values
+            .iter()
+            .map(|&value| {
+                if extent == 0.0 {
+                    0.0
+                } else {
+                    math::clamp((value - lower) / extent, 0.0, 1.0)
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the lowest and highest values in the map, or `None` if the map
+    /// is empty.
+    pub fn bounds(&self) -> Option<(f64, f64)> {
+        if self.values.is_empty() {
+            return None;
+        }
+
+        Some(self.values.iter().fold(
+            (f64::INFINITY, f64::NEG_INFINITY),
+            |(min, max), &value| (min.min(value), max.max(value)),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use utils::NoiseMap;
+
+    #[test]
+    #[should_panic(expected = "map is too large")]
+    fn test_oversized_map_panics() {
+        NoiseMap::new(usize::MAX, 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_checks_the_number_of_values() {
+        use serde_json;
+
+        let mut map = NoiseMap::new(3, 2).set_border_value(0.5);
+        map.set_value(2, 1, 0.25);
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(serde_json::from_str::<NoiseMap>(&json).unwrap(), map);
+
+        let empty = r#"{"width":4,"height":4,"border_value":0.0,"values":[]}"#;
+        assert!(serde_json::from_str::<NoiseMap>(empty).is_err());
+        let huge = format!(
+            r#"{{"width":{},"height":2,"border_value":0.0,"values":[]}}"#,
+            usize::MAX
+        );
+        assert!(serde_json::from_str::<NoiseMap>(&huge).is_err());
+    }
+}
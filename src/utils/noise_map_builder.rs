@@ -0,0 +1,387 @@
+// Copyright (c) 2017 The Noise-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. All files in the
+// project carrying such notice may not be copied, modified, or distributed
+// except according to those terms.
+
+use math::{Point3, interp};
+use noise_fns::NoiseFn;
+use utils::NoiseMap;
+
+/// Default width and height of the maps built by a `NoiseMapBuilder`.
+pub const DEFAULT_NOISE_MAP_SIZE: usize = 100;
+
+/// Trait for types that build a `NoiseMap` by sampling a noise function over
+/// some surface.
+pub trait NoiseMapBuilder {
+    /// Sets the width and height of the maps to build.
+    fn set_size(self, width: usize, height: usize) -> Self;
+
+    /// Returns the width and height of the maps to build.
+    fn size(&self) -> (usize, usize);
+
+    /// Samples the source function into a new `NoiseMap`.
+    fn build(&self) -> NoiseMap;
+}
+
+/// Builds a noise map from the values of a source function on a rectangle of
+/// the plane `z = 0`.
+///
+/// Column `x` of the map is sampled at `lower_x_bound + x * (upper_x_bound -
+/// lower_x_bound) / width`, and likewise for rows along the y axis.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PlaneMapBuilder<Source> {
+    /// Outputs the values to sample.
+    pub source: Source,
+
+    pub width: usize,
+    pub height: usize,
+
+    /// Bounds of the rectangle along the x axis. Default is -1.0 to 1.0.
+    pub lower_x_bound: f64,
+    pub upper_x_bound: f64,
+
+    /// Bounds of the rectangle along the y axis. Default is -1.0 to 1.0.
+    pub lower_y_bound: f64,
+    pub upper_y_bound: f64,
+
+    /// Blends the values near each edge of the rectangle with the values past
+    /// the opposite edge, so that the map tiles seamlessly. Default is false.
+    pub seamless: bool,
+}
+
+impl<Source> PlaneMapBuilder<Source> {
+    pub fn new(source: Source) -> PlaneMapBuilder<Source> {
+        PlaneMapBuilder {
+            source: source,
+            width: DEFAULT_NOISE_MAP_SIZE,
+            height: DEFAULT_NOISE_MAP_SIZE,
+            lower_x_bound: -1.0,
+            upper_x_bound: 1.0,
+            lower_y_bound: -1.0,
+            upper_y_bound: 1.0,
+            seamless: false,
+        }
+    }
+
+    pub fn set_x_bounds(self, lower_x_bound: f64, upper_x_bound: f64) -> PlaneMapBuilder<Source> {
+        PlaneMapBuilder {
+            lower_x_bound: lower_x_bound,
+            upper_x_bound: upper_x_bound,
+            ..self
+        }
+    }
+
+    pub fn set_y_bounds(self, lower_y_bound: f64, upper_y_bound: f64) -> PlaneMapBuilder<Source> {
+        PlaneMapBuilder {
+            lower_y_bound: lower_y_bound,
+            upper_y_bound: upper_y_bound,
+            ..self
+        }
+    }
+
+    /// Enables or disables seamless tiling of the map.
+    pub fn set_seamless(self, seamless: bool) -> PlaneMapBuilder<Source> {
+        PlaneMapBuilder {
+            seamless: seamless,
+            ..self
+        }
+    }
+}
+
+impl<Source> NoiseMapBuilder for PlaneMapBuilder<Source>
+where
+    Source: NoiseFn<Point3<f64>>,
+{
+    fn set_size(self, width: usize, height: usize) -> PlaneMapBuilder<Source> {
+        PlaneMapBuilder {
+            width: width,
+            height: height,
+            ..self
+        }
+    }
+
+    fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn build(&self) -> NoiseMap {
+        let mut map = NoiseMap::new(self.width, self.height);
+
+        let x_extent = self.upper_x_bound - self.lower_x_bound;
+        let y_extent = self.upper_y_bound - self.lower_y_bound;
+        let x_step = x_extent / self.width as f64;
+        let y_step = y_extent / self.height as f64;
+
+        for y in 0..self.height {
+            let current_y = self.lower_y_bound + y as f64 * y_step;
+
+            for x in 0..self.width {
+                let current_x = self.lower_x_bound + x as f64 * x_step;
+
+                let value = if self.seamless {
+                    // Blend towards the values one extent away, so that each
+                    // edge matches the one opposite it.
+                    let sw = self.source.get([current_x, current_y, 0.0]);
+                    let se = self.source.get([current_x + x_extent, current_y, 0.0]);
+                    let nw = self.source.get([current_x, current_y + y_extent, 0.0]);
+                    let ne = self.source
+                        .get([current_x + x_extent, current_y + y_extent, 0.0]);
+
+                    let x_blend = 1.0 - (current_x - self.lower_x_bound) / x_extent;
+                    let y_blend = 1.0 - (current_y - self.lower_y_bound) / y_extent;
+
+                    let south = interp::linear(sw, se, x_blend);
+                    let north = interp::linear(nw, ne, x_blend);
+                    interp::linear(south, north, y_blend)
+                } else {
+                    self.source.get([current_x, current_y, 0.0])
+                };
+
+                map.set_value(x, y, value);
+            }
+        }
+
+        map
+    }
+}
+
+/// Builds a noise map from the values of a source function on the surface of
+/// a unit sphere centered on the origin.
+///
+/// The map spans a range of latitudes, from south to north, and longitudes,
+/// from west to east, in degrees. The point at latitude `lat` and longitude
+/// `lon` is `[cos(lat) * cos(lon), sin(lat), cos(lat) * sin(lon)]`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SphereMapBuilder<Source> {
+    /// Outputs the values to sample.
+    pub source: Source,
+
+    pub width: usize,
+    pub height: usize,
+
+    /// Latitude bounds of the map, in degrees. Default is -90.0 to 90.0.
+    pub south_lat_bound: f64,
+    pub north_lat_bound: f64,
+
+    /// Longitude bounds of the map, in degrees. Default is -180.0 to 180.0.
+    pub west_lon_bound: f64,
+    pub east_lon_bound: f64,
+}
+
+impl<Source> SphereMapBuilder<Source> {
+    pub fn new(source: Source) -> SphereMapBuilder<Source> {
+        SphereMapBuilder {
+            source: source,
+            width: DEFAULT_NOISE_MAP_SIZE,
+            height: DEFAULT_NOISE_MAP_SIZE,
+            south_lat_bound: -90.0,
+            north_lat_bound: 90.0,
+            west_lon_bound: -180.0,
+            east_lon_bound: 180.0,
+        }
+    }
+
+    pub fn set_latitude_bounds(
+        self,
+        south_lat_bound: f64,
+        north_lat_bound: f64,
+    ) -> SphereMapBuilder<Source> {
+        SphereMapBuilder {
+            south_lat_bound: south_lat_bound,
+            north_lat_bound: north_lat_bound,
+            ..self
+        }
+    }
+
+    pub fn set_longitude_bounds(
+        self,
+        west_lon_bound: f64,
+        east_lon_bound: f64,
+    ) -> SphereMapBuilder<Source> {
+        SphereMapBuilder {
+            west_lon_bound: west_lon_bound,
+            east_lon_bound: east_lon_bound,
+            ..self
+        }
+    }
+}
+
+impl<Source> NoiseMapBuilder for SphereMapBuilder<Source>
+where
+    Source: NoiseFn<Point3<f64>>,
+{
+    fn set_size(self, width: usize, height: usize) -> SphereMapBuilder<Source> {
+        SphereMapBuilder {
+            width: width,
+            height: height,
+            ..self
+        }
+    }
+
+    fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn build(&self) -> NoiseMap {
+        let mut map = NoiseMap::new(self.width, self.height);
+
+        let lon_step = (self.east_lon_bound - self.west_lon_bound) / self.width as f64;
+        let lat_step = (self.north_lat_bound - self.south_lat_bound) / self.height as f64;
+
+        for y in 0..self.height {
+            let lat = (self.south_lat_bound + y as f64 * lat_step).to_radians();
+
+            for x in 0..self.width {
+                let lon = (self.west_lon_bound + x as f64 * lon_step).to_radians();
+
+                let point = [lat.cos() * lon.cos(), lat.sin(), lat.cos() * lon.sin()];
+                map.set_value(x, y, self.source.get(point));
+            }
+        }
+
+        map
+    }
+}
+
+/// Builds a noise map from the values of a source function on the surface of
+/// a unit cylinder around the y axis.
+///
+/// The map spans a range of angles around the cylinder, in degrees, and a
+/// range of heights along it. The point at angle `a` and height `h` is
+/// `[cos(a), h, sin(a)]`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CylinderMapBuilder<Source> {
+    /// Outputs the values to sample.
+    pub source: Source,
+
+    pub width: usize,
+    pub height: usize,
+
+    /// Angle bounds of the map, in degrees. Default is -180.0 to 180.0.
+    pub lower_angle_bound: f64,
+    pub upper_angle_bound: f64,
+
+    /// Height bounds of the map. Default is -1.0 to 1.0.
+    pub lower_height_bound: f64,
+    pub upper_height_bound: f64,
+}
+
+impl<Source> CylinderMapBuilder<Source> {
+    pub fn new(source: Source) -> CylinderMapBuilder<Source> {
+        CylinderMapBuilder {
+            source: source,
+            width: DEFAULT_NOISE_MAP_SIZE,
+            height: DEFAULT_NOISE_MAP_SIZE,
+            lower_angle_bound: -180.0,
+            upper_angle_bound: 180.0,
+            lower_height_bound: -1.0,
+            upper_height_bound: 1.0,
+        }
+    }
+
+    pub fn set_angle_bounds(
+        self,
+        lower_angle_bound: f64,
+        upper_angle_bound: f64,
+    ) -> CylinderMapBuilder<Source> {
+        CylinderMapBuilder {
+            lower_angle_bound: lower_angle_bound,
+            upper_angle_bound: upper_angle_bound,
+            ..self
+        }
+    }
+
+    pub fn set_height_bounds(
+        self,
+        lower_height_bound: f64,
+        upper_height_bound: f64,
+    ) -> CylinderMapBuilder<Source> {
+        CylinderMapBuilder {
+            lower_height_bound: lower_height_bound,
+            upper_height_bound: upper_height_bound,
+            ..self
+        }
+    }
+}
+
+impl<Source> NoiseMapBuilder for CylinderMapBuilder<Source>
+where
+    Source: NoiseFn<Point3<f64>>,
+{
+    fn set_size(self, width: usize, height: usize) -> CylinderMapBuilder<Source> {
+        CylinderMapBuilder {
+            width: width,
+            height: height,
+            ..self
+        }
+    }
+
+    fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn build(&self) -> NoiseMap {
+        let mut map = NoiseMap::new(self.width, self.height);
+
+        let angle_step = (self.upper_angle_bound - self.lower_angle_bound) / self.width as f64;
+        let height_step = (self.upper_height_bound - self.lower_height_bound) / self.height as f64;
+
+        for y in 0..self.height {
+            let height = self.lower_height_bound + y as f64 * height_step;
+
+            for x in 0..self.width {
+                let angle = (self.lower_angle_bound + x as f64 * angle_step).to_radians();
+
+                let point = [angle.cos(), height, angle.sin()];
+                map.set_value(x, y, self.source.get(point));
+            }
+        }
+
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {NoiseFn, Perlin};
+    use super::*;
+
+    #[test]
+    fn test_builders_sample_their_surfaces() {
+        let perlin = Perlin::new();
+
+        let plane = PlaneMapBuilder::new(&perlin)
+            .set_x_bounds(0.0, 4.0)
+            .set_y_bounds(-2.0, 2.0)
+            .set_size(8, 4)
+            .build();
+        assert_eq!(plane.size(), (8, 4));
+        assert_eq!(plane.get_value(3, 1), perlin.get([1.5, -1.0, 0.0]));
+        assert_eq!(plane.get_value(8, 0), plane.border_value);
+
+        // A seamless map starts with the values one extent past its origin, so
+        // it continues where its last row and column leave off.
+        let seamless = PlaneMapBuilder::new(&perlin)
+            .set_x_bounds(0.0, 4.0)
+            .set_y_bounds(-2.0, 2.0)
+            .set_seamless(true)
+            .build();
+        assert!((seamless.get_value(0, 0) - perlin.get([4.0, 2.0, 0.0])).abs() < 1e-12);
+
+        let sphere = SphereMapBuilder::new(&perlin).set_size(4, 2).build();
+        let north_east = perlin.get([0.0, 0.0, 1.0]);
+        assert!((sphere.get_value(3, 1) - north_east).abs() < 1e-12);
+
+        let cylinder = CylinderMapBuilder::new(&perlin)
+            .set_angle_bounds(0.0, 360.0)
+            .set_size(4, 2)
+            .build();
+        assert!((cylinder.get_value(1, 1) - perlin.get([0.0, 0.0, 1.0])).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,245 @@
+// Copyright (c) 2017 The Noise-rs Developers.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT
+// or http://opensource.org/licenses/MIT>, at your option. All files in the
+// project carrying such notice may not be copied, modified, or distributed
+// except according to those terms.
+
+use math::{self, Point2};
+use noise_fns::NoiseFn;
+use utils::{Color, NoiseImage, NoiseMap};
+
+/// Direction of the green channel of a normal map.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NormalMapConvention {
+    /// Green points up the image, as expected by OpenGL.
+    YUp,
+
+    /// Green points down the image, as expected by DirectX.
+    YDown,
+}
+
+/// Renders tangent-space normal maps from heightmaps.
+///
+/// The values of the heightmap are treated as heights, and the slope at each
+/// pixel is measured between its neighbours. Each component of the unit normal
+/// is stored in the red, green and blue channels, mapping -1.0 to 0 and 1.0 to
+/// 255, so a flat heightmap gives `[128, 128, 255]`. The alpha channel is
+/// always opaque.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NormalMapRenderer {
+    /// Height, in pixels, of a difference of 1.0 between values of the
+    /// heightmap. Default is 1.0.
+    pub bump_height: f64,
+
+    /// Direction of the green channel. Default is `NormalMapConvention::YUp`.
+    pub convention: NormalMapConvention,
+
+    /// Determines if the left and right edges, and the top and bottom edges,
+    /// of the noise map are treated as neighbours. Enable it for maps that
+    /// tile.
+    pub wrap_enabled: bool,
+}
+
+impl NormalMapRenderer {
+    pub fn new() -> NormalMapRenderer {
+        NormalMapRenderer {
+            bump_height: 1.0,
+            convention: NormalMapConvention::YUp,
+            wrap_enabled: false,
+        }
+    }
+
+    pub fn set_bump_height(self, bump_height: f64) -> NormalMapRenderer {
+        NormalMapRenderer {
+            bump_height: bump_height,
+            ..self
+        }
+    }
+
+    pub fn set_convention(self, convention: NormalMapConvention) -> NormalMapRenderer {
+        NormalMapRenderer {
+            convention: convention,
+            ..self
+        }
+    }
+
+    /// Enables or disables wrapping around the edges of the noise map.
+    pub fn enable_wrap(self, wrap_enabled: bool) -> NormalMapRenderer {
+        NormalMapRenderer {
+            wrap_enabled: wrap_enabled,
+            ..self
+        }
+    }
+
+    /// Renders the normals of a noise map. Without wrapping, the slopes at the
+    /// edges are measured between the edge and its one neighbour.
+    pub fn render(&self, map: &NoiseMap) -> NoiseImage {
+        let (width, height) = map.size();
+        let mut image = NoiseImage::new(width, height);
+
+        for y in 0..height {
+            let (north, south) = super::neighbours(y, height, self.wrap_enabled);
+
+            for x in 0..width {
+                let (west, east) = super::neighbours(x, width, self.wrap_enabled);
+
+                let x_slope = slope(
+                    map.get_value(west, y),
+                    map.get_value(east, y),
+                    distance(west, x, east),
+                );
+                let y_slope = slope(
+                    map.get_value(x, south),
+                    map.get_value(x, north),
+                    distance(north, y, south),
+                );
+
+                image.set_value(x, y, self.normal_color(x_slope, y_slope));
+            }
+        }
+
+        image
+    }
+
+    /// Samples the source function on a `width` by `height` grid spanning the
+    /// given bounds, like `NoiseMapBuilder`s do, and renders its normals.
+    ///
+    /// The samples just outside of the bounds are used for the slopes at the
+    /// edges, so a periodic function whose period matches the bounds gives a
+    /// normal map that tiles without enabling wrapping.
+    pub fn render_noise_fn<Source>(
+        &self,
+        source: &Source,
+        width: usize,
+        height: usize,
+        x_bounds: (f64, f64),
+        y_bounds: (f64, f64),
+    ) -> NoiseImage
+    where
+        Source: NoiseFn<Point2<f64>>,
+    {
+        let x_step = (x_bounds.1 - x_bounds.0) / width as f64;
+        let y_step = (y_bounds.1 - y_bounds.0) / height as f64;
+
+        // Sample one extra row and column on every side.
+        let mut map = NoiseMap::new(width + 2, height + 2);
+        for y in 0..height + 2 {
+            let current_y = y_bounds.0 + (y as f64 - 1.0) * y_step;
+
+            for x in 0..width + 2 {
+                let current_x = x_bounds.0 + (x as f64 - 1.0) * x_step;
+                map.set_value(x, y, source.get([current_x, current_y]));
+            }
+        }
+
+        let mut image = NoiseImage::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let x_slope = slope(map.get_value(x, y + 1), map.get_value(x + 2, y + 1), 2);
+                let y_slope = slope(map.get_value(x + 1, y + 2), map.get_value(x + 1, y), 2);
+
+                image.set_value(x, y, self.normal_color(x_slope, y_slope));
+            }
+        }
+
+        image
+    }
+
+    /// Encodes the normal of a surface with the given slopes, along the x axis
+    /// and up the image, as a color.
+    fn normal_color(&self, x_slope: f64, y_slope: f64) -> Color {
+        let normal = [
+            -x_slope * self.bump_height,
+            -y_slope * self.bump_height,
+            1.0,
+        ];
+        let length = math::dot3(normal, normal).sqrt();
+
+        let green_sign = match self.convention {
+            NormalMapConvention::YUp => 1.0,
+            NormalMapConvention::YDown => -1.0,
+        };
+
+        let encode = |component: f64| ((component / length + 1.0) * 127.5).round() as u8;
+        [
+            encode(normal[0]),
+            encode(green_sign * normal[1]),
+            encode(normal[2]),
+            255,
+        ]
+    }
+}
+
+impl Default for NormalMapRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Number of pixels between the neighbours of a pixel along one axis.
+fn distance(previous: usize, index: usize, next: usize) -> usize {
+    (previous != index) as usize + (next != index) as usize
+}
+
+/// Change in height per pixel between two neighbours.
+fn slope(previous: f64, next: f64, distance: usize) -> f64 {
+    if distance == 0 {
+        0.0
+    } else {
+        (next - previous) / distance as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {NoiseFn, Periodic, Perlin};
+    use utils::*;
+
+    #[test]
+    fn test_normal_maps() {
+        // A ramp rising towards the east at 0.5 per pixel.
+        let mut map = NoiseMap::new(4, 3);
+        for y in 0..3 {
+            for x in 0..4 {
+                map.set_value(x, y, x as f64 * 0.5);
+            }
+        }
+
+        let renderer = NormalMapRenderer::new().set_bump_height(2.0);
+        let image = renderer.render(&map);
+        let tilted = [37, 128, 218, 255];
+        assert_eq!(image.get_value(0, 0), tilted);
+        assert_eq!(image.get_value(2, 1), tilted);
+
+        let flat = NormalMapRenderer::new().render(&NoiseMap::new(2, 2));
+        assert_eq!(flat.get_value(1, 1), [128, 128, 255, 255]);
+
+        // Wrapping sees the drop from the east edge back to the west edge.
+        let wrapped = renderer.enable_wrap(true).render(&map);
+        assert!(wrapped.get_value(3, 0)[0] > 128);
+
+        // Sampling a periodic function tiles like a wrapped noise map, and the
+        // conventions only differ in the green channel.
+        let perlin = Perlin::new().set_period(Some(4));
+        let mut sampled = NoiseMap::new(8, 8);
+        for y in 0..8 {
+            for x in 0..8 {
+                sampled.set_value(x, y, perlin.get([x as f64 * 0.5, y as f64 * 0.5]));
+            }
+        }
+        let direct = renderer.render_noise_fn(&perlin, 8, 8, (0.0, 4.0), (0.0, 4.0));
+        assert_eq!(direct, renderer.enable_wrap(true).render(&sampled));
+
+        let y_down = renderer
+            .set_convention(NormalMapConvention::YDown)
+            .render_noise_fn(&perlin, 8, 8, (0.0, 4.0), (0.0, 4.0));
+        for (up, down) in direct.pixels().iter().zip(y_down.pixels()) {
+            assert_eq!((up[0], up[2]), (down[0], down[2]));
+            assert!((i32::from(up[1]) + i32::from(down[1]) - 255).abs() <= 1);
+        }
+    }
+}